@@ -0,0 +1,83 @@
+//! Compares creating frozen strings, symbols, and class lookups on every call
+//! with caching them per callsite with `cached!`.
+//!
+//! Run with `cargo bench --bench cached`.
+
+use std::time::Instant;
+
+use magnus::{cached, prelude::*, rb_sys::AsRawValue, Error, RClass, RString, Ruby};
+use rb_sys::VALUE;
+
+const ITERATIONS: usize = 1_000_000;
+
+// `func` returns a checksum of the results, which is printed so the calls
+// can't be optimised away.
+fn bench<F>(name: &str, func: F) -> Result<(), Error>
+where
+    F: FnOnce() -> Result<VALUE, Error>,
+{
+    let start = Instant::now();
+    let checksum = func()?;
+    let elapsed = start.elapsed();
+    println!(
+        "{:<14} {:>8.2} ns/iter (checksum {})",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        checksum
+    );
+    Ok(())
+}
+
+fn main() {
+    Ruby::init(|ruby| {
+        bench("str", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                let s = RString::new_frozen(ruby.str_new("example"));
+                sum = sum.wrapping_add(s.as_raw());
+            }
+            Ok(sum)
+        })?;
+        bench("cached str", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                sum = sum.wrapping_add(cached!(ruby, str "example").as_raw());
+            }
+            Ok(sum)
+        })?;
+        bench("sym", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                sum = sum.wrapping_add(ruby.sym_new("example").as_raw());
+            }
+            Ok(sum)
+        })?;
+        bench("cached sym", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                sum = sum.wrapping_add(cached!(ruby, sym "example").as_raw());
+            }
+            Ok(sum)
+        })?;
+        bench("class", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                let class: RClass = ruby
+                    .class_object()
+                    .funcall("const_get", ("Encoding::Converter",))?;
+                sum = sum.wrapping_add(class.as_raw());
+            }
+            Ok(sum)
+        })?;
+        bench("cached class", || {
+            let mut sum: VALUE = 0;
+            for _ in 0..ITERATIONS {
+                sum = sum.wrapping_add(cached!(ruby, class "Encoding::Converter").as_raw());
+            }
+            Ok(sum)
+        })?;
+
+        Ok(())
+    })
+    .unwrap()
+}
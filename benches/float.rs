@@ -0,0 +1,61 @@
+//! Compares creating Floats that can be stored as flonums with those that
+//! need a heap allocated `RFloat`, either because they are out of the flonum
+//! range, or because flonums aren't supported on the platform (e.g. 32 bit
+//! targets), and checking a Float can be converted to an `f32` without loss.
+//!
+//! Run with `cargo bench --bench float`.
+
+use std::time::Instant;
+
+use magnus::{Error, Ruby};
+
+const ITERATIONS: usize = 1_000_000;
+
+// `func` returns a checksum of the results, which is printed so the calls
+// can't be optimised away.
+fn bench<F>(name: &str, func: F) -> Result<(), Error>
+where
+    F: FnOnce() -> Result<f64, Error>,
+{
+    let start = Instant::now();
+    let checksum = func()?;
+    let elapsed = start.elapsed();
+    println!(
+        "{:<12} {:>8.2} ns/iter (checksum {})",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        checksum
+    );
+    Ok(())
+}
+
+fn main() {
+    Ruby::init(|ruby| {
+        bench("flonum", || {
+            let mut sum = 0.0;
+            for i in 0..ITERATIONS {
+                sum += ruby.float_from_f64(i as f64 + 0.5).to_f64();
+            }
+            Ok(sum)
+        })?;
+        // outside of the flonum range, so always heap allocated
+        bench("heap", || {
+            let mut sum = 0.0;
+            for i in 0..ITERATIONS {
+                sum += ruby.float_from_f64((i as f64 + 0.5) * 1e300).to_f64() * 1e-300;
+            }
+            Ok(sum)
+        })?;
+        bench("to_f32_exact", || {
+            let mut sum = 0.0;
+            for i in 0..ITERATIONS {
+                let f = ruby.float_from_f64(i as f64 + 0.5);
+                sum += f.to_f32_exact().map_or(0.0, f64::from);
+            }
+            Ok(sum)
+        })?;
+
+        Ok(())
+    })
+    .unwrap()
+}
@@ -0,0 +1,73 @@
+//! Compares looking up and setting String keys in a Hash with a newly
+//! allocated Ruby String per call, and with `RHash::get_str` and
+//! `RHash::aset_str`.
+//!
+//! Run with `cargo bench --bench hash_str`.
+
+use std::time::Instant;
+
+use magnus::{Error, RHash, Ruby};
+
+const ITERATIONS: usize = 1_000_000;
+
+const KEYS: [&str; 4] = ["host", "port", "user", "password"];
+
+// `func` returns a checksum of the results, which is printed so the calls
+// can't be optimised away.
+fn bench<F>(name: &str, func: F) -> Result<(), Error>
+where
+    F: FnOnce() -> Result<usize, Error>,
+{
+    let start = Instant::now();
+    let checksum = func()?;
+    let elapsed = start.elapsed();
+    println!(
+        "{:<10} {:>8.2} ns/iter (checksum {})",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        checksum
+    );
+    Ok(())
+}
+
+fn main() {
+    Ruby::init(|ruby| {
+        let hash: RHash = ruby.eval(
+            r#"{"host" => "localhost", "port" => 5432, "user" => "app", "password" => "secret"}"#,
+        )?;
+
+        bench("get", || {
+            let mut found = 0;
+            for i in 0..ITERATIONS {
+                if hash.get(ruby.str_new(KEYS[i % KEYS.len()])).is_some() {
+                    found += 1;
+                }
+            }
+            Ok(found)
+        })?;
+        bench("get_str", || {
+            let mut found = 0;
+            for i in 0..ITERATIONS {
+                if hash.get_str(KEYS[i % KEYS.len()]).is_some() {
+                    found += 1;
+                }
+            }
+            Ok(found)
+        })?;
+        bench("aset", || {
+            for i in 0..ITERATIONS {
+                hash.aset(ruby.str_new(KEYS[i % KEYS.len()]), i)?;
+            }
+            Ok(hash.len())
+        })?;
+        bench("aset_str", || {
+            for i in 0..ITERATIONS {
+                hash.aset_str(KEYS[i % KEYS.len()], i)?;
+            }
+            Ok(hash.len())
+        })?;
+
+        Ok(())
+    })
+    .unwrap()
+}
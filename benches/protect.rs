@@ -0,0 +1,62 @@
+//! Compares the overhead of the different ways of making a protected call.
+//!
+//! Run with `cargo bench --bench protect`.
+
+use std::time::Instant;
+
+use magnus::{
+    rb_sys::{protect, protect_batch, protect_fn, AsRawValue},
+    Error, Ruby,
+};
+use rb_sys::VALUE;
+
+const ITERATIONS: usize = 1_000_000;
+
+// `func` returns a checksum of the results, which is printed so the calls
+// can't be optimised away.
+fn bench<F>(name: &str, func: F) -> Result<(), Error>
+where
+    F: FnOnce() -> Result<VALUE, Error>,
+{
+    let start = Instant::now();
+    let checksum = func()?;
+    let elapsed = start.elapsed();
+    println!(
+        "{:<10} {:>8.2} ns/iter (checksum {})",
+        name,
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        checksum
+    );
+    Ok(())
+}
+
+fn main() {
+    Ruby::init(|ruby| {
+        // Fixnums, so there's no need to keep these alive for the GC
+        let values: Vec<VALUE> = (0..ITERATIONS as i64)
+            .map(|i| ruby.integer_from_i64(i).as_raw())
+            .collect();
+
+        bench("closure", || {
+            let mut sum: VALUE = 0;
+            for &v in &values {
+                sum = sum.wrapping_add(protect(|| unsafe { rb_sys::rb_to_int(v) })?);
+            }
+            Ok(sum)
+        })?;
+        bench("fn", || {
+            let mut sum: VALUE = 0;
+            for &v in &values {
+                sum = sum.wrapping_add(unsafe { protect_fn(rb_sys::rb_to_int, v) }?);
+            }
+            Ok(sum)
+        })?;
+        bench("batch", || {
+            let res = protect_batch(&values, |&v| unsafe { rb_sys::rb_to_int(v) })?;
+            Ok(res.into_iter().fold(0, VALUE::wrapping_add))
+        })?;
+
+        Ok(())
+    })
+    .unwrap()
+}
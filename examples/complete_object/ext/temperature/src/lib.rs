@@ -99,6 +99,11 @@ fn init(ruby: &Ruby) -> Result<(), Error> {
     class.define_method("to_celsius", method!(Temperature::to_celsius, 0))?;
     class.define_method("to_fahrenheit", method!(Temperature::to_fahrenheit, 0))?;
 
+    // register docs for Ruby tooling, see the `stubs` task in the Rakefile
+    class.document_method("to_kelvin", "Returns the temperature in Kelvin.")?;
+    class.document_method("to_celsius", "Returns the temperature in Celsius.")?;
+    class.document_method("to_fahrenheit", "Returns the temperature in Fahrenheit.")?;
+
     // make Ruby object cloneable, based on Rust Clone impl
     // #dup and #clone have slightly different behaviour in Ruby
     class.define_method("dup", method!(<Temperature as typed_data::Dup>::dup, 0))?;
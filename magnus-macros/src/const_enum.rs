@@ -0,0 +1,87 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, LitStr};
+
+use crate::util;
+
+pub fn expand_derive_const_enum(input: DeriveInput) -> Result<TokenStream, Error> {
+    let variants = match input.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "ConstEnum can only be derived for enums",
+            ))
+        }
+    };
+    if !input.generics.to_token_stream().is_empty() {
+        return Err(Error::new_spanned(
+            input.generics,
+            "ConstEnum can not be derived for types with generics",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            input.ident.span(),
+            "ConstEnum can not be derived for enums without variants",
+        ));
+    }
+
+    let mut names = Vec::new();
+    let mut idents = Vec::new();
+    for variant in variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.fields.span(),
+                "ConstEnum variants can not have fields",
+            ));
+        }
+        let mut rename = None;
+        if let Some(attrs) = util::get_magnus_attrubute(&variant.attrs)? {
+            attrs.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let lit = meta.value()?.parse::<LitStr>()?;
+                    if !lit.value().starts_with(|c: char| c.is_ascii_uppercase()) {
+                        return Err(Error::new(
+                            lit.span(),
+                            "constant name must start with an uppercase letter",
+                        ));
+                    }
+                    rename = Some(lit.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        let name =
+            rename.unwrap_or_else(|| util::snake_case(&variant.ident.to_string()).to_uppercase());
+        if names.contains(&name) {
+            return Err(Error::new(
+                variant.span(),
+                format!("duplicate constant `{}`", name),
+            ));
+        }
+        names.push(name);
+        idents.push(&variant.ident);
+    }
+
+    let ident = &input.ident;
+    let tokens = quote! {
+        impl magnus::module::ConstEnum for #ident {
+            const CONSTS: &'static [(&'static str, i64)] = &[
+                #((#names, #ident::#idents as i64),)*
+            ];
+
+            fn from_i64(val: i64) -> ::std::option::Option<Self> {
+                #(
+                    if val == #ident::#idents as i64 {
+                        return ::std::option::Option::Some(#ident::#idents);
+                    }
+                )*
+                ::std::option::Option::None
+            }
+        }
+    };
+    Ok(tokens)
+}
@@ -0,0 +1,106 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    ext::IdentExt, spanned::Spanned, Data, DataStruct, DeriveInput, Error, Fields, FieldsNamed,
+    LitStr, Type,
+};
+
+use crate::util;
+
+pub fn expand_derive_from_r_hash(input: DeriveInput) -> Result<TokenStream, Error> {
+    let fields = match input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(FieldsNamed { named, .. }),
+            ..
+        }) => named,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "FromRHash can only be derived for structs with named fields",
+            ))
+        }
+    };
+    if !input.generics.to_token_stream().is_empty() {
+        return Err(Error::new_spanned(
+            input.generics,
+            "FromRHash can not be derived for types with generics",
+        ));
+    }
+
+    let mut rename_all = None;
+    if let Some(attrs) = util::get_magnus_attrubute(&input.attrs)? {
+        attrs.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                rename_all = Some(util::RenameRule::parse(&meta.value()?.parse::<LitStr>()?)?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+    }
+
+    let mut names = Vec::new();
+    let mut inits = Vec::new();
+    for field in fields.iter() {
+        let mut rename = None;
+        let mut default = false;
+        if let Some(attrs) = util::get_magnus_attrubute(&field.attrs)? {
+            attrs.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    default = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        let ident = field.ident.as_ref().unwrap();
+        let name = match (rename, rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.apply(&ident.unraw().to_string()),
+            (None, None) => ident.unraw().to_string(),
+        };
+        if names.contains(&name) {
+            return Err(Error::new(
+                field.span(),
+                format!("duplicate field name `{}`", name),
+            ));
+        }
+        let ty = &field.ty;
+        inits.push(if default || is_option(ty) {
+            quote! { #ident: hash.get_field::<#ty>(#name)?.unwrap_or_default() }
+        } else {
+            quote! { #ident: hash.fetch_field::<#ty>(#name)? }
+        });
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let tokens = quote! {
+        impl magnus::r_hash::FromRHash for #ident {
+            fn from_r_hash(
+                hash: magnus::RHash,
+            ) -> ::std::result::Result<Self, magnus::Error> {
+                hash.check_fields(&[#(#names),*])?;
+                ::std::result::Result::Ok(Self {
+                    #(#inits,)*
+                })
+            }
+        }
+    };
+    Ok(tokens)
+}
+
+// Optional fields can be missing from the hash. This can only check the name
+// of the type, so doesn't see through type aliases.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => {
+            matches!(path.path.segments.last(), Some(seg) if seg.ident == "Option")
+        }
+        _ => false,
+    }
+}
@@ -19,7 +19,10 @@
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
 
+mod const_enum;
+mod from_r_hash;
 mod init;
+mod symbol_enum;
 mod typed_data;
 mod util;
 
@@ -126,6 +129,10 @@ pub fn init(attrs: TokenStream, item: TokenStream) -> TokenStream {
 ///   implmentation does not call Ruby.
 /// * `size` - Report the [`std::mem::size_of_val`] of the type to Ruby, used
 ///   to aid in deciding when to run the garbage collector.
+/// * `memsize = "..."` - Report the size of the type to Ruby as returned by
+///   the named function, which must take `&Self` and return `usize`. Implies
+///   `size`. This is reported by `ObjectSpace.memsize_of` and memory
+///   profilers, so should include any heap memory owned by the type.
 /// * `unsafe_generics` - The derived implementation of [`TypedData`] is not
 ///   guaranteed to be correct for types with generics. If you are sure it is
 ///   for your type this attribute can be used to override the compile time
@@ -216,9 +223,17 @@ pub fn wrap(attrs: TokenStream, item: TokenStream) -> TokenStream {
 /// For cases where no custom `DataTypeFunctions` are required a default
 /// implementation can be derived. The [`macro@wrap`] macro may be a simpler
 /// alternative in this use case.
-#[proc_macro_derive(DataTypeFunctions)]
+///
+/// If the `#[magnus(...)]` attribute for [`TypedData`] sets
+/// `memsize = "..."`, the derived `DataTypeFunctions::size` will call the
+/// named function.
+#[proc_macro_derive(DataTypeFunctions, attributes(magnus))]
 pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
-    typed_data::expand_derive_data_type_functions(parse_macro_input!(input)).into()
+    match typed_data::expand_derive_data_type_functions(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
 }
 
 /// Derives `TypedData`, allowing the type to be passed to Ruby automatically
@@ -239,6 +254,9 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   and `DataTypeFunctions::free` implementations do not call Ruby.
 /// * `mark` - Enable Ruby calling the `DataTypeFunctions::mark` function.
 /// * `size` - Enable Ruby calling the `DataTypeFunctions::size` function.
+/// * `memsize = "..."` - Implies `size`. When `DataTypeFunctions` is also
+///   derived, the derived `DataTypeFunctions::size` calls the named function,
+///   which must take `&Self` and return `usize`.
 /// * `compact` - Enable Ruby calling the `DataTypeFunctions::compact` function.
 /// * `wb_protected` - Enable the `wb_protected` flag.
 /// * `frozen_shareable` - Enable the `frozen_shareable` flag.
@@ -246,6 +264,10 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///   guaranteed to be correct for types with generics. If you are sure it is
 ///   for your type this attribute can be used to override the compile time
 ///   error usually generated for types with generics.
+/// * `rename_all = "..."` - Convert the Ruby names of all methods defined by
+///   `define_opaque_attr_readers` to the given case, one of `"lowercase"`,
+///   `"UPPERCASE"`, `"PascalCase"`, `"camelCase"`, `"snake_case"`,
+///   or `"SCREAMING_SNAKE_CASE"`.
 ///
 /// # Field Attributes
 ///
@@ -253,7 +275,12 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 /// following values:
 ///
 /// * `opaque_attr_reader` - For a Ruby value wrapped in `Opaque`, creates a
-///   accessor method that returns the unwrapped Ruby value.
+///   accessor method with the same name as the field that returns the
+///   unwrapped Ruby value. A private associated function
+///   `define_opaque_attr_readers(class: RClass) -> Result<(), Error>` is also
+///   created to define these accessors as Ruby methods on `class`.
+/// * `rename = "..."` - Use with `opaque_attr_reader` to set the name of the
+///   Ruby method, rather than using the field name or `rename_all`.
 ///
 /// # Variant Attributes
 ///
@@ -316,6 +343,26 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 /// }
 /// ```
 ///
+/// Reporting memory usage with a function:
+///
+/// ```
+/// use std::mem::size_of_val;
+///
+/// use magnus::{DataTypeFunctions, TypedData};
+///
+/// #[derive(DataTypeFunctions, TypedData)]
+/// #[magnus(class = "Buffer", memsize = "Buffer::memsize", free_immediately)]
+/// struct Buffer {
+///     data: Vec<u8>,
+/// }
+///
+/// impl Buffer {
+///     fn memsize(&self) -> usize {
+///         size_of_val(self) + self.data.capacity()
+///     }
+/// }
+/// ```
+///
 /// Defining a custom `DataType` function:
 ///
 /// ```
@@ -356,7 +403,7 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 /// struct Line {
 ///     #[magnus(opaque_attr_reader)]
 ///     start: Opaque<Obj<Point>>,
-///     #[magnus(opaque_attr_reader)]
+///     #[magnus(opaque_attr_reader, rename = "finish")]
 ///     end: Opaque<Obj<Point>>,
 /// }
 ///
@@ -388,6 +435,8 @@ pub fn derive_data_type_functions(input: TokenStream) -> TokenStream {
 ///     let line = define_class("Line", class::object())?;
 ///     line.define_singleton_method("new", function!(Line::new, 2))?;
 ///     line.define_method("length", method!(Line::length, 0))?;
+///     // defines `start` and `finish`
+///     Line::define_opaque_attr_readers(line)?;
 ///     Ok(())
 /// }
 /// ```
@@ -399,3 +448,215 @@ pub fn derive_typed_data(input: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+/// Derives `FromRHash` for a struct with named fields, converting from a Ruby
+/// Hash with an entry for each field.
+///
+/// Each field is fetched with `RHash::fetch_field`, so may be keyed by either
+/// a Symbol or a String, and is converted with `TryConvert`. Fields with an
+/// `Option` type may be missing from the hash. Any other keys in the hash
+/// raise an `ArgumentError`, as with unknown keyword arguments.
+///
+/// As well as converting records with `RArray::to_vec_of`, this can be used
+/// with `scan_args::KeywordArgs` to accept keyword arguments.
+///
+/// # Attributes
+///
+/// The `#[magnus(...)]` attribute can be set with the following values:
+///
+/// * `rename_all = "..."` - Convert the names of all fields to the given case
+///   to get the hash key, one of `"lowercase"`, `"UPPERCASE"`,
+///   `"PascalCase"`, `"camelCase"`, `"snake_case"`, `"SCREAMING_SNAKE_CASE"`,
+///   `"kebab-case"`, or `"SCREAMING-KEBAB-CASE"`.
+///
+/// # Field Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on struct fields with the
+/// following values:
+///
+/// * `rename = "..."` - sets the hash key for the field, rather than using
+///   the field name or `rename_all`.
+/// * `default` - if the field is missing from the hash use
+///   [`Default::default`].
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     function, r_hash::FromRHash, rb_assert, scan_args::KeywordArgs, Error, RArray, RHash, Ruby,
+/// };
+///
+/// #[derive(FromRHash)]
+/// #[magnus(rename_all = "camelCase")]
+/// struct User {
+///     user_name: String,
+///     #[magnus(rename = "years")]
+///     age: u8,
+///     email: Option<String>,
+///     #[magnus(default)]
+///     login_count: usize,
+/// }
+///
+/// fn describe(user: KeywordArgs<User>) -> String {
+///     let KeywordArgs(user) = user;
+///     format!(
+///         "{} ({}) {} {}",
+///         user.user_name,
+///         user.age,
+///         user.email.unwrap_or_default(),
+///         user.login_count
+///     )
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("describe", function!(describe, 0..=0, kw));
+///
+///     rb_assert!(
+///         ruby,
+///         r#"describe(userName: "Alice", years: 42, loginCount: 3) == "Alice (42)  3""#
+///     );
+///
+///     let users = ruby
+///         .eval::<RArray>(r#"[{"userName" => "Bob", "years" => 7, "email" => "b@x"}]"#)?
+///         .to_vec_of::<User>()?;
+///     assert_eq!(users[0].email.as_deref(), Some("b@x"));
+///     assert_eq!(users[0].login_count, 0);
+///
+///     let hash: RHash = ruby.eval("{userName: 'Eve'}")?;
+///     assert!(User::from_r_hash(hash).is_err());
+///
+///     let hash: RHash = ruby.eval("{userName: 'Eve', years: 30, admin: true}")?;
+///     let err = User::from_r_hash(hash).unwrap_err();
+///     assert_eq!(err.to_string(), "ArgumentError: unknown keyword: :admin");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[proc_macro_derive(FromRHash, attributes(magnus))]
+pub fn derive_from_r_hash(input: TokenStream) -> TokenStream {
+    match from_r_hash::expand_derive_from_r_hash(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Derives `TryConvert` and `IntoValue` for a fieldless enum, converting to
+/// and from a fixed set of Ruby Symbols.
+///
+/// By default each variant maps to its name converted to snake case, e.g.
+/// `GzipFast` maps to `:gzip_fast`. The Symbols are interned once, the first
+/// time they are used.
+///
+/// Converting any other Symbol raises an `ArgumentError` listing the allowed
+/// Symbols.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `rename = "..."` - sets the Symbol for the variant, rather than using the
+///   snake case variant name.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, rb_assert, Error, Ruby, SymbolEnum};
+///
+/// #[derive(Debug, PartialEq, SymbolEnum)]
+/// enum Compression {
+///     Gzip,
+///     None,
+///     #[magnus(rename = "auto")]
+///     Detect,
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let echo = function!(|c: Compression| c, 1);
+///     ruby.define_global_function("echo_compression", echo);
+///
+///     rb_assert!(ruby, "echo_compression(:gzip) == :gzip");
+///     rb_assert!(ruby, "echo_compression(:auto) == :auto");
+///     assert_eq!(ruby.eval::<Compression>(":none")?, Compression::None);
+///
+///     let err = ruby.eval::<Compression>(":zstd").unwrap_err();
+///     assert_eq!(
+///         err.to_string(),
+///         "ArgumentError: invalid value :zstd, expected one of :gzip, :none, :auto"
+///     );
+///
+///     // dynamic symbols are rejected without being interned
+///     assert!(ruby.eval::<Compression>(r#""brotli".to_sym"#).is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[proc_macro_derive(SymbolEnum, attributes(magnus))]
+pub fn derive_symbol_enum(input: TokenStream) -> TokenStream {
+    match symbol_enum::expand_derive_symbol_enum(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
+
+/// Derives `ConstEnum` for a fieldless enum, mapping each variant to an
+/// Integer Ruby constant.
+///
+/// By default each variant maps to a constant named after the variant
+/// converted to upper snake case, e.g. `GzipFast` maps to `GZIP_FAST`, with
+/// the variant's discriminant as its value.
+///
+/// The constants can be defined with `Module::define_enum_consts`, and a
+/// constant's value converted back to the enum with `ConstEnum::from_const`.
+///
+/// # Variant Attributes
+///
+/// The `#[magnus(...)]` attribute can be set on enum variants with the
+/// following values:
+///
+/// * `rename = "..."` - sets the constant name for the variant, rather than
+///   using the upper snake case variant name.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{prelude::*, rb_assert, ConstEnum, Error, Ruby};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, ConstEnum)]
+/// enum Signal {
+///     Hangup = 1,
+///     Interrupt = 2,
+///     #[magnus(rename = "KILL")]
+///     ForceQuit = 9,
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let module = ruby.define_module("Signals")?;
+///     module.define_enum_consts::<Signal>()?;
+///
+///     rb_assert!(ruby, "Signals::HANGUP == 1");
+///     rb_assert!(ruby, "Signals::KILL == 9");
+///     assert_eq!(Signal::from_const(ruby.eval("Signals::INTERRUPT")?)?, Signal::Interrupt);
+///
+///     let err = Signal::from_const(ruby.eval("3")?).unwrap_err();
+///     assert_eq!(
+///         err.to_string(),
+///         "ArgumentError: invalid value 3, expected one of HANGUP, INTERRUPT, KILL"
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[proc_macro_derive(ConstEnum, attributes(magnus))]
+pub fn derive_const_enum(input: TokenStream) -> TokenStream {
+    match const_enum::expand_derive_const_enum(parse_macro_input!(input)) {
+        Ok(tokens) => tokens,
+        Err(e) => e.into_compile_error(),
+    }
+    .into()
+}
@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, LitStr};
+
+use crate::util;
+
+pub fn expand_derive_symbol_enum(input: DeriveInput) -> Result<TokenStream, Error> {
+    let variants = match input.data {
+        Data::Enum(DataEnum { variants, .. }) => variants,
+        _ => {
+            return Err(Error::new(
+                input.span(),
+                "SymbolEnum can only be derived for enums",
+            ))
+        }
+    };
+    if !input.generics.to_token_stream().is_empty() {
+        return Err(Error::new_spanned(
+            input.generics,
+            "SymbolEnum can not be derived for types with generics",
+        ));
+    }
+    if variants.is_empty() {
+        return Err(Error::new(
+            input.ident.span(),
+            "SymbolEnum can not be derived for enums without variants",
+        ));
+    }
+
+    let mut names = Vec::new();
+    let mut idents = Vec::new();
+    for variant in variants.iter() {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.fields.span(),
+                "SymbolEnum variants can not have fields",
+            ));
+        }
+        let mut rename = None;
+        if let Some(attrs) = util::get_magnus_attrubute(&variant.attrs)? {
+            attrs.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported attribute"))
+                }
+            })?;
+        }
+        let name = rename.unwrap_or_else(|| util::snake_case(&variant.ident.to_string()));
+        if names.contains(&name) {
+            return Err(Error::new(
+                variant.span(),
+                format!("duplicate symbol `:{}`", name),
+            ));
+        }
+        names.push(name);
+        idents.push(&variant.ident);
+    }
+
+    let ident = &input.ident;
+    let len = names.len();
+    let indices = 0..len;
+    let indices2 = 0..len;
+    let expected = names
+        .iter()
+        .map(|n| format!(":{}", n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let tokens = quote! {
+        const _: () = {
+            use magnus::{
+                value::{Lazy, LazyId, ReprValue as _},
+                IntoValue as _,
+            };
+
+            static IDS: [LazyId; #len] = [#(LazyId::new(#names)),*];
+
+            // maps each symbol to the index of its variant
+            static TABLE: Lazy<magnus::RHash> = Lazy::new(|ruby| {
+                ruby.hash_from_iter(
+                    IDS.iter()
+                        .enumerate()
+                        .map(|(i, id)| (LazyId::get_inner_with(id, ruby), i)),
+                )
+            });
+
+            impl magnus::TryConvert for #ident {
+                fn try_convert(
+                    val: magnus::Value,
+                ) -> ::std::result::Result<Self, magnus::Error> {
+                    let ruby = magnus::Ruby::get_with(val);
+                    let sym = <magnus::Symbol as magnus::TryConvert>::try_convert(val)?;
+                    // a dynamic symbol can't match, as the variants' symbols
+                    // are static, and converting it to an `Id` would pin it
+                    // so it could never be garbage collected
+                    let index = sym
+                        .as_static()
+                        .and_then(|sym| ruby.get_inner(&TABLE).get(sym))
+                        .and_then(|i| <usize as magnus::TryConvert>::try_convert(i).ok());
+                    match index {
+                        #(::std::option::Option::Some(#indices) => {
+                            ::std::result::Result::Ok(Self::#idents)
+                        })*
+                        _ => ::std::result::Result::Err(magnus::Error::new(
+                            ruby.exception_arg_error(),
+                            ::std::format!(
+                                "invalid value {}, expected one of {}",
+                                sym.inspect(),
+                                #expected,
+                            ),
+                        )),
+                    }
+                }
+            }
+
+            unsafe impl magnus::try_convert::TryConvertOwned for #ident {}
+
+            impl magnus::IntoValue for #ident {
+                fn into_value_with(self, handle: &magnus::Ruby) -> magnus::Value {
+                    let index = match self {
+                        #(Self::#idents => #indices2,)*
+                    };
+                    LazyId::get_inner_with(&IDS[index], handle).into_value_with(handle)
+                }
+            }
+
+            unsafe impl magnus::IntoValueFromNative for #ident {}
+        };
+    };
+    Ok(tokens)
+}
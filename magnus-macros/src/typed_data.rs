@@ -1,7 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 use syn::{
-    spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, LitStr,
+    ext::IdentExt, spanned::Spanned, Data, DataEnum, DataStruct, DeriveInput, Error, Expr, Fields,
+    FieldsNamed, LitStr, Path, Token,
 };
 
 use crate::util;
@@ -14,12 +15,35 @@ pub fn expand(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
-pub fn expand_derive_data_type_functions(input: DeriveInput) -> TokenStream {
+pub fn expand_derive_data_type_functions(input: DeriveInput) -> Result<TokenStream, Error> {
+    let mut memsize = None;
+    if let Some(attrs) = util::get_magnus_attrubute(&input.attrs)? {
+        // other attributes are for the TypedData derive, skip over them
+        attrs.parse_nested_meta(|meta| {
+            if meta.path.is_ident("memsize") {
+                memsize = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
+            }
+            Ok(())
+        })?;
+    }
+
     let ident = input.ident;
     let generics = input.generics;
-    quote! {
-        impl #generics magnus::DataTypeFunctions for #ident #generics {}
-    }
+    let size = match memsize {
+        Some(path) => quote! {
+            fn size(&self) -> usize {
+                #path(self)
+            }
+        },
+        None => quote! {},
+    };
+    Ok(quote! {
+        impl #generics magnus::DataTypeFunctions for #ident #generics {
+            #size
+        }
+    })
 }
 
 pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -37,6 +61,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     let mut wb_protected = false;
     let mut frozen_shareable = false;
     let mut unsafe_generics = false;
+    let mut rename_all = None;
 
     attrs.parse_nested_meta(|meta| {
         if meta.path.is_ident("class") {
@@ -51,6 +76,11 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         } else if meta.path.is_ident("size") {
             size = true;
             Ok(())
+        } else if meta.path.is_ident("memsize") {
+            // used by the DataTypeFunctions derive, implies `size`
+            meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+            size = true;
+            Ok(())
         } else if meta.path.is_ident("compact") {
             compact = true;
             Ok(())
@@ -66,6 +96,9 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         } else if meta.path.is_ident("unsafe_generics") {
             unsafe_generics = true;
             Ok(())
+        } else if meta.path.is_ident("rename_all") {
+            rename_all = Some(util::RenameRule::parse(&meta.value()?.parse::<LitStr>()?)?);
+            Ok(())
         } else if meta.path.is_ident("free_immediatly") {
             Err(meta.error("unsupported attribute (use free_immediately)"))
         } else {
@@ -118,7 +151,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
                 Some(v) => v,
                 None => return Err(Error::new(attrs.span(), "missing attribute: `class = ...`")),
             };
-            let ident = &variant.ident;
+            let variant_ident = &variant.ident;
             let fetch_class = quote! {
                 static CLASS: Lazy<RClass> = Lazy::new(|ruby| {
                     let class: RClass = ruby.class_object().funcall("const_get", (#class,)).unwrap();
@@ -128,9 +161,9 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
                 ruby.get_inner(&CLASS)
             };
             arms.push(match variant.fields {
-                Fields::Named(_) => quote! { Self::#ident { .. } => { #fetch_class } },
-                Fields::Unnamed(_) => quote! { Self::#ident(_) => { #fetch_class } },
-                Fields::Unit => quote! { Self::#ident => #fetch_class },
+                Fields::Named(_) => quote! { Self::#variant_ident { .. } => { #fetch_class } },
+                Fields::Unnamed(_) => quote! { Self::#variant_ident(_) => { #fetch_class } },
+                Fields::Unit => quote! { Self::#variant_ident => #fetch_class },
             });
         }
     }
@@ -150,6 +183,7 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
     };
 
     let mut accessors = Vec::new();
+    let mut readers = Vec::new();
     if let Data::Struct(DataStruct {
         fields: Fields::Named(FieldsNamed { ref named, .. }),
         ..
@@ -161,24 +195,46 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
                 None => continue,
             };
             let mut read = false;
+            let mut rename = None;
             attrs.parse_nested_meta(|meta| {
                 if meta.path.is_ident("opaque_attr_reader") {
                     read = true;
                     Ok(())
+                } else if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
                 } else {
                     Err(meta.error("unsupported attribute"))
                 }
             })?;
-            let ident = field.ident.as_ref().unwrap();
+            if rename.is_some() && !read {
+                return Err(Error::new(
+                    attrs.span(),
+                    "`rename` requires `opaque_attr_reader`",
+                ));
+            }
+            let field_ident = field.ident.as_ref().unwrap();
+            let name = match (rename, rename_all) {
+                (Some(rename), _) => rename,
+                (None, Some(rule)) => rule.apply(&field_ident.unraw().to_string()),
+                (None, None) => field_ident.unraw().to_string(),
+            };
             let ty = &field.ty;
             if read {
                 accessors.push(quote! {
                     #[inline]
-                    fn #ident(&self) -> <#ty as magnus::value::OpaqueVal>::Val {
+                    fn #field_ident(&self) -> <#ty as magnus::value::OpaqueVal>::Val {
                         let handle = magnus::Ruby::get().unwrap();
-                        handle.get_inner(self.#ident)
+                        handle.get_inner(self.#field_ident)
                     }
                 });
+                readers.push(quote! {
+                    magnus::Module::define_method(
+                        class,
+                        #name,
+                        magnus::method!(#ident::#field_ident, 0),
+                    )?;
+                });
             }
         }
     }
@@ -187,6 +243,14 @@ pub fn expand_derive_typed_data(input: DeriveInput) -> Result<TokenStream, Error
         quote! {
             impl #ident {
                 #(#accessors)*
+
+                #[allow(dead_code)]
+                fn define_opaque_attr_readers(
+                    class: magnus::RClass,
+                ) -> ::std::result::Result<(), magnus::Error> {
+                    #(#readers)*
+                    ::std::result::Result::Ok(())
+                }
             }
         }
     } else {
@@ -13,7 +13,11 @@ extern "C" {
     fn ruby_thread_has_gvl_p() -> ::std::os::raw::c_int;
 }
 
-use crate::{error::RubyUnavailableError, value::ReprValue};
+use crate::{
+    error::RubyUnavailableError,
+    module::Module,
+    value::{ReprValue, Value},
+};
 
 #[derive(Clone, Copy)]
 enum RubyGvlState {
@@ -100,6 +104,7 @@ impl RubyGvlState {
 /// * [Extracting values from `Opaque`/`Lazy`](#extracting-values-from-opaquelazy)
 /// * [`false`](#false)
 /// * [`Fiber`](#fiber)
+/// * [Filesystem](#filesystem) - paths and directories
 /// * [`Fixnum`](#fixnum) - small/fast integers
 /// * [`Float`](#float)
 /// * [`Flonum`](#flonum) - lower precision/fast floats
@@ -108,6 +113,7 @@ impl RubyGvlState {
 ///   as calling the current `super` method.
 /// * [`Id`](#id) - low-level Symbol representation
 /// * [`Integer`](#integer)
+/// * [`IO::Buffer`](#iobuffer) - binary buffers
 /// * [`Mutex`](#mutex)
 /// * [`nil`](#nil)
 /// * [`Proc`](#proc) - Ruby's blocks as objects
@@ -116,6 +122,9 @@ impl RubyGvlState {
 /// * [`RArray`](#rarray)
 /// * [`RbEncoding`](#rbencoding) - string encoding
 /// * [`RBignum`](#rbignum) - big integers
+/// * [`RClass`](#rclass)
+/// * [`RComplex`](#rcomplex)
+/// * [`RFile`](#rfile) - files
 /// * [`RFloat`](#rfloat)
 /// * [`RHash`](#rhash)
 /// * [`RModule`](#rmodule)
@@ -130,6 +139,7 @@ impl RubyGvlState {
 /// * [`Time`](#time)
 /// * [`true`](#true)
 /// * [`typed_data::Obj`](#typed_dataobj) - wrapping Rust data in a Ruby object
+/// * [Version](#version) - runtime detection of Ruby's version and features
 pub struct Ruby(PhantomData<*mut ()>);
 
 /// # Accessing `Ruby`
@@ -179,3 +189,143 @@ impl Ruby {
         Self(PhantomData)
     }
 }
+
+/// # Version
+///
+/// Functions for detecting the version and features of the running Ruby.
+///
+/// Unlike the `ruby_gte_*` style `cfg`s set at compile time, these report on
+/// the Ruby the extension has been loaded in to, and can be used to choose
+/// behaviour at runtime.
+impl Ruby {
+    /// Returns the version of the running Ruby, as `(major, minor, teeny)`.
+    ///
+    /// This is parsed from Ruby's `RUBY_VERSION` constant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let (major, minor, teeny) = ruby.ruby_version();
+    ///     let version = format!("{}.{}.{}", major, minor, teeny);
+    ///     rb_assert!(ruby, "RUBY_VERSION == version", version);
+    ///
+    ///     if ruby.ruby_version() >= (3, 2, 0) {
+    ///         rb_assert!(ruby, "defined?(Data)");
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn ruby_version(&self) -> (u32, u32, u32) {
+        let version = self
+            .class_object()
+            .const_get::<_, String>("RUBY_VERSION")
+            .unwrap_or_default();
+        let mut parts = version.split('.').map(|part| {
+            part.bytes()
+                .take_while(u8::is_ascii_digit)
+                .fold(0u32, |acc, b| acc * 10 + (b - b'0') as u32)
+        });
+        (
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        )
+    }
+
+    /// Returns the C API version of the running Ruby, as
+    /// `(major, minor, teeny)`.
+    ///
+    /// Ruby's C API only changes with minor versions, so this is the
+    /// [`ruby_version`](Ruby::ruby_version) with the teeny version set to
+    /// `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let (major, minor, _) = ruby.ruby_version();
+    ///     assert_eq!(ruby.api_version(), (major, minor, 0));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn api_version(&self) -> (u32, u32, u32) {
+        let (major, minor, _) = self.ruby_version();
+        (major, minor, 0)
+    }
+
+    /// Returns the name of the Ruby implementation, from Ruby's
+    /// `RUBY_ENGINE` constant.
+    ///
+    /// This will be `"ruby"` for MRI (aka CRuby), and for example
+    /// `"truffleruby"` for TruffleRuby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.ruby_engine(), "ruby");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn ruby_engine(&self) -> String {
+        self.class_object()
+            .const_get("RUBY_ENGINE")
+            .unwrap_or_else(|_| String::from("ruby"))
+    }
+
+    /// Returns whether the running Ruby has YJIT available and enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let enabled: bool =
+    ///         ruby.eval("defined?(RubyVM::YJIT) && RubyVM::YJIT.enabled? || false")?;
+    ///     assert_eq!(ruby.has_yjit(), enabled);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn has_yjit(&self) -> bool {
+        self.class_object()
+            .funcall::<_, _, Value>("const_get", ("RubyVM::YJIT",))
+            .and_then(|yjit| yjit.funcall("enabled?", ()))
+            .unwrap_or(false)
+    }
+
+    /// Returns whether the running Ruby supports Ractors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.has_ractor(), ruby.ruby_version() >= (3, 0, 0));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn has_ractor(&self) -> bool {
+        self.class_object()
+            .funcall("const_defined?", ("Ractor",))
+            .unwrap_or(false)
+    }
+}
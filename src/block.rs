@@ -4,16 +4,18 @@
 
 use std::{
     fmt,
+    marker::PhantomData,
     mem::{forget, size_of},
     os::raw::c_int,
     slice,
 };
 
 use rb_sys::{
-    rb_block_given_p, rb_block_proc, rb_data_typed_object_wrap, rb_obj_is_proc, rb_proc_arity,
-    rb_proc_call_kw, rb_proc_lambda_p, rb_proc_new, rb_yield, rb_yield_splat, rb_yield_values_kw,
-    VALUE,
+    rb_block_call_kw, rb_block_given_p, rb_block_proc, rb_data_typed_object_wrap, rb_obj_is_proc,
+    rb_proc_arity, rb_proc_call_kw, rb_proc_lambda_p, rb_proc_new, rb_yield, rb_yield_splat,
+    rb_yield_values_kw, VALUE,
 };
+use seq_macro::seq;
 
 use crate::{
     data_type_builder,
@@ -24,11 +26,11 @@ use crate::{
     method::{Block, BlockReturn},
     object::Object,
     r_array::RArray,
-    try_convert::TryConvert,
+    try_convert::{to_proc, TryConvert},
     typed_data::{DataType, DataTypeFunctions},
     value::{
         private::{self, ReprValue as _},
-        NonZeroValue, ReprValue, Value,
+        BoxValue, IntoId, NonZeroValue, ReprValue, Value,
     },
     Ruby,
 };
@@ -155,6 +157,45 @@ impl Ruby {
         proc.ivar_set("__rust_closure", keepalive).unwrap();
         proc
     }
+
+    /// As [`proc_from_fn`](Ruby::proc_from_fn), but for closures that may be
+    /// called again before they return, e.g. by a method that recurses.
+    ///
+    /// `proc_from_fn` calls the closure through a mutable reference, so
+    /// re-entrant calls would create aliasing mutable references.
+    pub(crate) fn proc_from_shared_fn<F, R>(&self, block: F) -> Proc
+    where
+        F: 'static + Send + Fn(&Ruby, &[Value], Option<Proc>) -> R,
+        R: BlockReturn,
+    {
+        unsafe extern "C" fn call<F, R>(
+            _yielded_arg: VALUE,
+            callback_arg: VALUE,
+            argc: c_int,
+            argv: *const VALUE,
+            blockarg: VALUE,
+        ) -> VALUE
+        where
+            F: Fn(&Ruby, &[Value], Option<Proc>) -> R,
+            R: BlockReturn,
+        {
+            let closure = &*(callback_arg as *const F);
+            closure
+                .call_handle_error(argc, argv as *const Value, Value::new(blockarg))
+                .as_rb_value()
+        }
+
+        let (closure, keepalive) = wrap_closure(block);
+        let call_func =
+            call::<F, R> as unsafe extern "C" fn(VALUE, VALUE, c_int, *const VALUE, VALUE) -> VALUE;
+
+        let proc = unsafe {
+            Proc::from_rb_value_unchecked(rb_proc_new(Some(call_func), closure as VALUE))
+        };
+        // ivar without @ prefix is invisible from Ruby
+        proc.ivar_set("__rust_closure", keepalive).unwrap();
+        proc
+    }
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby’s Proc class.
@@ -451,34 +492,74 @@ impl ReprValue for Proc {}
 
 impl TryConvert for Proc {
     fn try_convert(val: Value) -> Result<Self, Error> {
-        let handle = Ruby::get_with(val);
-        if let Some(p) = Proc::from_value(val) {
-            return Ok(p);
-        }
-        let p_val: Value = match val.funcall("to_proc", ()) {
-            Ok(v) => v,
-            Err(_) => {
-                return Err(Error::new(
-                    handle.exception_type_error(),
-                    format!("no implicit conversion of {} into Proc", unsafe {
-                        val.classname()
-                    },),
-                ))
-            }
-        };
-        Proc::from_value(val).ok_or_else(|| {
+        to_proc(val)?.ok_or_else(|| {
             Error::new(
-                handle.exception_type_error(),
-                format!(
-                    "can't convert {0} to Proc ({0}#to_proc gives {1})",
-                    unsafe { val.classname() },
-                    unsafe { p_val.classname() },
-                ),
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into Proc", unsafe {
+                    val.classname()
+                },),
             )
         })
     }
 }
 
+/// Call `method` on `recv` with `args`, passing `func` as the block.
+///
+/// Unlike [`Ruby::proc_from_fn`] `func` is not required to be `'static`, as
+/// it is only borrowed for the duration of the call. This must only be used
+/// with methods that do not retain the block after returning.
+pub(crate) fn block_call_closure<M, A, F, R, T>(
+    recv: Value,
+    method: M,
+    args: A,
+    mut func: F,
+) -> Result<T, Error>
+where
+    M: IntoId,
+    A: ArgList,
+    F: FnMut(&Ruby, &[Value], Option<Proc>) -> R,
+    R: BlockReturn,
+    T: TryConvert,
+{
+    unsafe extern "C" fn call<F, R>(
+        _yielded_arg: VALUE,
+        callback_arg: VALUE,
+        argc: c_int,
+        argv: *const VALUE,
+        blockarg: VALUE,
+    ) -> VALUE
+    where
+        F: FnMut(&Ruby, &[Value], Option<Proc>) -> R,
+        R: BlockReturn,
+    {
+        let closure = &mut *(callback_arg as *mut F);
+        closure
+            .call_handle_error(argc, argv as *const Value, Value::new(blockarg))
+            .as_rb_value()
+    }
+
+    let handle = Ruby::get_with(recv);
+    let id = method.into_id_with(&handle);
+    let kw_splat = kw_splat(&args);
+    let args = args.into_arg_list_with(&handle);
+    let slice = args.as_ref();
+    let call_func =
+        call::<F, R> as unsafe extern "C" fn(VALUE, VALUE, c_int, *const VALUE, VALUE) -> VALUE;
+
+    protect(|| unsafe {
+        Value::new(rb_block_call_kw(
+            recv.as_rb_value(),
+            id.as_rb_id(),
+            slice.len() as c_int,
+            slice.as_ptr() as *const VALUE,
+            Some(call_func),
+            &mut func as *mut F as VALUE,
+            kw_splat as c_int,
+        ))
+    })
+    .and_then(TryConvert::try_convert)
+}
+
 /// Wrap a closure in a Ruby object with no class.
 ///
 /// This effectivly makes the closure's lifetime managed by Ruby. It will be
@@ -523,6 +604,154 @@ where
     unsafe { (&mut (*ptr).0 as *mut F, value) }
 }
 
+mod callback_args {
+    use super::*;
+
+    pub trait CallbackArgs: ArgList {
+        const LEN: usize;
+    }
+
+    macro_rules! impl_callback_args {
+        ($n:literal) => {
+            seq!(N in 0..$n {
+                impl<#(T~N,)*> CallbackArgs for (#(T~N,)*)
+                where
+                    #(T~N: IntoValue,)*
+                {
+                    const LEN: usize = $n;
+                }
+            });
+        }
+    }
+
+    seq!(N in 0..=12 {
+        impl_callback_args!(N);
+    });
+}
+
+/// Trait for types that can be used as the arguments of a [`Callback`].
+///
+/// This trait is implemented for `()`, `(T0,)`, `(T0, T1)`, `(T0, T1, T2)`,
+/// etc, through to a length of 12, where `T0`, `T1`, etc implement
+/// [`IntoValue`].
+pub trait CallbackArgs: callback_args::CallbackArgs {}
+impl<T> CallbackArgs for T where T: callback_args::CallbackArgs {}
+
+/// A Ruby callable (such as a `Proc`, lambda, or `Method`) with typed
+/// arguments and return value.
+///
+/// This is intended for APIs that accept a callback from Ruby to be called
+/// later, such as event handlers. When converted from a Ruby object the
+/// callable is checked to respond to `call`, and for lambdas and methods
+/// that it accepts `A::LEN` arguments, so errors are raised at registration
+/// rather than when the callback is called.
+///
+/// The callable is registered with Ruby's garbage collector (see
+/// [`BoxValue`]) so a `Callback` can be safely stored on the heap, for
+/// example in a wrapped type. It is unregistered when the `Callback` is
+/// dropped, which must happen on a Ruby thread.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{block::Callback, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let callback: Callback<(i64, i64), i64> = ruby.eval("->(a, b) { a + b }")?;
+///     assert_eq!(callback.call((1, 2))?, 3);
+///
+///     let callback: Callback<(i64,), i64> = ruby.eval("1.method(:+)")?;
+///     assert_eq!(callback.call((2,))?, 3);
+///
+///     // wrong number of arguments
+///     assert!(ruby.eval::<Callback<(i64, i64), i64>>("1.method(:+)").is_err());
+///     // not callable
+///     assert!(ruby.eval::<Callback<(), i64>>("1").is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct Callback<A, R> {
+    callable: BoxValue<Value>,
+    phantom: PhantomData<fn(A) -> R>,
+}
+
+impl<A, R> Callback<A, R>
+where
+    A: CallbackArgs,
+    R: TryConvert,
+{
+    /// Create a new `Callback` from `callable`.
+    ///
+    /// Errors with a `TypeError` if `callable` does not respond to `call`,
+    /// or an `ArgumentError` if `callable` is a lambda or method with an
+    /// arity incompatible with `A`. Non-lambda Procs are not checked, as they
+    /// accept any number of arguments.
+    pub fn new(callable: Value) -> Result<Self, Error> {
+        let handle = Ruby::get_with(callable);
+        if !callable.respond_to("call", false)? {
+            return Err(Error::new(
+                handle.exception_type_error(),
+                format!("{} is not callable", callable.inspect()),
+            ));
+        }
+        let check_arity = match Proc::from_value(callable) {
+            Some(proc) => proc.is_lambda(),
+            None => callable.respond_to("arity", false)?,
+        };
+        if check_arity {
+            let arity: i64 = callable.funcall("arity", ())?;
+            let ok = if arity >= 0 {
+                arity as usize == A::LEN
+            } else {
+                (-arity - 1) as usize <= A::LEN
+            };
+            if !ok {
+                return Err(Error::new(
+                    handle.exception_arg_error(),
+                    format!(
+                        "callback must accept {} arguments, {} has arity {}",
+                        A::LEN,
+                        callable.inspect(),
+                        arity
+                    ),
+                ));
+            }
+        }
+        Ok(Self {
+            callable: BoxValue::new(callable),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Call the callback with `args`, converting the result to `R`.
+    pub fn call(&self, args: A) -> Result<R, Error> {
+        self.callable.funcall("call", args)
+    }
+
+    /// Returns the Ruby callable.
+    pub fn as_value(&self) -> Value {
+        *self.callable
+    }
+}
+
+impl<A, R> fmt::Debug for Callback<A, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Callback").field(&*self.callable).finish()
+    }
+}
+
+impl<A, R> TryConvert for Callback<A, R>
+where
+    A: CallbackArgs,
+    R: TryConvert,
+{
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::new(val)
+    }
+}
+
 /// # Blocks
 ///
 /// Functions to enable working with Ruby blocks.
@@ -1068,6 +1297,46 @@ where
     );
 }
 
+// see do_yield_iter
+pub(crate) unsafe fn do_yield_with_iter<I, T, F, R>(iter: I, func: F) -> Result<Value, Error>
+where
+    I: Iterator<Item = T>,
+    T: IntoValue,
+    F: FnMut(Value) -> Result<YieldControl<R>, Error>,
+    R: IntoValue,
+{
+    let handle = Ruby::get_unchecked();
+    let mut state = (iter, func);
+    let ptr = &mut state as *mut (I, F);
+    forget(state);
+    let mut error = None;
+    let error_ptr = &mut error as *mut Option<Error>;
+    let val = ensure(
+        || {
+            let (iter, func) = &mut *ptr;
+            for val in iter {
+                let res = Value::new(rb_yield(handle.into_value(val).as_rb_value()));
+                match func(res) {
+                    Ok(YieldControl::Continue) => (),
+                    Ok(YieldControl::Stop(val)) => return handle.into_value(val),
+                    Err(e) => {
+                        *error_ptr = Some(e);
+                        break;
+                    }
+                }
+            }
+            handle.qnil().as_value()
+        },
+        || {
+            ptr.drop_in_place();
+        },
+    );
+    match error {
+        Some(e) => Err(e),
+        None => Ok(val),
+    }
+}
+
 /// Helper type for functions that either yield a single value to a block or
 /// return an Enumerator.
 ///
@@ -1207,3 +1476,76 @@ pub enum YieldSplat<I> {
     /// Returns `Enumerator` from the method.
     Enumerator(Enumerator),
 }
+
+/// The result of inspecting a block's return value for [`YieldWith`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum YieldControl<T> {
+    /// Continue yielding the following values from the iterator.
+    Continue,
+    /// Stop yielding, returning `T` from the method.
+    Stop(T),
+}
+
+/// Helper type for functions that yield a single value to a block, inspect
+/// the block's return value, or return an Enumerator.
+///
+/// `I` must implement `Iterator<Item = T>`, where `T` implements
+/// [`IntoValue`]. `F` must implement
+/// `FnMut(Value) -> Result<YieldControl<R>, Error>`, where `R` implements
+/// [`IntoValue`]. `F` is called with the return value of the block after
+/// each yield, and can return [`YieldControl::Stop`] to end iteration early,
+/// setting the return value of the method. If the iterator is exhausted the
+/// method returns `nil`.
+///
+/// A `break` within the block ends iteration and sets the return value of
+/// the method as usual for Ruby, `F` is not called.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     block::{YieldControl, YieldWith},
+///     method,
+///     prelude::*,
+///     rb_assert, Error, Ruby, Value,
+/// };
+///
+/// fn find_index(
+///     ruby: &Ruby,
+///     rb_self: Value,
+/// ) -> YieldWith<
+///     impl Iterator<Item = u8>,
+///     impl FnMut(Value) -> Result<YieldControl<usize>, Error>,
+/// > {
+///     if ruby.block_given() {
+///         let mut i = 0;
+///         YieldWith::Iter(1..=5, move |res: Value| {
+///             if res.to_bool() {
+///                 return Ok(YieldControl::Stop(i));
+///             }
+///             i += 1;
+///             Ok(YieldControl::Continue)
+///         })
+///     } else {
+///         YieldWith::Enumerator(rb_self.enumeratorize("find_index", ()))
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("find_index", method!(find_index, 0));
+///
+///     rb_assert!(ruby, "find_index {|i| i == 3} == 2");
+///     rb_assert!(ruby, "find_index {|i| i == 6}.nil?");
+///     rb_assert!(ruby, "find_index {|i| break :early if i == 2} == :early");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub enum YieldWith<I, F> {
+    /// Yields `I::Item` to given block, calling `F` with the block's return
+    /// value.
+    Iter(I, F),
+    /// Returns `Enumerator` from the method.
+    Enumerator(Enumerator),
+}
@@ -13,7 +13,8 @@ use rb_sys::{
     rb_cObject, rb_cProc, rb_cRandom, rb_cRange, rb_cRational, rb_cRegexp, rb_cStat, rb_cString,
     rb_cStruct, rb_cSymbol, rb_cThread, rb_cTime, rb_cTrueClass, rb_cUnboundMethod, rb_class2name,
     rb_class_new, rb_class_new_instance_kw, rb_class_superclass, rb_define_alloc_func,
-    rb_get_alloc_func, rb_obj_alloc, rb_undef_alloc_func, ruby_value_type, VALUE,
+    rb_get_alloc_func, rb_obj_alloc, rb_set_class_path_string, rb_undef_alloc_func,
+    ruby_value_type, VALUE,
 };
 
 use crate::{
@@ -21,6 +22,7 @@ use crate::{
     into_value::{kw_splat, ArgList, IntoValue},
     module::Module,
     object::Object,
+    r_string::IntoRString,
     try_convert::TryConvert,
     typed_data::TypedData,
     value::{
@@ -68,6 +70,109 @@ impl RClass {
     pub(crate) unsafe fn from_rb_value_unchecked(val: VALUE) -> Self {
         Self(NonZeroValue::new_unchecked(Value::new(val)))
     }
+
+    /// Create a new anonymous class with the superclass `superclass`.
+    ///
+    /// The class will not have a name until it is assigned to a constant, or
+    /// named with [`RClass::set_name`].
+    ///
+    /// This is the same as [`Class::new`], but doesn't require the [`Class`]
+    /// trait to be in scope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = RClass::new_anon(ruby.class_string())?;
+    ///     rb_assert!(ruby, "class.superclass == String", class);
+    ///     rb_assert!(ruby, "class.name.nil?", class);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn new_anon(superclass: RClass) -> Result<RClass, Error> {
+        debug_assert_value!(superclass);
+        protect(|| unsafe {
+            RClass::from_rb_value_unchecked(rb_class_new(superclass.as_rb_value()))
+        })
+    }
+
+    /// Set the name of an anonymous class, without assigning it to a
+    /// constant.
+    ///
+    /// `name` may include a namespace, e.g. `"Outer::Inner"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = RClass::new_anon(ruby.class_object())?;
+    ///     class.set_name("Generated::Model")?;
+    ///     rb_assert!(ruby, r#"class.name == "Generated::Model""#, class);
+    ///     rb_assert!(ruby, r#"!Object.const_defined?(:Generated)"#);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_name<T>(self, name: T) -> Result<(), Error>
+    where
+        T: IntoRString,
+    {
+        let ruby = Ruby::get_with(self);
+        let name = name.into_r_string_with(&ruby);
+        protect(|| {
+            unsafe { rb_set_class_path_string(self.as_rb_value(), rb_cObject, name.as_rb_value()) };
+            ruby.qnil()
+        })?;
+        Ok(())
+    }
+
+    /// Create a new object, an instance of `self`, passing the arguments
+    /// `args` to the initialiser, and convert the result to `T`.
+    ///
+    /// This is a convenience for [`Class::new_instance`] followed by
+    /// [`TryConvert::try_convert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, RClass, RObject, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class: RClass = ruby.eval(
+    ///         r#"
+    ///             Class.new do
+    ///               attr_reader :name
+    ///
+    ///               def initialize(name)
+    ///                 @name = name
+    ///               end
+    ///             end
+    ///         "#,
+    ///     )?;
+    ///     let obj: RObject = class.new_instance_as(("example",))?;
+    ///     assert!(obj.respond_to("name", false)?);
+    ///
+    ///     let s: String = ruby.class_string().new_instance_as(("foo",))?;
+    ///     assert_eq!(s, "foo");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn new_instance_as<A, T>(self, args: A) -> Result<T, Error>
+    where
+        A: ArgList,
+        T: TryConvert,
+    {
+        self.new_instance(args).and_then(TryConvert::try_convert)
+    }
 }
 
 impl fmt::Display for RClass {
@@ -117,6 +222,8 @@ pub trait Class: Module {
 
     /// Create a new anonymous class.
     ///
+    /// See also [`RClass::new_anon`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -263,7 +370,8 @@ pub trait Class: Module {
     /// ```
     fn obj_alloc(self) -> Result<Self::Instance, Error>;
 
-    /// Returns the parent class of `self`.
+    /// Returns the parent class of `self`, or `None` if `self` has no parent
+    /// class (e.g. `BasicObject`).
     ///
     /// Returns `Err` if `self` can not have a parent class.
     ///
@@ -273,9 +381,11 @@ pub trait Class: Module {
     /// use magnus::{prelude::*, Error, Ruby};
     ///
     /// fn example(ruby: &Ruby) -> Result<(), Error> {
-    ///     let klass = ruby.class_hash().superclass()?;
+    ///     let klass = ruby.class_hash().superclass()?.unwrap();
     ///     assert!(klass.equal(ruby.class_object())?);
     ///
+    ///     assert!(ruby.class_basic_object().superclass()?.is_none());
+    ///
     ///     Ok(())
     /// }
     /// # Ruby::init(example).unwrap()
@@ -285,17 +395,16 @@ pub trait Class: Module {
     /// use magnus::{prelude::*, Error, Ruby};
     ///
     /// fn example(ruby: &Ruby) -> Result<(), Error> {
-    ///     let klass = ruby.exception_exception().superclass()?;
+    ///     let klass = ruby.exception_exception().superclass()?.unwrap();
     ///     assert!(klass.equal(ruby.class_object())?);
     ///
     ///     Ok(())
     /// }
     /// # Ruby::init(example).unwrap()
     /// ```
-    fn superclass(self) -> Result<RClass, Error> {
-        protect(|| unsafe {
-            RClass::from_rb_value_unchecked(rb_class_superclass(self.as_rb_value()))
-        })
+    fn superclass(self) -> Result<Option<RClass>, Error> {
+        protect(|| unsafe { Value::new(rb_class_superclass(self.as_rb_value())) })
+            .map(RClass::from_value)
     }
 
     /// Return the name of `self`.
@@ -466,6 +575,44 @@ pub trait Class: Module {
         }
     }
 
+    /// Remove the allocator function of a class.
+    ///
+    /// After this instances of `self` can not be created with `allocate` or
+    /// `new`, regardless of which allocator function was previously set.
+    /// Subclasses will inherit this, unless they define their own allocator
+    /// function.
+    ///
+    /// See also [`undef_default_alloc_func`](Class::undef_default_alloc_func),
+    /// which only removes Ruby's default allocator function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, wrap, Error, Ruby};
+    ///
+    /// #[derive(Default)]
+    /// #[wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Point", ruby.class_object())?;
+    ///     class.define_alloc_func::<Point>();
+    ///     rb_assert!(ruby, "Point.allocate.is_a?(Point)");
+    ///
+    ///     class.undef_alloc_func();
+    ///     rb_assert!(ruby, "(Point.allocate rescue $!).is_a?(TypeError)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn undef_alloc_func(self) {
+        unsafe { rb_undef_alloc_func(self.as_rb_value()) }
+    }
+
     /// Remove the allocator function of a class if it is Ruby's default
     /// allocator function.
     ///
@@ -517,9 +664,7 @@ impl Class for RClass {
     type Instance = Value;
 
     fn new(superclass: Self) -> Result<Self, Error> {
-        debug_assert_value!(superclass);
-        let superclass = superclass.as_rb_value();
-        protect(|| unsafe { Self::from_rb_value_unchecked(rb_class_new(superclass)) })
+        Self::new_anon(superclass)
     }
 
     fn new_instance<T>(self, args: T) -> Result<Self::Instance, Error>
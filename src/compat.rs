@@ -0,0 +1,35 @@
+//! Functions for code ported from older versions of Ruby.
+//!
+//! These are no-ops on all Ruby versions supported by Magnus, and never call
+//! the deprecated Ruby APIs they replace, so don't emit warnings.
+
+use crate::value::ReprValue;
+
+/// Returns whether `val` is 'untrusted' (or 'tainted').
+///
+/// Ruby's taint tracking became a no-op in Ruby 2.7 and was removed in
+/// Ruby 3.2. As all Ruby versions supported by Magnus are 2.7 or later this
+/// always returns `false`.
+///
+/// # Examples
+///
+/// ```
+/// # #![allow(deprecated)]
+/// use magnus::{compat::is_untrusted, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     assert!(!is_untrusted(ruby.str_new("example")));
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[deprecated(note = "taint tracking is a no-op on all supported Ruby versions")]
+#[inline]
+pub fn is_untrusted<T>(val: T) -> bool
+where
+    T: ReprValue,
+{
+    let _ = val;
+    false
+}
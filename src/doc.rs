@@ -0,0 +1,82 @@
+//! Documentation for methods defined in Rust, for use by Ruby tooling.
+//!
+//! Methods defined with [`Module::define_method`] and friends have no Ruby
+//! source, so tools like `ri` and YARD can't find any documentation for them.
+//! Documentation can be registered with [`Module::document_method`] and
+//! [`Module::document_singleton_method`], which add it to a registry that
+//! can be read from Ruby once the extension has been loaded, e.g. to generate
+//! YARD stubs from a Rake task.
+//!
+//! The registry is an Array stored in the global variable
+//! `$__magnus_docs__`, shared by all extensions loaded in the process. Each
+//! entry is a Hash with the String keys:
+//!
+//! * `"owner"`: the class or module the method is defined on.
+//! * `"name"`: the method name, as a String.
+//! * `"singleton"`: `true` for singleton methods, `false` otherwise.
+//! * `"doc"`: the documentation, as a String.
+//!
+//! See `examples/complete_object/Rakefile` in the Magnus repository for a
+//! Rake task generating YARD stubs from the registry.
+//!
+//! # Examples
+//!
+//! ```
+//! use magnus::{method, prelude::*, rb_assert, Error, Ruby};
+//!
+//! fn shout(s: String) -> String {
+//!     s.to_uppercase()
+//! }
+//!
+//! fn example(ruby: &Ruby) -> Result<(), Error> {
+//!     let class = ruby.class_string();
+//!     class.define_method("shout", method!(shout, 0))?;
+//!     class.document_method("shout", "Returns the string in upper case.")?;
+//!
+//!     rb_assert!(
+//!         ruby,
+//!         r#"$__magnus_docs__.any? { |d| d["owner"] == String && d["name"] == "shout" }"#
+//!     );
+//!
+//!     Ok(())
+//! }
+//! # Ruby::init(example).unwrap()
+//! ```
+//!
+//! [`Module::define_method`]: crate::module::Module::define_method
+//! [`Module::document_method`]: crate::module::Module::document_method
+//! [`Module::document_singleton_method`]: crate::module::Module::document_singleton_method
+
+use rb_sys::{rb_gv_get, rb_gv_set};
+
+use crate::{
+    error::{protect, Error},
+    r_array::RArray,
+    value::{ReprValue, Value},
+    Ruby,
+};
+
+const REGISTRY: &[u8] = b"$__magnus_docs__\0";
+
+fn registry(handle: &Ruby) -> Result<RArray, Error> {
+    let val = protect(|| unsafe { Value::new(rb_gv_get(REGISTRY.as_ptr() as *const _)) })?;
+    if let Some(ary) = RArray::from_value(val) {
+        return Ok(ary);
+    }
+    let ary = handle.ary_new();
+    protect(|| unsafe { Value::new(rb_gv_set(REGISTRY.as_ptr() as *const _, ary.as_rb_value())) })?;
+    Ok(ary)
+}
+
+pub(crate) fn register<T>(owner: T, name: &str, singleton: bool, doc: &str) -> Result<(), Error>
+where
+    T: ReprValue,
+{
+    let handle = Ruby::get_with(owner);
+    let entry = handle.hash_new();
+    entry.aset(handle.str_new("owner"), owner)?;
+    entry.aset(handle.str_new("name"), name)?;
+    entry.aset(handle.str_new("singleton"), singleton)?;
+    entry.aset(handle.str_new("doc"), doc)?;
+    registry(&handle)?.push(entry)
+}
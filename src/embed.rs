@@ -1,9 +1,49 @@
 //! Helpers for use when embedding Ruby in a Rust project.
 //!
 //! See also [`Ruby`](Ruby#embedding) for more embedding related methods.
+//!
+//! # Defining functions
+//!
+//! All of the method definition machinery available to extensions works the
+//! same when embedding. Functions can be defined with methods on the
+//! [`Ruby`] handle, such as [`Ruby::define_global_function`], and the
+//! [`function!`](crate::function) and [`method!`](crate::method) macros.
+//! Functions with an arity of `-1` receive their arguments as a slice, which
+//! can be parsed with [`scan_args`](crate::scan_args) to accept optional
+//! arguments, splats, keywords, and a block.
+//!
+//! ```
+//! use magnus::{
+//!     block::Proc,
+//!     function, rb_assert,
+//!     scan_args::{get_kwargs, scan_args},
+//!     Error, RArray, Ruby, Value,
+//! };
+//!
+//! fn map_words(ruby: &Ruby, args: &[Value]) -> Result<RArray, Error> {
+//!     let args = scan_args::<(), (), RArray, (), _, Proc>(args)?;
+//!     let kw = get_kwargs::<_, (), (Option<String>,), ()>(args.keywords, &[], &["prefix"])?;
+//!     let (prefix,) = kw.optional;
+//!     let result = ruby.ary_new();
+//!     for word in args.splat.to_vec::<String>()? {
+//!         let word = format!("{}{}", prefix.as_deref().unwrap_or(""), word);
+//!         result.push(args.block.call::<_, Value>((word,))?)?;
+//!     }
+//!     Ok(result)
+//! }
+//!
+//! let ruby = unsafe { magnus::embed::init() };
+//! ruby.define_global_function("map_words", function!(map_words, -1));
+//!
+//! rb_assert!(
+//!     ruby,
+//!     r#"map_words("a", "b", prefix: "x") { |w| w.upcase } == ["XA", "XB"]"#
+//! );
+//! ```
 
 use std::{
     ffi::CString,
+    mem::forget,
     ops::Deref,
     sync::atomic::{AtomicBool, Ordering},
 };
@@ -11,14 +51,15 @@ use std::{
 #[cfg(windows)]
 use rb_sys::rb_w32_sysinit;
 use rb_sys::{
-    ruby_cleanup, ruby_exec_node, ruby_init_stack, ruby_process_options, ruby_set_script_name,
-    ruby_setup, VALUE,
+    rb_errinfo, rb_set_errinfo, ruby_cleanup, ruby_exec_node, ruby_executable_node,
+    ruby_init_stack, ruby_process_options, ruby_set_script_name, ruby_setup, VALUE,
 };
 
 use crate::{
     error::{protect, Error},
+    exception::Exception,
     r_string::IntoRString,
-    value::private::ReprValue,
+    value::{private::ReprValue, Value},
     Ruby,
 };
 
@@ -129,14 +170,83 @@ pub unsafe fn setup() -> Cleanup {
 #[inline(always)]
 pub unsafe fn init() -> Cleanup {
     let cleanup = setup();
-    init_options(&["-e", ""]);
+    if init_options(&["-e", ""]).is_err() {
+        panic!("Ruby init code failed");
+    }
     cleanup
 }
 
+/// Initialises the Ruby VM, processing `args` as if they were command line
+/// options passed to the `ruby` executable.
+///
+/// This allows options such as `-I` (add to the load path), `-E` (set the
+/// default external/internal encodings), `-r` (require a library), or
+/// `--disable-gems` to be used when embedding Ruby. `args` should not include
+/// the program name.
+///
+/// As with the `ruby` executable, a script to run should be given, either as
+/// a file path or with `-e`. If not, Ruby will attempt to read a script from
+/// stdin. The script is run before this function returns.
+///
+/// See also [`init`] and [`setup`].
+///
+/// # Safety
+///
+/// Must be called in `main()`, or at least a function higher up the stack than
+/// any code calling Ruby. Must not drop Cleanup until the very end of the
+/// process, after all Ruby execution has finished. Do not use Ruby values
+/// after Cleanup has been dropped.
+///
+/// # Errors
+///
+/// Returns `Err` if the options are invalid, or the script raises an
+/// exception. Options that cause the `ruby` executable to exit without running
+/// a script, such as `--version`, result in a `SystemExit` error.
+///
+/// On error the Ruby VM is left running so the error can be inspected, but it
+/// will not be cleaned up at exit, and can not be initialised again.
+///
+/// # Panics
+///
+/// Panics if this, [`init`], [`setup`], or [`Ruby::init`] are collectively
+/// called more than once.
+///
+/// # Examples
+///
+/// ```
+/// let args = ["--disable-gems", "-E", "UTF-8", "-e", "$answer = 42"];
+/// let ruby = unsafe { magnus::embed::init_with_args(&args) }.unwrap();
+/// let result: i64 = ruby.eval("$answer").unwrap();
+/// assert_eq!(result, 42);
+/// assert_eq!(ruby.default_external_encoding().name(), "UTF-8");
+/// ```
+#[inline(always)]
+pub unsafe fn init_with_args<T>(args: &[T]) -> Result<Cleanup, Error>
+where
+    T: AsRef<str>,
+{
+    let cleanup = setup();
+    if let Err(e) = init_options(args) {
+        // `e` may reference a Ruby exception, so the VM must outlive it
+        forget(cleanup);
+        return Err(e);
+    }
+    Ok(cleanup)
+}
+
 #[inline(always)]
-unsafe fn init_options(opts: &[&str]) {
+unsafe fn init_options<T>(opts: &[T]) -> Result<(), Error>
+where
+    T: AsRef<str>,
+{
+    let ruby = Ruby::get_unchecked();
     let mut argv = vec![CString::new("ruby").unwrap()];
-    argv.extend(opts.iter().map(|s| CString::new(*s).unwrap()));
+    for opt in opts {
+        argv.push(
+            CString::new(opt.as_ref())
+                .map_err(|e| Error::new(ruby.exception_arg_error(), e.to_string()))?,
+        );
+    }
     let mut argv = argv
         .iter()
         .map(|cs| cs.as_ptr() as *mut _)
@@ -144,12 +254,24 @@ unsafe fn init_options(opts: &[&str]) {
     let mut node = 0 as _;
     protect(|| {
         node = ruby_process_options(argv.len() as i32, argv.as_mut_ptr());
-        Ruby::get_unchecked().qnil()
-    })
-    .unwrap();
+        ruby.qnil()
+    })?;
+    let mut status = 0;
+    if ruby_executable_node(node, &mut status) == 0 {
+        return Err(Error::new(
+            ruby.exception_system_exit(),
+            format!("exit {}", status),
+        ));
+    }
     if ruby_exec_node(node) != 0 {
-        panic!("Ruby init code failed");
-    };
+        let err = rb_errinfo();
+        rb_set_errinfo(ruby.qnil().as_rb_value());
+        return Err(match Exception::from_value(Value::new(err)) {
+            Some(ex) => ex.into(),
+            None => Error::new(ruby.exception_runtime_error(), "Ruby init code failed"),
+        });
+    }
+    Ok(())
 }
 
 /// # Embedding
@@ -30,8 +30,9 @@ use rb_sys::{
     rb_enc_codelen, rb_enc_codepoint_len, rb_enc_compatible, rb_enc_copy, rb_enc_default_external,
     rb_enc_default_internal, rb_enc_fast_mbclen, rb_enc_find, rb_enc_find_index,
     rb_enc_from_encoding, rb_enc_from_index, rb_enc_get_index, rb_enc_mbclen,
-    rb_enc_precise_mbclen, rb_enc_set_index, rb_enc_to_index, rb_enc_uint_chr, rb_encoding,
-    rb_filesystem_encindex, rb_filesystem_encoding, rb_find_encoding, rb_locale_encindex,
+    rb_enc_precise_mbclen, rb_enc_set_default_external, rb_enc_set_default_internal,
+    rb_enc_set_index, rb_enc_to_index, rb_enc_uint_chr, rb_encoding, rb_filesystem_encindex,
+    rb_filesystem_encoding, rb_find_encoding, rb_locale_charmap, rb_locale_encindex,
     rb_locale_encoding, rb_to_encoding, rb_to_encoding_index, rb_usascii_encindex,
     rb_usascii_encoding, rb_utf8_encindex, rb_utf8_encoding,
 };
@@ -70,6 +71,88 @@ impl Ruby {
     pub fn enc_default_internal(&self) -> Option<Encoding> {
         Encoding::from_value(Value::new(unsafe { rb_enc_default_internal() }))
     }
+
+    /// Sets the default external encoding.
+    ///
+    /// Equivalent to `Encoding.default_external = enc` in Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let prev = ruby.enc_default_external();
+    ///
+    ///     ruby.enc_set_default_external(ruby.usascii_encoding())?;
+    ///     assert_eq!(ruby.default_external_encoding().name(), "US-ASCII");
+    ///
+    ///     ruby.enc_set_default_external(prev)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn enc_set_default_external<T>(&self, enc: T) -> Result<(), Error>
+    where
+        T: Into<Encoding>,
+    {
+        let enc = enc.into();
+        protect(|| unsafe {
+            rb_enc_set_default_external(enc.as_rb_value());
+            self.qnil()
+        })?;
+        Ok(())
+    }
+
+    /// Sets the default internal encoding, or unsets it if `enc` is `None`.
+    ///
+    /// Equivalent to `Encoding.default_internal = enc` in Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let prev = ruby.enc_default_internal();
+    ///
+    ///     ruby.enc_set_default_internal(Some(ruby.utf8_encoding().into()))?;
+    ///     assert_eq!(ruby.default_internal_encoding().unwrap().name(), "UTF-8");
+    ///
+    ///     ruby.enc_set_default_internal(None)?;
+    ///     assert!(ruby.default_internal_encoding().is_none());
+    ///
+    ///     ruby.enc_set_default_internal(prev)?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn enc_set_default_internal(&self, enc: Option<Encoding>) -> Result<(), Error> {
+        protect(|| unsafe {
+            rb_enc_set_default_internal(
+                enc.map_or_else(|| self.qnil().as_rb_value(), |e| e.as_rb_value()),
+            );
+            self.qnil()
+        })?;
+        Ok(())
+    }
+
+    /// Returns the name of the process' current locale character map, e.g.
+    /// `"UTF-8"`.
+    ///
+    /// This is the name reported by the operating system, so may not be the
+    /// name of an encoding known to Ruby. See
+    /// [`locale_encoding`](Ruby::locale_encoding) for the encoding Ruby uses
+    /// for the locale.
+    ///
+    /// Equivalent to `Encoding.locale_charmap` in Ruby.
+    pub fn locale_charmap(&self) -> RString {
+        unsafe {
+            RString::from_rb_value_unchecked(rb_locale_charmap(self.class_encoding().as_rb_value()))
+        }
+    }
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby's Encoding class.
@@ -12,8 +12,10 @@ use rb_sys::{
 use crate::{
     class::Class,
     exception::Exception,
-    into_value::IntoValue,
+    into_value::{ArgList, IntoValue},
     module::Module,
+    symbol::IntoSymbol,
+    trace,
     value::{private::ReprValue as _, ReprValue, Value},
     ExceptionClass, Ruby,
 };
@@ -83,6 +85,149 @@ impl Ruby {
         .unwrap_err()
     }
 
+    /// Create a new `KeyError` with `msg`, exposing `receiver` and `key` via
+    /// the exception's `receiver` and `key` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn fetch(ruby: &Ruby, hash: RHash, key: String) -> Result<i64, Error> {
+    ///     hash.lookup::<_, Option<i64>>(key.as_str())?.ok_or_else(|| {
+    ///         let msg = format!("key not found: {}", key);
+    ///         ruby.key_error_with(hash, key.as_str(), msg)
+    ///     })
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("fetch", function!(fetch, 2));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"
+    ///         h = {"a" => 1}
+    ///         begin
+    ///           fetch(h, "b")
+    ///         rescue KeyError => e
+    ///           e.receiver.equal?(h) && e.key == "b" && e.message == "key not found: b"
+    ///         end
+    ///         "#
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn key_error_with<R, K, T>(&self, receiver: R, key: K, msg: T) -> Error
+    where
+        R: IntoValue,
+        K: IntoValue,
+        T: IntoValue,
+    {
+        let kw = crate::kwargs!(self, "receiver" => receiver, "key" => key);
+        self.exception_with(self.exception_key_error(), (msg, kw))
+    }
+
+    /// Create a new `NameError` with `msg`, exposing `receiver` and `name`
+    /// via the exception's `receiver` and `name` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let obj = ruby.str_new("example");
+    ///     let err = ruby.name_error_with(obj, "foo", "undefined name foo");
+    ///     let e = err.value().unwrap();
+    ///     rb_assert!(ruby, "e.is_a?(NameError) && !e.is_a?(NoMethodError)", e);
+    ///     rb_assert!(ruby, "e.receiver.equal?(obj) && e.name == :foo", e, obj);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn name_error_with<R, N, T>(&self, receiver: R, name: N, msg: T) -> Error
+    where
+        R: IntoValue,
+        N: IntoSymbol,
+        T: IntoValue,
+    {
+        let name = name.into_symbol_with(self);
+        let kw = crate::kwargs!(self, "receiver" => receiver);
+        self.exception_with(self.exception_name_error(), (msg, name, kw))
+    }
+
+    /// Create a new `NoMethodError` with `msg`, exposing `receiver` and
+    /// `name` via the exception's `receiver` and `name` methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let obj = ruby.str_new("example");
+    ///     let err = ruby.no_method_error_with(obj, "foo", "undefined method foo");
+    ///     let e = err.value().unwrap();
+    ///     rb_assert!(ruby, "e.is_a?(NoMethodError)", e);
+    ///     rb_assert!(ruby, "e.receiver.equal?(obj) && e.name == :foo", e, obj);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn no_method_error_with<R, N, T>(&self, receiver: R, name: N, msg: T) -> Error
+    where
+        R: IntoValue,
+        N: IntoSymbol,
+        T: IntoValue,
+    {
+        let name = name.into_symbol_with(self);
+        let kw = crate::kwargs!(self, "receiver" => receiver);
+        self.exception_with(self.exception_no_method_error(), (msg, name, kw))
+    }
+
+    /// Create a new `FrozenError` with `msg`, exposing `receiver` via the
+    /// exception's `receiver` method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let obj = ruby.str_new("example");
+    ///     let err = ruby.frozen_error_with(obj, "can't modify frozen String");
+    ///     let e = err.value().unwrap();
+    ///     rb_assert!(ruby, "e.is_a?(FrozenError) && e.receiver.equal?(obj)", e, obj);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn frozen_error_with<R, T>(&self, receiver: R, msg: T) -> Error
+    where
+        R: IntoValue,
+        T: IntoValue,
+    {
+        let kw = crate::kwargs!(self, "receiver" => receiver);
+        self.exception_with(self.exception_frozen_error(), (msg, kw))
+    }
+
+    // Instantiate `class` with `args`. If that raises, the raised error is
+    // returned instead.
+    fn exception_with<A>(&self, class: ExceptionClass, args: A) -> Error
+    where
+        A: ArgList,
+    {
+        class
+            .new_instance(args)
+            .map(Error::from)
+            .unwrap_or_else(|e| e)
+    }
+
     /// Outputs `s` to Ruby's stderr if Ruby is configured to output warnings.
     pub fn warning(&self, s: &str) {
         let s = CString::new(s).unwrap();
@@ -285,6 +430,33 @@ impl Error {
         }
     }
 
+    /// Returns a new `Error` of the same class with `context` prepended to the
+    /// message, e.g. `"element 1: no implicit conversion of..."`.
+    ///
+    /// Exceptions are copied with Ruby's `Exception#exception`, so keep their
+    /// backtrace, cause, and other data such as `KeyError#key`. Jumps such as
+    /// `break` or `throw` are returned unchanged.
+    pub(crate) fn with_context<T>(self, context: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        match self.0 {
+            ErrorType::Jump(_) => self,
+            ErrorType::Error(class, msg) => Self::new(class, format!("{}: {}", context, msg)),
+            ErrorType::Exception(e) => {
+                let msg: String = match e.funcall("message", ()) {
+                    Ok(msg) => msg,
+                    Err(_) => return self,
+                };
+                let msg = format!("{}: {}", context, msg);
+                match e.funcall::<_, _, Exception>("exception", (msg,)) {
+                    Ok(e) => e.into(),
+                    Err(_) => self,
+                }
+            }
+        }
+    }
+
     /// Create an `Error` from the error value of [`std::panic::catch_unwind`].
     ///
     /// The Ruby Exception will be `fatal`, terminating the Ruby process, but
@@ -321,6 +493,59 @@ impl From<Exception> for Error {
 }
 
 /// Conversions into [`Error`].
+///
+/// Functions and methods bound to Ruby with [`function!`](crate::function)
+/// and [`method!`](crate::method) can return `Result<T, E>` for any `E`
+/// implementing `IntoError`. This allows a library's own error type to be
+/// mapped to Ruby exceptions in one place, rather than at every return site.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{error::IntoError, function, rb_assert, Error, Ruby};
+///
+/// enum ParseError {
+///     Empty,
+///     Invalid(char),
+/// }
+///
+/// impl IntoError for ParseError {
+///     fn into_error(self, ruby: &Ruby) -> Error {
+///         match self {
+///             Self::Empty => Error::new(ruby.exception_arg_error(), "empty input"),
+///             Self::Invalid(c) => Error::new(
+///                 ruby.exception_runtime_error(),
+///                 format!("invalid character {:?}", c),
+///             ),
+///         }
+///     }
+/// }
+///
+/// fn parse_digits(s: String) -> Result<u32, ParseError> {
+///     if s.is_empty() {
+///         return Err(ParseError::Empty);
+///     }
+///     s.chars().try_fold(0, |acc, c| {
+///         c.to_digit(10)
+///             .map(|d| acc * 10 + d)
+///             .ok_or(ParseError::Invalid(c))
+///     })
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("parse_digits", function!(parse_digits, 1));
+///
+///     rb_assert!(ruby, r#"parse_digits("42") == 42"#);
+///     rb_assert!(ruby, r#"(parse_digits("") rescue $!).is_a?(ArgumentError)"#);
+///     rb_assert!(
+///         ruby,
+///         r#"(parse_digits("4x") rescue $!).message == "invalid character 'x'""#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 pub trait IntoError {
     /// Convert `self` into [`Error`].
     fn into_error(self, ruby: &Ruby) -> Error;
@@ -450,6 +675,8 @@ where
     F: FnOnce() -> T,
     T: ReprValue,
 {
+    let _span = trace::protect();
+
     // nested function as this is totally unsafe to call out of this context
     // arg should not be a VALUE, but a mutable pointer to F, cast to VALUE
     unsafe extern "C" fn call<F, T>(arg: VALUE) -> VALUE
@@ -481,9 +708,31 @@ where
         rb_protect(Some(call::<F, T>), closure, &mut state as *mut c_int)
     };
 
+    protect_result(state, result).map(|v| unsafe { T::from_value_unchecked(v) })
+}
+
+/// As [`protect`], but passes `func` directly to Ruby, rather than going
+/// through an adapter to call a Rust closure.
+///
+/// # Safety
+///
+/// `func` must be safe to call with `arg`.
+pub(crate) unsafe fn protect_fn(
+    func: unsafe extern "C" fn(VALUE) -> VALUE,
+    arg: VALUE,
+) -> Result<Value> {
+    let _span = trace::protect();
+
+    // Tag::None
+    let mut state = 0;
+    let result = rb_protect(Some(func), arg, &mut state as *mut c_int);
+    protect_result(state, result)
+}
+
+fn protect_result(state: c_int, result: VALUE) -> Result<Value> {
     match state {
         // Tag::None
-        0 => unsafe { Ok(T::from_value_unchecked(Value::new(result))) },
+        0 => Ok(Value::new(result)),
         // Tag::Raise
         6 => unsafe {
             let ex = Exception::from_rb_value_unchecked(rb_errinfo());
@@ -494,6 +743,23 @@ where
     }
 }
 
+/// As [`protect`], but for closures returning types that aren't a Ruby value.
+///
+/// The closure's return value is stored on the Rust side, and `nil` is
+/// returned to Ruby.
+pub(crate) fn protect_returning<F, T>(func: F) -> Result<T>
+where
+    F: FnOnce() -> T,
+{
+    let mut out = None;
+    protect(|| {
+        out = Some(func());
+        unsafe { Ruby::get_unchecked() }.qnil()
+    })?;
+    // protect only returns Ok if the closure ran to completion
+    Ok(out.unwrap())
+}
+
 pub(crate) fn ensure<F1, F2, T>(func: F1, ensure: F2) -> T
 where
     F1: FnOnce() -> T,
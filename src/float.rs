@@ -28,6 +28,12 @@ use crate::{
 impl Ruby {
     /// Create a new `Float` from an `f64`.
     ///
+    /// Where Ruby supports flonums (most 64 bit platforms) values that fit in
+    /// a [`Flonum`](crate::value::Flonum) are returned as an immediate value,
+    /// without allocating. Other values, and all values on platforms without
+    /// flonums (such as 32 bit platforms), are allocated on Ruby's heap as an
+    /// [`RFloat`](crate::r_float::RFloat).
+    ///
     /// # Examples
     ///
     /// ```
@@ -65,6 +71,28 @@ impl Ruby {
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
 /// See [`Ruby`](Ruby#float) for methods to create a `Float`.
+///
+/// `Float` implements [`Display`](fmt::Display) with Ruby's `Float#to_s`, so
+/// [`to_string`](ToString::to_string) will give the same shortest
+/// representation Ruby would output, which may differ from the formatting of
+/// an `f64`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     assert_eq!(ruby.float_from_f64(1.0).to_string(), "1.0");
+///     assert_eq!(ruby.float_from_f64(1e20).to_string(), "1.0e+20");
+///     assert_eq!(ruby.float_from_f64(0.1 + 0.2).to_string(), "0.30000000000000004");
+///     assert_eq!(ruby.float_from_f64(f64::NEG_INFINITY).to_string(), "-Infinity");
+///     assert_eq!(ruby.float_from_f64(f64::NAN).to_string(), "NaN");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Float(NonZeroValue);
@@ -149,6 +177,41 @@ impl Float {
         unsafe { rb_float_value(self.as_rb_value()) }
     }
 
+    /// Convert `self` to a `f32`, returning `None` if the conversion would
+    /// lose precision.
+    ///
+    /// `NaN` converts to `NaN`, and infinities convert to the infinity of the
+    /// same sign, as these are exactly representable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Float, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let f: Float = ruby.eval("1.5")?;
+    ///     assert_eq!(f.to_f32_exact(), Some(1.5));
+    ///
+    ///     let f: Float = ruby.eval("0.1")?;
+    ///     assert_eq!(f.to_f32_exact(), None);
+    ///
+    ///     let f: Float = ruby.eval("1e100")?;
+    ///     assert_eq!(f.to_f32_exact(), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_f32_exact(self) -> Option<f32> {
+        let n = self.to_f64();
+        let f = n as f32;
+        if f64::from(f) == n || n.is_nan() {
+            Some(f)
+        } else {
+            None
+        }
+    }
+
     /// Returns a rational approximation of `self`.
     ///
     /// # Examples
@@ -0,0 +1,439 @@
+//! Types for working with the filesystem.
+//!
+//! These use Ruby's own path handling, so results match those of the
+//! equivalent Ruby code.
+//!
+//! See also [`Ruby`](Ruby#filesystem) for functions for working with the
+//! filesystem.
+
+use std::{
+    os::raw::c_int,
+    path::{Path, PathBuf},
+};
+
+use rb_sys::{rb_dir_getwd, rb_file_absolute_path, rb_file_expand_path};
+
+use crate::{
+    api::Ruby,
+    class::{Class, RClass},
+    error::{protect, Error},
+    module::Module,
+    r_object::RObject,
+    r_string::IntoRString,
+    try_convert::TryConvert,
+    value::{private::ReprValue as _, ReprValue, Value},
+};
+
+/// # Filesystem
+///
+/// Functions for working with the filesystem.
+///
+/// See also the [`fs`](crate::fs) module.
+impl Ruby {
+    /// Convert `path` to an absolute path, as with Ruby's `File.expand_path`.
+    ///
+    /// Relative paths are resolved from `dir`, or the current working
+    /// directory if `dir` is `None`. A leading `~` is expanded to the current
+    /// user's home directory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = ruby.file_expand_path("../bar", Some(Path::new("/foo/baz")))?;
+    ///     assert_eq!(path, PathBuf::from("/foo/bar"));
+    ///
+    ///     let home: PathBuf = ruby.eval("Dir.home")?;
+    ///     assert_eq!(ruby.file_expand_path("~", None)?, home);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(unix)]
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn file_expand_path<P>(&self, path: P, dir: Option<&Path>) -> Result<PathBuf, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().into_r_string_with(self);
+        let dir = dir.map_or_else(
+            || self.qnil().as_value(),
+            |d| d.into_r_string_with(self).as_value(),
+        );
+        protect(|| unsafe {
+            Value::new(rb_file_expand_path(path.as_rb_value(), dir.as_rb_value()))
+        })
+        .and_then(TryConvert::try_convert)
+    }
+
+    /// Convert `path` to an absolute path, as with Ruby's
+    /// `File.absolute_path`.
+    ///
+    /// Relative paths are resolved from `dir`, or the current working
+    /// directory if `dir` is `None`. Unlike
+    /// [`file_expand_path`](Ruby::file_expand_path) a leading `~` is not
+    /// expanded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = ruby.file_absolute_path("~", Some(Path::new("/foo")))?;
+    ///     assert_eq!(path, PathBuf::from("/foo/~"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(unix)]
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn file_absolute_path<P>(&self, path: P, dir: Option<&Path>) -> Result<PathBuf, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().into_r_string_with(self);
+        let dir = dir.map_or_else(
+            || self.qnil().as_value(),
+            |d| d.into_r_string_with(self).as_value(),
+        );
+        protect(|| unsafe {
+            Value::new(rb_file_absolute_path(path.as_rb_value(), dir.as_rb_value()))
+        })
+        .and_then(TryConvert::try_convert)
+    }
+
+    /// Returns the real absolute path of `path`, as with Ruby's
+    /// `File.realpath`.
+    ///
+    /// Relative paths are resolved from `dir`, or the current working
+    /// directory if `dir` is `None`. All components of the path must exist,
+    /// and symbolic links are resolved.
+    ///
+    /// Errors if the path does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let dir = std::env::temp_dir();
+    ///     let path = ruby.file_realpath(".", Some(&dir))?;
+    ///     assert_eq!(path, dir.canonicalize().unwrap());
+    ///
+    ///     assert!(ruby.file_realpath("does_not_exist", Some(&dir)).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(unix)]
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn file_realpath<P>(&self, path: P, dir: Option<&Path>) -> Result<PathBuf, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().into_r_string_with(self);
+        match dir {
+            Some(dir) => self
+                .class_file()
+                .funcall("realpath", (path, dir.into_r_string_with(self))),
+            None => self.class_file().funcall("realpath", (path,)),
+        }
+    }
+
+    /// Returns the paths matching `pattern`, as with Ruby's `Dir.glob`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{fs::GlobFlags, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let dir = std::env::temp_dir().join("magnus_dir_glob");
+    ///     std::fs::create_dir_all(&dir).unwrap();
+    ///     std::fs::write(dir.join("a.txt"), "").unwrap();
+    ///     std::fs::write(dir.join(".b.txt"), "").unwrap();
+    ///
+    ///     let pattern = dir.join("*.txt");
+    ///     let paths = ruby.dir_glob(&pattern, GlobFlags::new())?;
+    ///     assert_eq!(paths, vec![dir.join("a.txt")]);
+    ///
+    ///     let mut paths = ruby.dir_glob(&pattern, GlobFlags::DOTMATCH)?;
+    ///     paths.sort();
+    ///     assert_eq!(paths, vec![dir.join(".b.txt"), dir.join("a.txt")]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # #[cfg(unix)]
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn dir_glob<P>(&self, pattern: P, flags: GlobFlags) -> Result<Vec<PathBuf>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let pattern = pattern.as_ref().into_r_string_with(self);
+        self.class_dir().funcall("glob", (pattern, flags.0))
+    }
+
+    /// Returns the current working directory, as with Ruby's `Dir.pwd`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.dir_getwd()?, std::env::current_dir().unwrap());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn dir_getwd(&self) -> Result<PathBuf, Error> {
+        protect(|| unsafe { Value::new(rb_dir_getwd()) }).and_then(TryConvert::try_convert)
+    }
+
+    /// Create a new Ruby `Pathname` from `path`.
+    ///
+    /// This will require Ruby's `pathname` library if it has not already been
+    /// loaded.
+    ///
+    /// A `Pathname` can be converted back to a [`PathBuf`] with
+    /// [`TryConvert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    ///
+    /// use magnus::{prelude::*, rb_assert, Error, Ruby, TryConvert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let pathname = ruby.pathname_new("/foo/bar")?;
+    ///     rb_assert!(ruby, r#"pathname == Pathname.new("/foo/bar")"#, pathname);
+    ///     rb_assert!(ruby, r#"pathname.basename.to_s == "bar""#, pathname);
+    ///
+    ///     let path = PathBuf::try_convert(pathname.as_value())?;
+    ///     assert_eq!(path, PathBuf::from("/foo/bar"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn pathname_new<P>(&self, path: P) -> Result<RObject, Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.require("pathname")?;
+        let class: RClass = self.class_object().const_get("Pathname")?;
+        class
+            .new_instance((path.as_ref().into_r_string_with(self),))
+            .and_then(TryConvert::try_convert)
+    }
+
+    /// Returns the path of the library or executable containing Magnus, e.g.
+    /// the `.so`/`.bundle`/`.dll` file of a native extension gem.
+    ///
+    /// Returns `Err` containing a `RuntimeError` if the path can't be
+    /// determined. When embedding Ruby, with the `embed` feature enabled, this
+    /// falls back to the path of the current executable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     // when embedding Ruby this is the path of the executable
+    ///     let path = ruby.extension_path()?;
+    ///     assert!(path.is_absolute());
+    ///     assert!(path.is_file());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn extension_path(&self) -> Result<PathBuf, Error> {
+        let path = library_path().filter(|path| !path.as_os_str().is_empty());
+        // in a native extension a fallback would give the path of the Ruby
+        // interpreter, rather than the extension
+        #[cfg(feature = "embed")]
+        let path = path.or_else(|| std::env::current_exe().ok());
+        let path = path.ok_or_else(|| {
+            Error::new(
+                self.exception_runtime_error(),
+                "could not determine extension path",
+            )
+        })?;
+        if path.is_absolute() {
+            Ok(path)
+        } else {
+            std::fs::canonicalize(&path)
+                .map_err(|e| Error::new(self.exception_runtime_error(), e.to_string()))
+        }
+    }
+
+    /// Finds and loads the feature `name`, relative to the directory of the
+    /// native extension, if not already loaded.
+    ///
+    /// This is useful for loading Ruby files shipped alongside a native
+    /// extension, similar to Ruby's `require_relative`. See
+    /// [`extension_path`](Ruby::extension_path).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let dir = ruby.extension_path()?.parent().unwrap().to_owned();
+    ///     let file = dir.join("magnus_sibling_example.rb");
+    ///     std::fs::write(&file, "MAGNUS_SIBLING = 42").unwrap();
+    ///
+    ///     let res = ruby.require_sibling("magnus_sibling_example");
+    ///     std::fs::remove_file(&file).unwrap();
+    ///     assert!(res?);
+    ///
+    ///     rb_assert!(ruby, "MAGNUS_SIBLING == 42");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn require_sibling<P>(&self, name: P) -> Result<bool, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = self.extension_path()?;
+        let dir = path.parent().unwrap_or(&path);
+        self.require(dir.join(name))
+    }
+}
+
+// Returns the path of the shared library (or executable) containing this
+// function.
+#[cfg(unix)]
+fn library_path() -> Option<PathBuf> {
+    use std::{
+        ffi::{c_void, CStr, OsStr},
+        os::{
+            raw::{c_char, c_int},
+            unix::ffi::OsStrExt,
+        },
+        ptr,
+    };
+
+    #[repr(C)]
+    struct DlInfo {
+        dli_fname: *const c_char,
+        dli_fbase: *mut c_void,
+        dli_sname: *const c_char,
+        dli_saddr: *mut c_void,
+    }
+
+    #[cfg_attr(target_os = "linux", link(name = "dl"))]
+    extern "C" {
+        fn dladdr(addr: *const c_void, info: *mut DlInfo) -> c_int;
+    }
+
+    let mut info = DlInfo {
+        dli_fname: ptr::null(),
+        dli_fbase: ptr::null_mut(),
+        dli_sname: ptr::null(),
+        dli_saddr: ptr::null_mut(),
+    };
+    unsafe {
+        if dladdr(library_path as *const c_void, &mut info) == 0 || info.dli_fname.is_null() {
+            return None;
+        }
+        let bytes = CStr::from_ptr(info.dli_fname).to_bytes();
+        Some(PathBuf::from(OsStr::from_bytes(bytes)))
+    }
+}
+
+#[cfg(windows)]
+fn library_path() -> Option<PathBuf> {
+    use std::{ffi::c_void, os::windows::ffi::OsStringExt, ptr};
+
+    const GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT: u32 = 0x2;
+    const GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS: u32 = 0x4;
+
+    extern "system" {
+        fn GetModuleHandleExW(flags: u32, name: *const u16, module: *mut *mut c_void) -> i32;
+        fn GetModuleFileNameW(module: *mut c_void, filename: *mut u16, size: u32) -> u32;
+    }
+
+    unsafe {
+        let mut module = ptr::null_mut();
+        if GetModuleHandleExW(
+            GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+            library_path as *const u16,
+            &mut module,
+        ) == 0
+        {
+            return None;
+        }
+        let mut buf = vec![0u16; 32768];
+        let len = GetModuleFileNameW(module, buf.as_mut_ptr(), buf.len() as u32) as usize;
+        if len == 0 || len >= buf.len() {
+            return None;
+        }
+        Some(std::ffi::OsString::from_wide(&buf[..len]).into())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn library_path() -> Option<PathBuf> {
+    None
+}
+
+const FNM_NOESCAPE: c_int = 0x01;
+const FNM_DOTMATCH: c_int = 0x04;
+const FNM_CASEFOLD: c_int = 0x08;
+
+/// Argument type for [`Ruby::dir_glob`].
+#[derive(Clone, Copy)]
+pub struct GlobFlags(c_int);
+
+impl GlobFlags {
+    /// An instance of `GlobFlags` with only `NOESCAPE` set.
+    pub const NOESCAPE: Self = Self::new().noescape();
+    /// An instance of `GlobFlags` with only `DOTMATCH` set.
+    pub const DOTMATCH: Self = Self::new().dotmatch();
+    /// An instance of `GlobFlags` with only `CASEFOLD` set.
+    pub const CASEFOLD: Self = Self::new().casefold();
+
+    /// Create a new `GlobFlags` with no flags set.
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Set the `NOESCAPE` flag, backslashes are not treated as escapes.
+    pub const fn noescape(self) -> Self {
+        Self(self.0 | FNM_NOESCAPE)
+    }
+
+    /// Set the `DOTMATCH` flag, wildcards match files beginning with `.`.
+    pub const fn dotmatch(self) -> Self {
+        Self(self.0 | FNM_DOTMATCH)
+    }
+
+    /// Set the `CASEFOLD` flag, matching is case insensitive on platforms
+    /// where this is supported.
+    pub const fn casefold(self) -> Self {
+        Self(self.0 | FNM_CASEFOLD)
+    }
+}
+
+impl Default for GlobFlags {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,7 +2,11 @@
 //!
 //! See also [`Ruby`](Ruby#gc) for more GC related methods.
 
-use std::{marker::PhantomData, ops::Range};
+use std::{
+    marker::PhantomData,
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use rb_sys::{
     rb_gc_adjust_memory_usage, rb_gc_count, rb_gc_disable, rb_gc_enable, rb_gc_location,
@@ -61,6 +65,9 @@ impl<T> Mark for crate::value::Opaque<T> where T: ReprValue {}
 
 /// A handle to GC marking functions.
 ///
+/// A `Marker` is only available during the GC marking phase, and only exposes
+/// the functions that are valid to call during marking.
+///
 /// See also
 /// [`DataTypeFunctions::mark`](`crate::typed_data::DataTypeFunctions::mark`).
 pub struct Marker(PhantomData<*mut ()>);
@@ -179,6 +186,135 @@ impl Compactor {
     }
 }
 
+/// Tracks memory allocated outside of Ruby's heap, reporting changes to
+/// Ruby's garbage collector.
+///
+/// This is a convenience wrapper around [`Ruby::gc_adjust_memory_usage`] for
+/// wrapped types that own a changing amount of memory, such as a buffer. Any
+/// memory still reported when this is dropped is reported as freed.
+///
+/// The tracked size can be included in
+/// [`DataTypeFunctions::size`](crate::typed_data::DataTypeFunctions::size),
+/// so that `ObjectSpace.memsize_of` and memory profilers report accurate
+/// numbers for the wrapped type.
+///
+/// # Examples
+///
+/// ```
+/// use std::{cell::RefCell, mem::size_of_val};
+///
+/// use magnus::{
+///     function, gc::ExternalMemory, method, prelude::*, rb_assert, DataTypeFunctions, Error,
+///     Ruby, TypedData,
+/// };
+///
+/// #[derive(TypedData)]
+/// #[magnus(class = "Buffer", size, free_immediately)]
+/// struct Buffer {
+///     data: RefCell<Vec<u8>>,
+///     mem: ExternalMemory,
+/// }
+///
+/// impl Buffer {
+///     fn new(ruby: &Ruby, capacity: usize) -> Self {
+///         let data = Vec::with_capacity(capacity);
+///         let mem = ExternalMemory::new();
+///         mem.set(ruby, data.capacity());
+///         Self {
+///             data: RefCell::new(data),
+///             mem,
+///         }
+///     }
+///
+///     fn clear(ruby: &Ruby, rb_self: &Self) {
+///         let mut data = rb_self.data.borrow_mut();
+///         *data = Vec::new();
+///         rb_self.mem.set(ruby, data.capacity());
+///     }
+/// }
+///
+/// impl DataTypeFunctions for Buffer {
+///     fn size(&self) -> usize {
+///         size_of_val(self) + self.mem.get()
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Buffer", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Buffer::new, 1))?;
+///     class.define_method("clear", method!(Buffer::clear, 0))?;
+///
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         require "objspace"
+///         buf = Buffer.new(1024)
+///         before = ObjectSpace.memsize_of(buf)
+///         buf.clear
+///         before > 1024 && ObjectSpace.memsize_of(buf) < before
+///         "#
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Debug, Default)]
+pub struct ExternalMemory(AtomicUsize);
+
+impl ExternalMemory {
+    /// Create a new `ExternalMemory`, tracking zero bytes.
+    pub const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    /// Returns the number of bytes currently tracked.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Set the number of bytes tracked to `size`, reporting the difference
+    /// from the previous size to Ruby.
+    pub fn set(&self, handle: &Ruby, size: usize) {
+        let prev = self.0.swap(size, Ordering::Relaxed);
+        handle.gc_adjust_memory_usage(size as isize - prev as isize);
+    }
+
+    /// Increase the number of bytes tracked by `size`, reporting the
+    /// allocation to Ruby.
+    pub fn grow(&self, handle: &Ruby, size: usize) {
+        self.0.fetch_add(size, Ordering::Relaxed);
+        handle.gc_adjust_memory_usage(size as isize);
+    }
+
+    /// Decrease the number of bytes tracked by `size`, reporting the memory
+    /// as freed to Ruby.
+    ///
+    /// The tracked size will not go below zero.
+    pub fn shrink(&self, handle: &Ruby, size: usize) {
+        let prev = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_sub(size))
+            })
+            .unwrap_or_else(|n| n);
+        handle.gc_adjust_memory_usage(-(prev.min(size) as isize));
+    }
+}
+
+impl Drop for ExternalMemory {
+    fn drop(&mut self) {
+        let size = *self.0.get_mut();
+        if size == 0 {
+            return;
+        }
+        // can't report to Ruby if dropped on a non-Ruby thread
+        if let Ok(handle) = Ruby::get() {
+            handle.gc_adjust_memory_usage(-(size as isize));
+        }
+    }
+}
+
 /// Registers `value` to never be garbage collected.
 ///
 /// This is essentially a deliberate memory leak.
@@ -1,13 +1,21 @@
 use std::{
+    ffi::CString,
     fmt,
-    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
-    os::raw::c_long,
+    mem::size_of,
+    ops::{
+        Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+        DivAssign, Mul, MulAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+    },
+    os::raw::{c_int, c_long, c_void},
+    ptr,
 };
 
 use rb_sys::{
-    rb_big_cmp, rb_big_div, rb_big_eq, rb_big_minus, rb_big_mul, rb_big_norm, rb_big_plus,
-    rb_int2big, rb_ll2inum, rb_to_int, rb_ull2inum, ruby_special_consts, ruby_value_type, Qtrue,
-    VALUE,
+    rb_absint_numwords, rb_big2str, rb_big_and, rb_big_cmp, rb_big_div, rb_big_eq, rb_big_lshift,
+    rb_big_minus, rb_big_mul, rb_big_norm, rb_big_or, rb_big_plus, rb_big_rshift, rb_big_xor,
+    rb_cstr_to_inum, rb_fix2str, rb_int2big, rb_integer_pack, rb_integer_unpack, rb_ll2inum,
+    rb_to_int, rb_ull2inum, ruby_special_consts, ruby_value_type, Qtrue, INTEGER_PACK_LSWORD_FIRST,
+    INTEGER_PACK_NATIVE, INTEGER_PACK_NEGATIVE, VALUE,
 };
 
 use crate::{
@@ -15,6 +23,7 @@ use crate::{
     into_value::IntoValue,
     numeric::Numeric,
     r_bignum::RBignum,
+    r_string::RString,
     try_convert::TryConvert,
     value::{
         private::{self, ReprValue as _},
@@ -23,6 +32,10 @@ use crate::{
     Ruby,
 };
 
+// Words are least significant first, with the bytes of each word in native
+// order, matching a `&[u32]` of little-endian 'digits'.
+const WORDS_LE: c_int = (INTEGER_PACK_LSWORD_FIRST | INTEGER_PACK_NATIVE) as c_int;
+
 pub(crate) enum IntegerType {
     Fixnum(Fixnum),
     Bignum(RBignum),
@@ -97,6 +110,102 @@ impl Ruby {
             )
         }
     }
+
+    /// Parse `s` as an `Integer` in the given `base`.
+    ///
+    /// This follows the same rules as Ruby's `Integer(s, base)`, so leading
+    /// and trailing whitespace, underscores between digits, and a prefix
+    /// matching `base` (such as `0x` for base 16) are allowed. If `base` is
+    /// `0` the base is determined from the prefix, defaulting to base 10.
+    ///
+    /// Unlike [`i64::from_str_radix`] the result is not limited in size.
+    ///
+    /// Returns `Err` if `s` is not a valid integer, or `base` is not `0` or
+    /// between `2` and `36`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let i = ruby.integer_from_str_radix("ff", 16)?;
+    ///     assert_eq!(i.to_i64()?, 255);
+    ///
+    ///     let i = ruby.integer_from_str_radix("0b1010_1010", 2)?;
+    ///     assert_eq!(i.to_i64()?, 170);
+    ///
+    ///     let i = ruby.integer_from_str_radix("0o777", 0)?;
+    ///     assert_eq!(i.to_i64()?, 511);
+    ///
+    ///     let i = ruby.integer_from_str_radix("123456789012345678901234567890", 10)?;
+    ///     rb_assert!(ruby, "i == 123456789012345678901234567890", i);
+    ///
+    ///     assert!(ruby.integer_from_str_radix("12z", 10).is_err());
+    ///     assert!(ruby.integer_from_str_radix("178", 8).is_err());
+    ///     assert!(ruby.integer_from_str_radix("12", 37).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_str_radix(&self, s: &str, base: u32) -> Result<Integer, Error> {
+        let s = CString::new(s).map_err(|e| {
+            Error::new(
+                self.exception_arg_error(),
+                format!("string contains null byte at {}", e.nul_position()),
+            )
+        })?;
+        let base = c_int::try_from(base).unwrap_or(c_int::MAX);
+        protect(|| unsafe {
+            Integer::from_rb_value_unchecked(rb_cstr_to_inum(s.as_ptr(), base, 1))
+        })
+    }
+
+    /// Create a new `Integer` from the absolute value `words` and a sign.
+    ///
+    /// `words` are 32 bit 'digits', least significant first, so
+    /// `[0, 1]` is `2**32`. The value is negated if `negative` is `true`.
+    ///
+    /// This is the inverse of [`Integer::to_words_le`], and allows
+    /// arbitrary precision integers to be exchanged with Ruby without going
+    /// via a string or an external crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let i = ruby.integer_from_words_le(&[], false);
+    ///     rb_assert!(ruby, "i == 0", i);
+    ///
+    ///     let i = ruby.integer_from_words_le(&[1, 2], false);
+    ///     rb_assert!(ruby, "i == 2 * 2**32 + 1", i);
+    ///
+    ///     let i = ruby.integer_from_words_le(&[0, 0, 1], true);
+    ///     rb_assert!(ruby, "i == -(2**64)", i);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn integer_from_words_le(&self, words: &[u32], negative: bool) -> Integer {
+        let flags = if negative {
+            WORDS_LE | INTEGER_PACK_NEGATIVE as c_int
+        } else {
+            WORDS_LE
+        };
+        unsafe {
+            Integer::from_rb_value_unchecked(rb_integer_unpack(
+                words.as_ptr() as *const c_void,
+                words.len(),
+                size_of::<u32>(),
+                0,
+                flags,
+            ))
+        }
+    }
 }
 
 /// A type wrapping either a [`Fixnum`] or a [`RBignum`].
@@ -502,6 +611,273 @@ impl Integer {
         }
     }
 
+    /// Returns `self` raised to the power `exp`, modulo `modulus`.
+    ///
+    /// This is equivalent to Ruby's `Integer#pow(exp, modulus)`, and is
+    /// significantly faster than calculating `self ** exp % modulus`.
+    ///
+    /// Errors if `exp` is negative or `modulus` is zero.
+    ///
+    /// When all arguments are Fixnums this is calculated in Rust, otherwise
+    /// it calls Ruby's `Integer#pow` method, as the C function backing it is
+    /// not part of Ruby's public API. Expect the overhead of a method call
+    /// in the Bignum case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let i = ruby.integer_from_i64(4);
+    ///     let res = i.pow_mod(ruby.integer_from_i64(13), ruby.integer_from_i64(497))?;
+    ///     assert_eq!(res.to_i64()?, 445);
+    ///
+    ///     assert!(i
+    ///         .pow_mod(ruby.integer_from_i64(2), ruby.integer_from_i64(0))
+    ///         .is_err());
+    ///
+    ///     // like Ruby's `%`, the result takes the sign of `modulus`
+    ///     let i = ruby.integer_from_i64(3);
+    ///     let res = i.pow_mod(ruby.integer_from_i64(2), ruby.integer_from_i64(-5))?;
+    ///     assert_eq!(res.to_i64()?, -1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn pow_mod(self, exp: Self, modulus: Self) -> Result<Self, Error> {
+        match (
+            self.integer_type(),
+            exp.integer_type(),
+            modulus.integer_type(),
+        ) {
+            (IntegerType::Fixnum(base), IntegerType::Fixnum(exp), IntegerType::Fixnum(modulus))
+                if exp.to_i64() >= 0 && modulus.to_i64() != 0 =>
+            {
+                // Fixnums are at most 63 bits, so the product of two values
+                // less than the modulus can't overflow a u128
+                let m = modulus.to_i64().unsigned_abs() as u128;
+                let mut base = base.to_i64().rem_euclid(m as i64) as u128;
+                let mut exp = exp.to_i64() as u64;
+                let mut res = 1 % m;
+                while exp > 0 {
+                    if exp & 1 == 1 {
+                        res = res * base % m;
+                    }
+                    base = base * base % m;
+                    exp >>= 1;
+                }
+                let mut res = res as i64;
+                if modulus.to_i64() < 0 && res != 0 {
+                    res -= m as i64;
+                }
+                Ok(Ruby::get_with(self).integer_from_i64(res))
+            }
+            _ => self.funcall("pow", (exp, modulus)),
+        }
+    }
+
+    /// Returns the greatest common divisor of `self` and `other`.
+    ///
+    /// The result is always positive, or zero if both `self` and `other` are
+    /// zero.
+    ///
+    /// When both `self` and `other` are Fixnums this is calculated in Rust,
+    /// otherwise it calls Ruby's `Integer#gcd` method, as the C function
+    /// backing it is not part of Ruby's public API.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let i = ruby.integer_from_i64(-36);
+    ///     assert_eq!(i.gcd(ruby.integer_from_i64(60))?.to_i64()?, 12);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn gcd(self, other: Self) -> Result<Self, Error> {
+        match (self.integer_type(), other.integer_type()) {
+            (IntegerType::Fixnum(a), IntegerType::Fixnum(b)) => {
+                // Fixnums are at most 63 bits, so unsigned_abs can't overflow
+                let (mut a, mut b) = (a.to_i64().unsigned_abs(), b.to_i64().unsigned_abs());
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                Ok(Ruby::get_with(self).integer_from_u64(a))
+            }
+            _ => self.funcall("gcd", (other,)),
+        }
+    }
+
+    /// Returns the number of bits needed to represent `self`, excluding the
+    /// sign bit.
+    ///
+    /// This is equivalent to Ruby's `Integer#bit_length`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.integer_from_i64(0).bit_length()?, 0);
+    ///     assert_eq!(ruby.integer_from_i64(255).bit_length()?, 8);
+    ///     assert_eq!(ruby.integer_from_i64(-256).bit_length()?, 8);
+    ///     assert_eq!(ruby.integer_from_u64(u64::MAX).bit_length()?, 64);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn bit_length(self) -> Result<usize, Error> {
+        match self.integer_type() {
+            IntegerType::Fixnum(fix) => {
+                let i = fix.to_i64();
+                let i = if i < 0 { !i } else { i };
+                Ok((i64::BITS - i.leading_zeros()) as usize)
+            }
+            IntegerType::Bignum(_) => self.funcall("bit_length", ()),
+        }
+    }
+
+    /// Returns whether `self` is odd.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.integer_from_i64(-3).is_odd());
+    ///     assert!(!ruby.integer_from_u64(u64::MAX - 1).is_odd());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_odd(self) -> bool {
+        match self.integer_type() {
+            IntegerType::Fixnum(fix) => fix.to_i64() & 1 == 1,
+            IntegerType::Bignum(big) => {
+                let one = Ruby::get_with(self).integer_from_i64(1);
+                let result = unsafe {
+                    Integer::from_rb_value_unchecked(rb_big_and(
+                        big.as_rb_value(),
+                        one.as_rb_value(),
+                    ))
+                };
+                result == one
+            }
+        }
+    }
+
+    /// Returns whether `self` is even.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.integer_from_i64(-4).is_even());
+    ///     assert!(!ruby.integer_from_u64(u64::MAX).is_even());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_even(self) -> bool {
+        !self.is_odd()
+    }
+
+    /// Returns a string representing `self` in the given `base`.
+    ///
+    /// This is equivalent to Ruby's `Integer#to_s(base)`, digits above `9`
+    /// are represented by lowercase letters.
+    ///
+    /// Returns `Err` if `base` is not between `2` and `36`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.integer_from_i64(255).to_string_radix(16)?, "ff");
+    ///     assert_eq!(ruby.integer_from_i64(-5).to_string_radix(2)?, "-101");
+    ///     assert_eq!(
+    ///         ruby.integer_from_u64(u64::MAX).to_string_radix(36)?,
+    ///         "3w5e11264sgsf"
+    ///     );
+    ///
+    ///     assert!(ruby.integer_from_i64(1).to_string_radix(37).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_string_radix(self, base: u32) -> Result<String, Error> {
+        let base = c_int::try_from(base).unwrap_or(c_int::MAX);
+        protect(|| unsafe {
+            RString::from_rb_value_unchecked(match self.integer_type() {
+                IntegerType::Fixnum(fix) => rb_fix2str(fix.as_rb_value(), base),
+                IntegerType::Bignum(big) => rb_big2str(big.as_rb_value(), base),
+            })
+        })?
+        .to_string()
+    }
+
+    /// Returns the absolute value of `self` as 32 bit 'digits', least
+    /// significant first.
+    ///
+    /// The result has no trailing zero words, so `0` returns an empty `Vec`.
+    /// The sign is not included, it can be checked by comparing `self` with
+    /// zero. See [`Ruby::integer_from_words_le`] for the inverse.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Integer, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.integer_from_i64(0).to_words_le().is_empty());
+    ///     assert_eq!(ruby.integer_from_i64(-5).to_words_le(), vec![5]);
+    ///     assert_eq!(
+    ///         ruby.integer_from_u64(u64::MAX).to_words_le(),
+    ///         vec![u32::MAX, u32::MAX]
+    ///     );
+    ///
+    ///     let i: Integer = ruby.eval("-(2**100 + 7)")?;
+    ///     let words = i.to_words_le();
+    ///     assert_eq!(words, vec![7, 0, 0, 16]);
+    ///     assert!(ruby.integer_from_words_le(&words, true) == i);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_words_le(self) -> Vec<u32> {
+        let len =
+            unsafe { rb_absint_numwords(self.as_rb_value(), u32::BITS as usize, ptr::null_mut()) };
+        let mut words = vec![0; len];
+        unsafe {
+            rb_integer_pack(
+                self.as_rb_value(),
+                words.as_mut_ptr() as *mut c_void,
+                words.len(),
+                size_of::<u32>(),
+                0,
+                WORDS_LE,
+            )
+        };
+        words
+    }
+
     fn binary_operation_visit<T>(
         &self,
         other: &Self,
@@ -708,3 +1084,122 @@ impl DivAssign for Integer {
         *self = *self / other;
     }
 }
+
+impl BitAnd for Integer {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() & b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_and(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitAndAssign for Integer {
+    fn bitand_assign(&mut self, other: Self) {
+        *self = *self & other;
+    }
+}
+
+impl BitOr for Integer {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() | b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_or(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitOrAssign for Integer {
+    fn bitor_assign(&mut self, other: Self) {
+        *self = *self | other;
+    }
+}
+
+impl BitXor for Integer {
+    type Output = Self;
+
+    fn bitxor(self, other: Self) -> Self {
+        self.binary_operation_visit(
+            &other,
+            |a, b| Ruby::get_with(a).integer_from_i64(a.to_i64() ^ b.to_i64()),
+            |a, b| {
+                let result = unsafe { rb_big_xor(a, b) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            },
+        )
+    }
+}
+
+impl BitXorAssign for Integer {
+    fn bitxor_assign(&mut self, other: Self) {
+        *self = *self ^ other;
+    }
+}
+
+impl Shl<u32> for Integer {
+    type Output = Self;
+
+    fn shl(self, bits: u32) -> Self {
+        let ruby = Ruby::get_with(self);
+        match self.integer_type() {
+            IntegerType::Fixnum(a) => {
+                let a = a.to_i64();
+                match a.checked_shl(bits) {
+                    Some(result) if result >> bits == a => ruby.integer_from_i64(result),
+                    _ => {
+                        let a = unsafe { rb_int2big(a as isize) };
+                        let bits = ruby.integer_from_u64(bits as u64);
+                        let result = unsafe { rb_big_lshift(a, bits.as_rb_value()) };
+                        unsafe { Integer::from_rb_value_unchecked(result) }
+                    }
+                }
+            }
+            IntegerType::Bignum(a) => {
+                let bits = ruby.integer_from_u64(bits as u64);
+                let result = unsafe { rb_big_lshift(a.as_rb_value(), bits.as_rb_value()) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            }
+        }
+    }
+}
+
+impl ShlAssign<u32> for Integer {
+    fn shl_assign(&mut self, bits: u32) {
+        *self = *self << bits;
+    }
+}
+
+impl Shr<u32> for Integer {
+    type Output = Self;
+
+    fn shr(self, bits: u32) -> Self {
+        let ruby = Ruby::get_with(self);
+        match self.integer_type() {
+            // shifting by 63 or more bits leaves only the sign
+            IntegerType::Fixnum(a) => ruby.integer_from_i64(a.to_i64() >> bits.min(63)),
+            IntegerType::Bignum(a) => {
+                let bits = ruby.integer_from_u64(bits as u64);
+                let result = unsafe { rb_big_rshift(a.as_rb_value(), bits.as_rb_value()) };
+                unsafe { Integer::from_rb_value_unchecked(result) }
+            }
+        }
+    }
+}
+
+impl ShrAssign<u32> for Integer {
+    fn shr_assign(&mut self, bits: u32) {
+        *self = *self >> bits;
+    }
+}
@@ -0,0 +1,528 @@
+//! Types for working with Ruby's `IO::Buffer` class.
+
+use std::{fmt, os::raw::c_void, ptr, slice};
+
+use rb_sys::{
+    rb_cIOBuffer, rb_io_buffer_flags, rb_io_buffer_free, rb_io_buffer_lock, rb_io_buffer_new,
+    rb_io_buffer_unlock,
+};
+#[cfg(ruby_gte_3_2)]
+use rb_sys::{rb_io_buffer_get_bytes_for_reading, rb_io_buffer_get_bytes_for_writing};
+#[cfg(ruby_lt_3_2)]
+use rb_sys::{rb_io_buffer_get_immutable, rb_io_buffer_get_mutable};
+
+use crate::{
+    class::{Class, RClass},
+    error::{protect, Error},
+    into_value::IntoValue,
+    object::Object,
+    r_typed_data::RTypedData,
+    try_convert::TryConvert,
+    value::{
+        private::{self, ReprValue as _},
+        ReprValue, Value,
+    },
+    Ruby,
+};
+
+/// # `IO::Buffer`
+///
+/// Functions that can be used to create Ruby `IO::Buffer`s.
+///
+/// See also the [`IoBuffer`] type.
+impl Ruby {
+    /// Create a new zeroed `IO::Buffer` of `size` bytes, as with Ruby's
+    /// `IO::Buffer.new(size)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_new(8)?;
+    ///     assert_eq!(buf.len()?, 8);
+    ///     assert_eq!(buf.get_bytes(0, 8)?, vec![0; 8]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn io_buffer_new(&self, size: usize) -> Result<IoBuffer, Error> {
+        self.io_buffer_class()
+            .new_instance((size,))
+            .and_then(TryConvert::try_convert)
+    }
+
+    /// Create a new `IO::Buffer` containing a copy of `bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_from_slice(b"hello")?;
+    ///     rb_assert!(ruby, r#"buf.get_string == "hello""#, buf);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn io_buffer_from_slice(&self, bytes: &[u8]) -> Result<IoBuffer, Error> {
+        let buf = self.io_buffer_new(bytes.len())?;
+        buf.set_bytes(0, bytes)?;
+        Ok(buf)
+    }
+
+    /// Create a new `IO::Buffer` directly referencing the memory of `bytes`,
+    /// without copying.
+    ///
+    /// Changes to the buffer from Ruby are written directly to `bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let bytes = Box::leak(Box::new(*b"hello"));
+    ///     let buf = ruby.io_buffer_from_static(bytes)?;
+    ///     rb_assert!(ruby, r#"buf.external?"#, buf);
+    ///     rb_assert!(ruby, r#"buf.get_string == "hello""#, buf);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn io_buffer_from_static(&self, bytes: &'static mut [u8]) -> Result<IoBuffer, Error> {
+        unsafe { self.io_buffer_from_raw_parts(bytes.as_mut_ptr(), bytes.len()) }
+    }
+
+    /// Create a new `IO::Buffer` directly referencing `len` bytes of memory
+    /// starting at `ptr`, without copying.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be valid for reads and writes of `len` bytes for as long as
+    /// the buffer is in use, and must not be otherwise accessed during that
+    /// time. The buffer can be detached from the memory with
+    /// [`IoBuffer::free`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let mut bytes = *b"hello";
+    ///     let (ptr, len) = (bytes.as_mut_ptr(), bytes.len());
+    ///     let buf = unsafe { ruby.io_buffer_from_raw_parts(ptr, len)? };
+    ///     let _: usize = buf.funcall("set_string", ("j",))?;
+    ///     buf.free()?;
+    ///     assert_eq!(&bytes, b"jello");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn io_buffer_from_raw_parts(
+        &self,
+        ptr: *mut u8,
+        len: usize,
+    ) -> Result<IoBuffer, Error> {
+        protect(|| {
+            IoBuffer::from_rb_value_unchecked(rb_io_buffer_new(
+                ptr as *mut c_void,
+                len,
+                rb_io_buffer_flags::RB_IO_BUFFER_EXTERNAL,
+            ))
+        })
+    }
+
+    fn io_buffer_class(&self) -> RClass {
+        unsafe { RClass::from_rb_value_unchecked(rb_cIOBuffer) }
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's `IO::Buffer`
+/// class.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#iobuffer) for methods to create
+/// an `IoBuffer`.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct IoBuffer(RTypedData);
+
+impl IoBuffer {
+    /// Return `Some(IoBuffer)` if `val` is an `IO::Buffer`, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, IoBuffer, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(IoBuffer::from_value(ruby.eval("IO::Buffer.new(1)")?).is_some());
+    ///     assert!(IoBuffer::from_value(ruby.eval("true")?).is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        let class = Ruby::get_with(val).io_buffer_class();
+        RTypedData::from_value(val)
+            .filter(|_| val.is_kind_of(class))
+            .map(Self)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn from_rb_value_unchecked(val: rb_sys::VALUE) -> Self {
+        Self(RTypedData::from_rb_value_unchecked(val))
+    }
+
+    /// Returns the size of the buffer in bytes.
+    ///
+    /// Errors if `IO::Buffer#size` has been redefined and raises.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.io_buffer_new(16)?.len()?, 16);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn len(self) -> Result<usize, Error> {
+        self.funcall("size", ())
+    }
+
+    /// Returns whether the buffer has a size of zero.
+    ///
+    /// Errors if `IO::Buffer#size` has been redefined and raises.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.io_buffer_new(0)?.is_empty()?);
+    ///     assert!(!ruby.io_buffer_new(1)?.is_empty()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_empty(self) -> Result<bool, Error> {
+        self.len().map(|len| len == 0)
+    }
+
+    /// Returns whether the buffer is read-only.
+    ///
+    /// Errors if `IO::Buffer#readonly?` has been redefined and raises.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, IoBuffer, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(!ruby.io_buffer_new(1)?.is_readonly()?);
+    ///     let buf: IoBuffer = ruby.eval(r#"IO::Buffer.for("frozen".freeze)"#)?;
+    ///     assert!(buf.is_readonly()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_readonly(self) -> Result<bool, Error> {
+        self.funcall("readonly?", ())
+    }
+
+    /// Returns whether the buffer is locked.
+    ///
+    /// Errors if `IO::Buffer#locked?` has been redefined and raises.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_new(1)?;
+    ///     assert!(!buf.is_locked()?);
+    ///     unsafe { buf.locked(|_| assert!(buf.is_locked().unwrap()))? };
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_locked(self) -> Result<bool, Error> {
+        self.funcall("locked?", ())
+    }
+
+    /// Returns a copy of `len` bytes of the buffer, starting at `offset`.
+    ///
+    /// Errors if the range is outside the bounds of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_from_slice(b"hello world")?;
+    ///     assert_eq!(buf.get_bytes(6, 5)?, b"world");
+    ///     assert!(buf.get_bytes(6, 6).is_err());
+    ///
+    ///     let buf = ruby.io_buffer_new(0)?;
+    ///     assert!(buf.get_bytes(0, 0)?.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get_bytes(self, offset: usize, len: usize) -> Result<Vec<u8>, Error> {
+        let (base, size) = self.bytes_for_reading()?;
+        self.check_bounds(offset, len, size)?;
+        // the base pointer may be null for an empty buffer
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(unsafe { slice::from_raw_parts(base.add(offset), len) }.to_vec())
+    }
+
+    /// Copy `bytes` in to the buffer, starting at `offset`.
+    ///
+    /// Errors if the buffer is read-only, or if `bytes` would extend past the
+    /// end of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_from_slice(b"hello world")?;
+    ///     buf.set_bytes(6, b"there")?;
+    ///     rb_assert!(ruby, r#"buf.get_string == "hello there""#, buf);
+    ///     assert!(buf.set_bytes(6, b"everyone").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set_bytes(self, offset: usize, bytes: &[u8]) -> Result<(), Error> {
+        let (base, size) = self.bytes_for_writing()?;
+        self.check_bounds(offset, bytes.len(), size)?;
+        // the base pointer may be null for an empty buffer
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), base.add(offset), bytes.len()) };
+        Ok(())
+    }
+
+    /// Lock the buffer and call `func` with its contents.
+    ///
+    /// While locked the buffer can not be resized, freed, or transferred, so
+    /// the slice remains valid for the duration of `func`.
+    ///
+    /// Errors if the buffer is already locked.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be written to from Ruby during `func`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_from_slice(&[1, 2, 3])?;
+    ///     let sum: u32 = unsafe { buf.locked(|bytes| bytes.iter().map(|b| *b as u32).sum())? };
+    ///     assert_eq!(sum, 6);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn locked<F, R>(self, func: F) -> Result<R, Error>
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let _guard = self.lock()?;
+        let (base, size) = self.bytes_for_reading()?;
+        if size == 0 {
+            return Ok(func(&[]));
+        }
+        Ok(func(slice::from_raw_parts(base, size)))
+    }
+
+    /// Lock the buffer and call `func` with its contents as a mutable slice.
+    ///
+    /// While locked the buffer can not be resized, freed, or transferred, so
+    /// the slice remains valid for the duration of `func`.
+    ///
+    /// Errors if the buffer is already locked, or is read-only.
+    ///
+    /// # Safety
+    ///
+    /// The buffer must not be read or written from Ruby during `func`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_from_slice(b"hello")?;
+    ///     unsafe { buf.locked_mut(|bytes| bytes.make_ascii_uppercase())? };
+    ///     rb_assert!(ruby, r#"buf.get_string == "HELLO""#, buf);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn locked_mut<F, R>(self, func: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let _guard = self.lock()?;
+        let (base, size) = self.bytes_for_writing()?;
+        if size == 0 {
+            return Ok(func(&mut []));
+        }
+        Ok(func(slice::from_raw_parts_mut(base, size)))
+    }
+
+    /// Free the buffer's memory, or detach it from external memory.
+    ///
+    /// The buffer will have a size of zero after this call.
+    ///
+    /// Errors if the buffer is locked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let buf = ruby.io_buffer_new(8)?;
+    ///     buf.free()?;
+    ///     assert!(buf.is_empty()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn free(self) -> Result<(), Error> {
+        protect(|| unsafe { Value::new(rb_io_buffer_free(self.as_rb_value())) })?;
+        Ok(())
+    }
+
+    fn lock(self) -> Result<LockGuard, Error> {
+        protect(|| unsafe { Value::new(rb_io_buffer_lock(self.as_rb_value())) })?;
+        Ok(LockGuard(self))
+    }
+
+    fn check_bounds(self, offset: usize, len: usize, size: usize) -> Result<(), Error> {
+        match offset.checked_add(len) {
+            Some(end) if end <= size => Ok(()),
+            _ => Err(Error::new(
+                Ruby::get_with(self).exception_arg_error(),
+                format!(
+                    "range {}..{} out of bounds for buffer of size {}",
+                    offset,
+                    offset.saturating_add(len),
+                    size
+                ),
+            )),
+        }
+    }
+
+    fn bytes_for_reading(self) -> Result<(*const u8, usize), Error> {
+        let mut base: *const c_void = ptr::null();
+        let mut size = 0;
+        protect(|| unsafe {
+            #[cfg(ruby_gte_3_2)]
+            rb_io_buffer_get_bytes_for_reading(self.as_rb_value(), &mut base, &mut size);
+            #[cfg(ruby_lt_3_2)]
+            rb_io_buffer_get_immutable(self.as_rb_value(), &mut base, &mut size);
+            Ruby::get_unchecked().qnil()
+        })?;
+        Ok((base as *const u8, size))
+    }
+
+    fn bytes_for_writing(self) -> Result<(*mut u8, usize), Error> {
+        let mut base: *mut c_void = ptr::null_mut();
+        let mut size = 0;
+        protect(|| unsafe {
+            #[cfg(ruby_gte_3_2)]
+            rb_io_buffer_get_bytes_for_writing(self.as_rb_value(), &mut base, &mut size);
+            #[cfg(ruby_lt_3_2)]
+            rb_io_buffer_get_mutable(self.as_rb_value(), &mut base, &mut size);
+            Ruby::get_unchecked().qnil()
+        })?;
+        Ok((base as *mut u8, size))
+    }
+}
+
+/// Unlocks the buffer when dropped, so it is unlocked even if the closure
+/// passed to [`IoBuffer::locked`] panics.
+struct LockGuard(IoBuffer);
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // only errors if the buffer isn't locked, which can only happen if
+        // it was unlocked from Ruby, in which case there's nothing to do
+        let _ = protect(|| unsafe { Value::new(rb_io_buffer_unlock(self.0.as_rb_value())) });
+    }
+}
+
+impl fmt::Display for IoBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for IoBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for IoBuffer {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.as_value()
+    }
+}
+
+impl Object for IoBuffer {}
+
+unsafe impl private::ReprValue for IoBuffer {}
+
+impl ReprValue for IoBuffer {}
+
+impl TryConvert for IoBuffer {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into IO::Buffer", unsafe {
+                    val.classname()
+                },),
+            )
+        })
+    }
+}
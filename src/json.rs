@@ -0,0 +1,283 @@
+//! Conversions between Ruby objects and [`serde_json::Value`].
+//!
+//! This maps JSON data directly to and from Ruby's core types, without the
+//! need for a full serializer:
+//!
+//! | JSON          | Ruby                                   |
+//! |---------------|----------------------------------------|
+//! | `null`        | `nil`                                  |
+//! | boolean       | `true`/`false`                         |
+//! | number        | `Integer` or `Float`                   |
+//! | string        | `String`                               |
+//! | array         | `Array`                                |
+//! | object        | `Hash`, with String or Symbol keys     |
+//!
+//! `serde_json::Value` also implements [`IntoValue`] (using String keys,
+//! panicking if nested more than [`MAX_NESTING`] levels deep) and
+//! [`TryConvert`](crate::TryConvert).
+
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::{
+    error::Error,
+    float::Float,
+    integer::Integer,
+    into_value::{IntoValue, IntoValueFromNative},
+    r_array::RArray,
+    r_hash::{ForEach, RHash},
+    r_string::RString,
+    symbol::Symbol,
+    try_convert::{TryConvert, TryConvertOwned},
+    value::{Qfalse, Qtrue, ReprValue, Value},
+    Ruby,
+};
+
+/// The maximum depth of nested arrays/objects converted by [`to_value`] and
+/// [`from_value`].
+///
+/// This guards against overflowing the stack when converting a recursive
+/// data structure.
+pub const MAX_NESTING: usize = 128;
+
+/// How the keys of JSON objects are converted to Ruby.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Keys {
+    /// Convert keys to Ruby Strings.
+    String,
+    /// Convert keys to Ruby Symbols.
+    Symbol,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self::String
+    }
+}
+
+/// Convert `json` to a Ruby object, with object keys converted as specified
+/// by `keys`.
+///
+/// Errors if arrays/objects are nested more than [`MAX_NESTING`] levels deep.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{json, rb_assert, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let data = serde_json::json!({"name": "Alice", "tags": ["a", "b"], "age": 42});
+///
+///     let val = json::to_value(ruby, &data, json::Keys::String)?;
+///     rb_assert!(
+///         ruby,
+///         r#"val == {"name" => "Alice", "tags" => ["a", "b"], "age" => 42}"#,
+///         val
+///     );
+///
+///     let val = json::to_value(ruby, &data, json::Keys::Symbol)?;
+///     rb_assert!(
+///         ruby,
+///         r#"val == {name: "Alice", tags: ["a", "b"], age: 42}"#,
+///         val
+///     );
+///
+///     let mut deep = serde_json::json!(null);
+///     for _ in 0..=json::MAX_NESTING {
+///         deep = serde_json::json!([deep]);
+///     }
+///     assert!(json::to_value(ruby, &deep, json::Keys::String).is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_value(ruby: &Ruby, json: &JsonValue, keys: Keys) -> Result<Value, Error> {
+    to_value_nested(ruby, json, keys, 0)
+}
+
+fn to_value_nested(
+    ruby: &Ruby,
+    json: &JsonValue,
+    keys: Keys,
+    depth: usize,
+) -> Result<Value, Error> {
+    if matches!(json, JsonValue::Array(_) | JsonValue::Object(_)) && depth >= MAX_NESTING {
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            format!("nesting of {} is too deep", depth + 1),
+        ));
+    }
+    let val = match json {
+        JsonValue::Null => ruby.qnil().as_value(),
+        JsonValue::Bool(b) => ruby.into_value(*b),
+        JsonValue::Number(n) => number_to_value(ruby, n),
+        JsonValue::String(s) => ruby.str_new(s).as_value(),
+        JsonValue::Array(vec) => {
+            let ary = ruby.ary_new_capa(vec.len());
+            for item in vec {
+                // can't fail, ary is new and not frozen
+                let _ = ary.push(to_value_nested(ruby, item, keys, depth + 1)?);
+            }
+            ary.as_value()
+        }
+        JsonValue::Object(map) => {
+            let hash = ruby.hash_new();
+            for (k, v) in map {
+                let key = match keys {
+                    Keys::String => ruby.str_new(k).as_value(),
+                    Keys::Symbol => ruby.to_symbol(k).as_value(),
+                };
+                // can't fail, hash is new and not frozen, and keys are
+                // Strings or Symbols
+                let _ = hash.aset(key, to_value_nested(ruby, v, keys, depth + 1)?);
+            }
+            hash.as_value()
+        }
+    };
+    Ok(val)
+}
+
+fn number_to_value(ruby: &Ruby, n: &Number) -> Value {
+    if let Some(i) = n.as_i64() {
+        ruby.into_value(i)
+    } else if let Some(u) = n.as_u64() {
+        ruby.into_value(u)
+    } else {
+        ruby.into_value(n.as_f64().unwrap_or(f64::NAN))
+    }
+}
+
+/// Convert the Ruby object `val` to a [`serde_json::Value`].
+///
+/// `nil`, `true`, `false`, Integers, Floats, Strings, Symbols (converted to
+/// strings), Arrays, and Hashes with String or Symbol keys are supported.
+///
+/// Errors if `val` or any nested value is of an unsupported type, if an
+/// Integer is out of range of `i64`/`u64`, if a Float is `NaN` or infinite,
+/// or if arrays/hashes are nested more than [`MAX_NESTING`] levels deep.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{json, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val: Value = ruby.eval(r#"{name: "Alice", "tags" => [:a, 1.5, nil]}"#)?;
+///     assert_eq!(
+///         json::from_value(val)?,
+///         serde_json::json!({"name": "Alice", "tags": ["a", 1.5, null]})
+///     );
+///
+///     let val: Value = ruby.eval("Object.new")?;
+///     assert!(json::from_value(val).is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn from_value(val: Value) -> Result<JsonValue, Error> {
+    from_value_nested(&Ruby::get_with(val), val, 0)
+}
+
+fn from_value_nested(ruby: &Ruby, val: Value, depth: usize) -> Result<JsonValue, Error> {
+    if val.is_nil() {
+        return Ok(JsonValue::Null);
+    }
+    if Qtrue::from_value(val).is_some() {
+        return Ok(JsonValue::Bool(true));
+    }
+    if Qfalse::from_value(val).is_some() {
+        return Ok(JsonValue::Bool(false));
+    }
+    if let Some(i) = Integer::from_value(val) {
+        return i
+            .to_i64()
+            .map(JsonValue::from)
+            .or_else(|_| i.to_u64().map(JsonValue::from))
+            .map_err(|_| {
+                Error::new(
+                    ruby.exception_range_error(),
+                    format!("integer {} too big to convert to JSON", i),
+                )
+            });
+    }
+    if let Some(f) = Float::from_value(val) {
+        return Number::from_f64(f.to_f64())
+            .map(JsonValue::Number)
+            .ok_or_else(|| {
+                Error::new(
+                    ruby.exception_float_domain_error(),
+                    format!("{} not allowed in JSON", f),
+                )
+            });
+    }
+    if let Some(s) = RString::from_value(val) {
+        return s.to_string().map(JsonValue::String);
+    }
+    if let Some(s) = Symbol::from_value(val) {
+        return s.name().map(|n| JsonValue::String(n.into_owned()));
+    }
+    if depth >= MAX_NESTING {
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            format!("nesting of {} is too deep", depth + 1),
+        ));
+    }
+    if let Some(ary) = RArray::from_value(val) {
+        let mut vec = Vec::with_capacity(ary.len());
+        let mut i = 0;
+        while i < ary.len() {
+            vec.push(from_value_nested(ruby, ary.entry(i as isize)?, depth + 1)?);
+            i += 1;
+        }
+        return Ok(JsonValue::Array(vec));
+    }
+    if let Some(hash) = RHash::from_value(val) {
+        let mut map = Map::new();
+        hash.foreach(|k: Value, v: Value| {
+            let key = if let Some(s) = RString::from_value(k) {
+                s.to_string()?
+            } else if let Some(s) = Symbol::from_value(k) {
+                s.name()?.into_owned()
+            } else {
+                return Err(Error::new(
+                    ruby.exception_type_error(),
+                    format!(
+                        "JSON object keys must be String or Symbol, not {}",
+                        k.class()
+                    ),
+                ));
+            };
+            map.insert(key, from_value_nested(ruby, v, depth + 1)?);
+            Ok(ForEach::Continue)
+        })?;
+        return Ok(JsonValue::Object(map));
+    }
+    Err(Error::new(
+        ruby.exception_type_error(),
+        format!("no implicit conversion of {} into JSON", val.class()),
+    ))
+}
+
+/// # Panics
+///
+/// Panics if arrays/objects are nested more than [`MAX_NESTING`] levels deep.
+/// Use [`to_value`] to handle this case as an error.
+impl IntoValue for JsonValue {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        to_value(handle, &self, Keys::String)
+            .expect("JSON nested too deeply to convert to a Ruby object")
+    }
+}
+
+unsafe impl IntoValueFromNative for JsonValue {}
+
+impl TryConvert for JsonValue {
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        from_value(val)
+    }
+}
+
+unsafe impl TryConvertOwned for JsonValue {}
@@ -305,7 +305,7 @@
 // * `rb_big2int`:
 // * `rb_big2ll`:
 // * `rb_big2long`:
-// * `rb_big2str`:
+//! * `rb_big2str`: [`Integer::to_string_radix`].
 // * `rb_big2uint`:
 // * `rb_big2ull`:
 // * `rb_big2ulong`:
@@ -331,7 +331,7 @@
 // * `rb_big_pow`:
 // * `rb_big_resize`:
 // * `rb_big_rshift`:
-// * `rb_big_sign`:
+//! * `rb_big_sign`: See [`RBignum::is_positive`].
 // * `rb_big_unpack`:
 // * `rb_big_xor`:
 //! * `rb_block_call`: See [`Value::block_call`].
@@ -355,7 +355,7 @@
 // * `rb_char_to_option_kcode`:
 //! * `rb_check_arity`: [`Ruby::check_arity`].
 //! * `rb_check_array_type`:  See [`TryConvert`] and [`Value::try_convert`].
-// * `rb_check_convert_type`:
+//! * `rb_check_convert_type`: See [`try_convert::to_proc`].
 // * `rb_check_copyable`:
 //! * `rb_check_frozen`: See [`ReprValue::check_frozen`].
 // * `rb_check_frozen_inline`:
@@ -368,7 +368,7 @@
 // * `rb_check_safe_str`:
 //! * `rb_check_string_type`: See [`TryConvert`] and [`Value::try_convert`].
 //! * `rb_check_symbol`: Similar to [`StaticSymbol::check`].
-//! * `rb_check_symbol_cstr`: [`StaticSymbol::check`].
+//! * `rb_check_symbol_cstr`: [`Ruby::find_symbol`] and [`StaticSymbol::check`].
 //! * `rb_check_to_float`: See [`TryConvert`] and [`Value::try_convert`].
 //! * `rb_check_to_int`: See [`TryConvert`] and [`Value::try_convert`].
 // * `rb_check_to_integer`:
@@ -378,15 +378,17 @@
 //! * `rb_class2name`: [`RClass::name`].
 // * `rb_class_descendants`:
 // * `rb_class_get_superclass`:
-// * `rb_class_inherited_p`: [`Module::is_inherited`].
+//! * `rb_class_inherited_p`: See [`Module::is_inherited`],
+//!   [`Module::is_subclass_of`], [`Module::is_superclass_of`], &
+//!   [`Module::module_cmp`].
 // * `rb_class_instance_methods`:
 //! * `rb_class_name`: Simmilar to [`Value::classname`].
-//! * `rb_class_new`: [`RClass::new`].
+//! * `rb_class_new`: See [`RClass::new_anon`] and [`RClass::new`].
 //! * `rb_class_new_instance`: See [`RClass::new_instance`].
 //! * `rb_class_new_instance_kw`: [`RClass::new_instance`].
 // * `rb_class_new_instance_pass_kw`:
 // * `rb_class_of`:
-// * `rb_class_path`:
+//! * `rb_class_path`: See [`Module::to_path`].
 // * `rb_class_path_cached`:
 // * `rb_class_private_instance_methods`:
 // * `rb_class_protected_instance_methods`:
@@ -421,10 +423,10 @@
 // * `rb_complex_minus`:
 // * `rb_complex_mul`:
 // * `rb_complex_nagate`:
-//! * `rb_complex_new`: [`RComplex::new`].
+//! * `rb_complex_new`: [`Ruby::complex_new`] / [`RComplex::new`].
 // * `rb_complex_new1`:
 // * `rb_complex_new2`:
-//! * `rb_complex_new_polar`: [`RComplex::polar`].
+//! * `rb_complex_new_polar`: [`Ruby::complex_new_polar`] / [`RComplex::polar`].
 // * `rb_complex_plus`:
 // * `rb_complex_pow`:
 // * `rb_complex_raw`:
@@ -446,7 +448,7 @@
 // * `rb_copy_generic_ivar`:
 // * `rb_cstr2inum`:
 // * `rb_cstr_to_dbl`:
-// * `rb_cstr_to_inum`:
+//! * `rb_cstr_to_inum`: [`Ruby::integer_from_str_radix`].
 //! * `rb_current_receiver`: [`Ruby::current_receiver`].
 // * `rb_cvar_defined`:
 // * `rb_cvar_find`:
@@ -479,7 +481,7 @@
 //! * `rb_default_internal_encoding`:
 //!   [`RbEncoding::default_internal`](encoding::RbEncoding::default_internal).
 //! * `rb_define_alias`: [`Module::define_alias`].
-// * `rb_define_alloc_func`:
+//! * `rb_define_alloc_func`: [`Class::define_alloc_func`].
 //! * `rb_define_attr`: See [`Module::define_attr`].
 //! * `rb_define_class`: [`Ruby::define_class`].
 //! * `rb_define_class_id`: Simmilar to [`Ruby::define_class`].
@@ -491,7 +493,7 @@
 // * `rb_define_finalizer`:
 // * `rb_define_global_const`:
 //! * `rb_define_global_function`: [`Ruby::define_global_function`].
-// * `rb_define_hooked_variable`:
+//! * `rb_define_hooked_variable`: See [`Ruby::define_virtual_variable`].
 //! * `rb_define_method`: See [`Module::define_method`].
 //! * `rb_define_method_id`: [`Module::define_method`].
 //! * `rb_define_module`: [`Ruby::define_module`].
@@ -504,10 +506,10 @@
 // * `rb_define_readonly_variable`:
 //! * `rb_define_singleton_method`: [`Object::define_singleton_method`].
 //! * `rb_define_variable`: [`Ruby::define_variable`].
-// * `rb_define_virtual_variable`:
+//! * `rb_define_virtual_variable`: See [`Ruby::define_virtual_variable`].
 // * `rb_deprecate_constant`:
 // * `rb_detach_process`:
-// * `rb_dir_getwd`:
+//! * `rb_dir_getwd`: See [`Ruby::dir_getwd`].
 // * `rb_disable_super`:
 // * `rb_during_gc`:
 // * `RB_DYNAMIC_SYM_P`:
@@ -583,7 +585,7 @@
 //!   plus [`std::convert::From`].
 //! * `rb_enc_get_index`:
 //!   [`EncodingCapable::enc_get`](encoding::EncodingCapable::enc_get).
-// * `rb_enc_interned_str`:
+//! * `rb_enc_interned_str`: See [`RHash::get_str`].
 // * `rb_enc_interned_str_cstr`:
 // * `rb_enc_isalnum`:
 // * `rb_enc_isalpha`:
@@ -624,7 +626,7 @@
 // * `rb_enc_strlen`:
 // * `rb_enc_str_asciicompat_p`:
 // * `rb_enc_str_asciionly_p`:
-// * `rb_enc_str_buf_cat`:
+//! * `rb_enc_str_buf_cat`: [`RString::push_str`].
 //! * `rb_enc_str_coderange`: [`RString::enc_coderange_scan`].
 //! * `rb_enc_str_new`: [`RString::enc_new`].
 // * `rb_enc_str_new_cstr`:
@@ -734,12 +736,12 @@
 //!   [`RbEncoding::filesystem`](encoding::RbEncoding::filesystem).
 // * `rb_filesystem_str_new`:
 // * `rb_filesystem_str_new_cstr`:
-// * `rb_file_absolute_path`:
+//! * `rb_file_absolute_path`: See [`Ruby::file_absolute_path`].
 // * `rb_file_directory_p`:
 // * `rb_file_dirname`:
-// * `rb_file_expand_path`:
+//! * `rb_file_expand_path`: See [`Ruby::file_expand_path`].
 // * `rb_file_open`:
-// * `rb_file_open_str`:
+//! * `rb_file_open_str`: See [`Ruby::file_open`].
 // * `rb_file_size`:
 // * `rb_file_s_absolute_path`:
 // * `rb_file_s_expand_path`:
@@ -752,7 +754,7 @@
 // * `rb_fix2long`:
 // * `RB_FIX2SHORT`:
 // * `rb_fix2short`:
-// * `rb_fix2str`:
+//! * `rb_fix2str`: [`Integer::to_string_radix`].
 // * `RB_FIX2UINT`:
 // * `rb_fix2uint`:
 // * `RB_FIX2ULONG`:
@@ -847,7 +849,7 @@
 // * `rb_get_values_at`:
 // * `rb_glob`:
 // * `rb_global_variable`:
-// * `rb_gvar_readonly_setter`:
+//! * `rb_gvar_readonly_setter`: See [`Ruby::define_readonly_virtual_variable`].
 // * `rb_gvar_val_getter`:
 // * `rb_gvar_val_marker`:
 // * `rb_gvar_val_setter`:
@@ -900,9 +902,9 @@
 // * `RB_INT2NUM`:
 // * `rb_int2num_inline`:
 // * `rb_Integer`:
-// * `rb_integer_pack`:
+//! * `rb_integer_pack`: [`Integer::to_words_le`].
 // * `rb_integer_type_p`:
-// * `rb_integer_unpack`:
+//! * `rb_integer_unpack`: [`Ruby::integer_from_words_le`].
 //! * `rb_intern`: [`std::convert::From`].
 //! * `rb_intern2`: [`std::convert::From`].
 //! * `rb_intern3`: [`std::convert::From`].
@@ -920,6 +922,14 @@
 // * `rb_io_addstr`:
 // * `rb_io_ascii8bit_binmode`:
 // * `rb_io_binmode`:
+//! * `rb_io_buffer_free`: [`IoBuffer::free`].
+//! * `rb_io_buffer_get_bytes_for_reading`: See [`IoBuffer::get_bytes`] &
+//!   [`IoBuffer::locked`].
+//! * `rb_io_buffer_get_bytes_for_writing`: See [`IoBuffer::set_bytes`] &
+//!   [`IoBuffer::locked_mut`].
+//! * `rb_io_buffer_lock`: See [`IoBuffer::locked`] & [`IoBuffer::locked_mut`].
+//! * `rb_io_buffer_new`: See [`Ruby::io_buffer_from_raw_parts`].
+//! * `rb_io_buffer_unlock`: See [`IoBuffer::locked`] & [`IoBuffer::locked_mut`].
 // * `rb_io_bufwrite`:
 // * `rb_io_check_byte_readable`:
 // * `rb_io_check_char_readable`:
@@ -928,13 +938,13 @@
 // * `rb_io_check_io`:
 // * `rb_io_check_readable`:
 // * `rb_io_check_writable`:
-// * `rb_io_close`:
+//! * `rb_io_close`: See [`RFile::close`].
 // * `rb_io_descriptor`:
 // * `rb_io_eof`:
 // * `rb_io_extract_encoding_option`:
 // * `rb_io_extract_modeenc`:
 // * `rb_io_fdopen`:
-// * `rb_io_flush`:
+//! * `rb_io_flush`: See [`RFile::flush`].
 // * `rb_io_fptr_finalize`:
 // * `rb_io_getbyte`:
 // * `rb_io_gets`:
@@ -998,7 +1008,7 @@
 //! ## `rb_j`-`rb_k`
 //!
 //! * `rb_jump_tag`: Return [`Error`].
-// * `rb_keyword_given_p`:
+//! * `rb_keyword_given_p`: See [`scan_args::MethodArgs::new`].
 //!
 //! ## `rb_l`
 // * `rb_lastline_get`:
@@ -1061,7 +1071,7 @@
 // * `rb_method_basic_definition_p`:
 // * `rb_method_boundp`:
 // * `rb_method_call`:
-// * `rb_method_call_kw`:
+//! * `rb_method_call_kw`: [`RMethod::call`].
 // * `rb_method_call_with_block`:
 // * `rb_method_call_with_block_kw`:
 //! * `rb_module_new`: [`RModule::new`].
@@ -1072,11 +1082,11 @@
 // * `rb_mod_const_missing`:
 // * `rb_mod_const_of`:
 // * `rb_mod_included_modules`:
-// * `rb_mod_include_p`:
+//! * `rb_mod_include_p`: See [`Module::includes_module`].
 // * `rb_mod_init_copy`:
 // * `rb_mod_method_arity`:
 // * `rb_mod_module_eval`:
-// * `rb_mod_module_exec`:
+//! * `rb_mod_module_exec`: See [`Module::class_eval_closure`].
 // * `rb_mod_name`:
 // * `rb_mod_remove_const`:
 // * `rb_mod_remove_cvar`:
@@ -1173,16 +1183,16 @@
 // * `rb_obj_frozen_p`:
 // * `RB_OBJ_FROZEN_RAW`:
 // * `rb_obj_hide`:
-// * `rb_obj_id`:
+//! * `rb_obj_id`: [`Value::object_id`].
 // * `RB_OBJ_INIT_COPY`:
 // * `rb_obj_init_copy`:
 // * `rb_obj_instance_eval`:
-// * `rb_obj_instance_exec`:
+//! * `rb_obj_instance_exec`: See [`Value::instance_exec_closure`].
 // * `rb_obj_instance_variables`:
 //! * `rb_obj_is_fiber`: [`Fiber::from_value`].
 // * `rb_obj_is_instance_of`:
 //! * `rb_obj_is_kind_of`: [`Value::is_kind_of`].
-// * `rb_obj_is_method`:
+//! * `rb_obj_is_method`: [`RMethod::from_value`].
 //! * `rb_obj_is_proc`: [`Proc::from_value`](block::Proc::from_value).
 // * `rb_obj_method`:
 // * `rb_obj_method_arity`:
@@ -1193,7 +1203,7 @@
 // * `rb_obj_reveal`:
 // * `rb_obj_setup`:
 // * `RB_OBJ_SHAREABLE_P`:
-// * `rb_obj_singleton_methods`:
+//! * `rb_obj_singleton_methods`: [`Object::singleton_methods`].
 // * `RB_OBJ_WB_UNPROTECT`:
 // * `rb_obj_wb_unprotect`:
 // * `RB_OBJ_WB_UNPROTECT_FOR`:
@@ -1232,7 +1242,9 @@
 // * `rb_profile_frame_qualified_method_name`:
 // * `rb_profile_frame_singleton_method_p`:
 //! * `rb_protect`: Called internally by Magnus when required. Available as
-//!   [`rb_sys::protect`] with `rb-sys` feature for calling raw Ruby api.
+//!   [`rb_sys::protect`], [`rb_sys::protect_returning`],
+//!   [`rb_sys::protect_fn`], and [`rb_sys::protect_batch`] with `rb-sys`
+//!   feature for calling raw Ruby api.
 // * `rb_provide`:
 // * `rb_provided`:
 //!
@@ -1244,9 +1256,9 @@
 // * `rb_ractor_local_storage_value_lookup`:
 // * `rb_ractor_local_storage_value_newkey`:
 // * `rb_ractor_local_storage_value_set`:
-// * `rb_ractor_make_shareable`:
+//! * `rb_ractor_make_shareable`: [`ReprValue::make_shareable`].
 // * `rb_ractor_make_shareable_copy`:
-// * `rb_ractor_shareable_p`:
+//! * `rb_ractor_shareable_p`: See [`ReprValue::is_shareable`].
 // * `rb_ractor_stderr`:
 // * `rb_ractor_stderr_set`:
 // * `rb_ractor_stdin`:
@@ -1329,7 +1341,7 @@
 // * `rb_scan_args_kw`:
 // * `rb_scan_args_length_mismatch`:
 // * `rb_set_class_path`:
-// * `rb_set_class_path_string`:
+//! * `rb_set_class_path_string`: See [`RClass::set_name`].
 // * `rb_set_end_proc`:
 // * `rb_set_errinfo`:
 //! * `rb_singleton_class`: [`Object::singleton_class`].
@@ -1372,7 +1384,7 @@
 // * `rb_struct_s_members`:
 //!
 //! ## `rb_str`
-// * `rb_str_append`:
+//! * `rb_str_append`: [`RString::push_value`].
 //! * `rb_str_buf_append`: [`RString::buf_append`].
 //! * `rb_str_buf_cat`: [`RString::cat`].
 //! * `rb_str_buf_cat_ascii`: See [`RString::cat`].
@@ -1405,9 +1417,9 @@
 // * `rb_str_hash`:
 // * `rb_str_hash_cmp`:
 // * `rb_str_inspect`:
-// * `rb_str_intern`:
+//! * `rb_str_intern`: See [`RHash::symbolize_keys`].
 // * `rb_str_length`:
-// * `rb_str_locktmp`:
+//! * `rb_str_locktmp`: [`RString::with_bytes`].
 // * `rb_str_modify`:
 // * `rb_str_modify_expand`:
 //! * `rb_str_new`: [`RString::from_slice`].
@@ -1421,7 +1433,7 @@
 //! * `rb_str_offset`: [`RString::offset`].
 //! * `rb_str_plus`: [`RString::plus`].
 //! * `rb_str_replace`: [`RString::replace`].
-// * `rb_str_resize`:
+//! * `rb_str_resize`: [`RString::clear`].
 // * `rb_str_resurrect`:
 //! * `rb_str_scrub`: [`RString::scrub`].
 // * `rb_str_setter`:
@@ -1431,7 +1443,7 @@
 //! * `rb_str_strlen`: [`RString::length`].
 // * `rb_str_sublen`:
 // * `rb_str_subpos`:
-// * `rb_str_subseq`:
+//! * `rb_str_subseq`: [`RString::each_char`].
 // * `rb_str_substr`:
 // * `rb_str_succ`:
 //! * `rb_str_times`: [`RString::times`].
@@ -1440,7 +1452,7 @@
 //! * `rb_str_to_interned_str`: [`RString::to_interned_str`].
 // * `rb_str_to_inum`:
 //! * `rb_str_to_str`: [`TryConvert`] or [`Value::try_convert`].
-// * `rb_str_unlocktmp`:
+//! * `rb_str_unlocktmp`: [`RString::with_bytes`].
 //! * `rb_str_update`: [`RString::update`].
 // * `rb_str_vcatf`:
 //!
@@ -1510,7 +1522,7 @@
 //! * `rb_thread_alone`: [`Ruby::thread_alone`].
 // * `rb_thread_atfork`:
 // * `rb_thread_atfork_before_exec`:
-// * `rb_thread_call_without_gvl`:
+//! * `rb_thread_call_without_gvl`: [`Ruby::thread_call_without_gvl`].
 // * `rb_thread_call_without_gvl2`:
 // * `rb_thread_call_with_gvl`:
 //! * `rb_thread_check_ints`: [`Ruby::thread_check_ints`].
@@ -1576,7 +1588,7 @@
 // * `rb_trap_exit`:
 // * `rb_type`:
 // * `rb_typeddata_inherited_p`:
-// * `rb_typeddata_is_kind_of`:
+//! * `rb_typeddata_is_kind_of`: [`Value::is_wrapped`].
 // * `RB_TYPE_P`:
 // * `rb_type_p`:
 //!
@@ -1593,7 +1605,8 @@
 // * `rb_ulong2num_inline`:
 // * `rb_undef`:
 // * `rb_undefine_finalizer`:
-//! * `rb_undef_alloc_func`: See [`Class::undef_default_alloc_func`].
+//! * `rb_undef_alloc_func`: [`Class::undef_alloc_func`] and
+//!   [`Class::undef_default_alloc_func`].
 // * `rb_undef_method`:
 // * `rb_unexpected_type`:
 // * `RB_UNLIKELY`:
@@ -1620,12 +1633,14 @@
 // * `rb_vsprintf`:
 // * `rb_w32_fd_copy`:
 // * `rb_w32_fd_dup`:
+//! * `rb_wait_for_single_fd`: [`Ruby::thread_wait_fd_readable`].
 //! * `rb_waitpid`: [`Ruby::waitpid`].
 // * `rb_warn`:
 //! * `rb_warning`: [`Ruby::warning`].
 // * `rb_write_error`:
 // * `rb_write_error2`:
-//! * `rb_yield`: [`Ruby::yield_value`] / return [`block::Yield`].
+//! * `rb_yield`: [`Ruby::yield_value`] / return [`block::Yield`] or
+//!   [`block::YieldWith`].
 // * `rb_yield_block`:
 //! * `rb_yield_splat`: [`Ruby::yield_splat`] / return [`block::YieldSplat`].
 // * `rb_yield_splat_kw`:
@@ -1717,7 +1732,7 @@
 // * `ruby_each_words`:
 // * `ruby_enc_find_basename`:
 // * `ruby_enc_find_extname`:
-// * `ruby_executable_node`:
+//! * `ruby_executable_node`: See [`embed::init_with_args`].
 // * `ruby_exec_node`:
 // * `ruby_finalize`:
 // * `ruby_getcwd`:
@@ -1733,7 +1748,7 @@
 // * `RUBY_NDEBUG`:
 // * `ruby_options`:
 // * `ruby_posix_signal`:
-// * `ruby_process_options`:
+//! * `ruby_process_options`: [`embed::init_with_args`].
 // * `ruby_prog_init`:
 // * `ruby_qsort`:
 // * `ruby_run_node`:
@@ -1794,6 +1809,8 @@ mod macros;
 mod api;
 pub mod block;
 pub mod class;
+pub mod compat;
+pub mod doc;
 #[cfg(feature = "embed")]
 #[cfg_attr(docsrs, doc(cfg(feature = "embed")))]
 pub mod embed;
@@ -1805,16 +1822,37 @@ pub mod exception;
 #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_1)))]
 pub mod fiber;
 mod float;
+pub mod fs;
 pub mod gc;
 mod integer;
 mod into_value;
+#[cfg(any(ruby_gte_3_1, docsrs))]
+mod io_buffer;
+#[cfg(feature = "serde_json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde_json")))]
+pub mod json;
 pub mod method;
+pub mod method_table;
+#[cfg(feature = "migrate")]
+#[cfg_attr(docsrs, doc(cfg(feature = "migrate")))]
+pub mod migrate;
 pub mod module;
+#[cfg(feature = "msgpack")]
+#[cfg_attr(docsrs, doc(cfg(feature = "msgpack")))]
+pub mod msgpack;
 mod mutex;
 pub mod numeric;
 mod object;
 pub mod process;
 /// Traits that commonly should be in scope.
+///
+/// Methods common to all Ruby types are provided by the
+/// [`ReprValue`](crate::value::ReprValue) trait, and methods common to
+/// classes, modules, and objects by the [`Class`](crate::Class),
+/// [`Module`](crate::Module), and [`Object`](crate::Object) traits. Code
+/// written for versions of Magnus where Ruby types dereferenced to
+/// [`Value`](crate::Value) can generally be updated by adding
+/// `use magnus::prelude::*;`.
 pub mod prelude {
     pub use crate::{
         class::Class as _, encoding::EncodingCapable as _, module::Module as _,
@@ -1829,6 +1867,7 @@ mod r_file;
 mod r_float;
 pub mod r_hash;
 mod r_match;
+mod r_method;
 mod r_object;
 mod r_rational;
 pub mod r_regexp;
@@ -1841,26 +1880,38 @@ mod range;
 pub mod rb_sys;
 pub mod scan_args;
 pub mod symbol;
+pub mod testing;
 mod thread;
 pub mod time;
+mod trace;
 pub mod try_convert;
 pub mod typed_data;
 pub mod value;
+mod weak_map;
 
 use std::{ffi::CString, mem::transmute, os::raw::c_int};
 
 use ::rb_sys::{
     rb_alias_variable, rb_backref_get, rb_call_super_kw, rb_current_receiver, rb_define_class,
-    rb_define_global_const, rb_define_global_function, rb_define_module, rb_define_variable,
-    rb_errinfo, rb_eval_string_protect, rb_require_string, rb_set_errinfo, VALUE,
+    rb_define_global_const, rb_define_global_function, rb_define_hooked_variable, rb_define_module,
+    rb_define_variable, rb_errinfo, rb_eval_string_protect, rb_gvar_readonly_setter,
+    rb_require_string, rb_set_errinfo, ID, VALUE,
+};
+pub use magnus_macros::{
+    init, wrap, ConstEnum, DataTypeFunctions, FromRHash, SymbolEnum, TypedData,
 };
-pub use magnus_macros::{init, wrap, DataTypeFunctions, TypedData};
 
 #[cfg(any(ruby_gte_3_1, docsrs))]
 #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_1)))]
 pub use crate::fiber::Fiber;
+#[cfg(any(ruby_gte_3_1, docsrs))]
+#[cfg_attr(docsrs, doc(cfg(ruby_gte_3_1)))]
+pub use crate::io_buffer::IoBuffer;
 #[cfg(ruby_use_flonum)]
 pub use crate::value::Flonum;
+#[cfg(any(ruby_gte_3_3, docsrs))]
+#[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+pub use crate::weak_map::WeakKeyMap;
 pub use crate::{
     api::Ruby,
     class::{Class, RClass},
@@ -1870,7 +1921,7 @@ pub use crate::{
     float::Float,
     integer::Integer,
     into_value::{ArgList, IntoValue, IntoValueFromNative, KwArgs, RArrayArgList},
-    module::{Attr, Module, RModule},
+    module::{Attr, ConstEnum, Module, RModule},
     mutex::Mutex,
     numeric::Numeric,
     object::Object,
@@ -1881,6 +1932,7 @@ pub use crate::{
     r_float::RFloat,
     r_hash::RHash,
     r_match::RMatch,
+    r_method::{RMethod, UnboundMethod},
     r_object::RObject,
     r_rational::RRational,
     r_regexp::RRegexp,
@@ -1894,18 +1946,31 @@ pub use crate::{
     try_convert::TryConvert,
     typed_data::{DataType, DataTypeFunctions, TypedData},
     value::{Fixnum, StaticSymbol, Value},
+    weak_map::WeakMap,
 };
 use crate::{
-    error::protect,
-    method::Method,
+    block::Proc,
+    error::{protect, raise},
+    method::{BlockReturn, Method},
     r_string::IntoRString,
-    value::{private::ReprValue as _, IntoId, ReprValue},
+    value::{private::ReprValue as _, GlobalVariable, IntoId, ReprValue},
 };
 
 /// Evaluate a literal string of Ruby code with the given local variables.
 ///
 /// Any type that implements [`IntoValue`] can be passed to Ruby.
 ///
+/// Local variables can be given as `name = <expr>` pairs, as a bare
+/// identifier to pass a Rust variable with the same name, or as
+/// `**<expr>` to set all the pairs from an [`RHash`] of names to values.
+///
+/// The code is evaluated in a new binding, unless one is given with
+/// `binding = <expr>` before the string of code. Local variables will be set
+/// in the given binding.
+///
+/// Errors if a local variable can not be set, e.g. if a key of an [`RHash`]
+/// of locals is not a Symbol or String.
+///
 /// See also the [`eval`](fn@crate::eval) function.
 ///
 /// # Panics
@@ -1926,14 +1991,49 @@ use crate::{
 /// let result: i64 = magnus::eval!("a + b", a, b).unwrap();
 /// assert_eq!(result, 3);
 /// ```
+/// ```
+/// # let _cleanup = unsafe { magnus::embed::init() };
+/// let ruby = magnus::Ruby::get().unwrap();
+/// let locals = ruby.hash_new();
+/// locals.aset("a", 1).unwrap();
+/// locals.aset("b", 2).unwrap();
+/// let result: i64 = magnus::eval!(ruby, "a + b + c", **locals, c = 3 * 4).unwrap();
+/// assert_eq!(result, 15);
+///
+/// let locals = ruby.hash_new();
+/// locals.aset(1, 2).unwrap();
+/// let result: Result<i64, magnus::Error> = magnus::eval!(ruby, "1", **locals);
+/// assert!(result.is_err());
+/// ```
+/// ```
+/// # let _cleanup = unsafe { magnus::embed::init() };
+/// let ruby = magnus::Ruby::get().unwrap();
+/// let binding: magnus::Value = ruby.eval("a = 1; binding").unwrap();
+/// let result: i64 = magnus::eval!(ruby, binding = binding, "a + b", b = 2).unwrap();
+/// assert_eq!(result, 3);
+/// ```
 #[macro_export]
 macro_rules! eval {
+    (binding = $binding:expr, $str:literal $(, $($bindings:tt)*)?) => {{
+        $crate::eval!(
+            $crate::Ruby::get().unwrap(),
+            binding = $binding,
+            $str
+            $(, $($bindings)*)?
+        )
+    }};
     ($str:literal) => {{
         $crate::eval!($crate::Ruby::get().unwrap(), $str)
     }};
     ($str:literal, $($bindings:tt)*) => {{
         $crate::eval!($crate::Ruby::get().unwrap(), $str, $($bindings)*)
     }};
+    ($ruby:expr, binding = $binding:expr, $str:literal $(, $($bindings:tt)*)?) => {{
+        use $crate::{r_string::IntoRString, value::ReprValue};
+        let binding = $binding.as_value();
+        $crate::bind!(binding, $($($bindings)*)?)
+            .and_then(|()| binding.funcall("eval", ($str.into_r_string_with(&$ruby),)))
+    }};
     ($ruby:expr, $str:literal) => {{
         use $crate::{r_string::IntoRString, value::ReprValue};
         $ruby
@@ -1944,48 +2044,44 @@ macro_rules! eval {
     ($ruby:expr, $str:literal, $($bindings:tt)*) => {{
         use $crate::{r_string::IntoRString, value::ReprValue};
         let binding = $ruby.eval::<$crate::Value>("binding").unwrap();
-        $crate::bind!(binding, $($bindings)*);
-        binding.funcall("eval", ($str.into_r_string_with(&$ruby),))
+        $crate::bind!(binding, $($bindings)*)
+            .and_then(|()| binding.funcall("eval", ($str.into_r_string_with(&$ruby),)))
     }};
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! bind {
-    ($binding:ident,) => {};
+    ($binding:ident,) => {
+        Ok::<(), $crate::Error>(())
+    };
+    ($binding:ident, ** $locals:expr) => {{
+        let locals: $crate::RHash = $locals;
+        locals.foreach(|k: $crate::Value, v: $crate::Value| {
+            let _: $crate::Value = $binding.funcall("local_variable_set", (k, v))?;
+            Ok($crate::r_hash::ForEach::Continue)
+        })
+    }};
+    ($binding:ident, ** $locals:expr, $($rest:tt)*) => {{
+        $crate::bind!($binding, **$locals).and_then(|()| $crate::bind!($binding, $($rest)*))
+    }};
     ($binding:ident, $k:ident = $v:expr) => {{
         use $crate::symbol::IntoSymbol;
-        let _: $crate::Value = $binding.funcall(
-            "local_variable_set",
-            (stringify!($k).into_symbol_with(&$crate::Ruby::get_with($binding)), $v),
-        )
-        .unwrap();
+        $binding
+            .funcall::<_, _, $crate::Value>(
+                "local_variable_set",
+                (stringify!($k).into_symbol_with(&$crate::Ruby::get_with($binding)), $v),
+            )
+            .map(|_| ())
     }};
     ($binding:ident, $k:ident) => {{
-        use $crate::symbol::IntoSymbol;
-        let _: $crate::Value = $binding.funcall(
-            "local_variable_set",
-            (stringify!($k).into_symbol_with(&$crate::Ruby::get_with($binding)), $k),
-        )
-        .unwrap();
+        $crate::bind!($binding, $k = $k)
     }};
     ($binding:ident, $k:ident = $v:expr, $($rest:tt)*) => {{
-        use $crate::symbol::IntoSymbol;
-        let _: $crate::Value = $binding.funcall(
-            "local_variable_set",
-            (stringify!($k).into_symbol_with(&$crate::Ruby::get_with($binding)), $v),
-        )
-        .unwrap();
-        $crate::bind!($binding, $($rest)*);
+        $crate::bind!($binding, $k = $v).and_then(|()| $crate::bind!($binding, $($rest)*))
     }};
     ($binding:ident, $k:ident, $($rest:tt)*) => {{
-        use $crate::symbol::IntoSymbol;
-        let _: $crate::Value = $binding.funcall(
-            "local_variable_set",
-            (stringify!($k).into_symbol_with(&$crate::Ruby::get_with($binding)), $k),
-        )
-        .unwrap();
-        $crate::bind!($binding, $($rest)*);
+        $crate::bind!($binding, $k = $k).and_then(|()| $crate::bind!($binding, $($rest)*))
     }};
 }
 
@@ -2068,6 +2164,70 @@ macro_rules! rb_assert {
     }};
 }
 
+/// Asserts a Ruby expression evaluates to a value equal (with Ruby's `==`) to
+/// a Rust value.
+///
+/// On failure the panic message includes the `inspect` output of both values.
+///
+/// ``` should_panic
+/// # let _cleanup = unsafe { magnus::embed::init() };
+/// magnus::assert_ruby_eq!("[1, 2].sum", 4);
+/// ```
+///
+/// Outputs:
+///
+/// ``` plain
+/// thread 'main' panicked at 'assertion `[1, 2].sum == expected` failed
+///   actual: 3
+/// expected: 4', src/lib.rs:5:1
+/// ```
+///
+/// # Panics
+///
+/// Panics if called from a non-Ruby thread.
+///
+/// # Examples
+///
+/// ```
+/// # let _cleanup = unsafe { magnus::embed::init() };
+/// magnus::assert_ruby_eq!("1 + 2", 3);
+/// ```
+///
+/// Passing [`Ruby`] to avoid the Ruby thread check, and making local variables
+/// accessible to Ruby:
+///
+/// ```
+/// # let _cleanup = unsafe { magnus::embed::init() };
+/// let ruby = magnus::Ruby::get().unwrap();
+/// let a = "foo";
+/// magnus::assert_ruby_eq!(ruby, "a.upcase + b", "FOObar", a, b = "bar");
+/// ```
+#[macro_export]
+macro_rules! assert_ruby_eq {
+    ($expr:literal, $expected:expr) => {{
+        $crate::assert_ruby_eq!($crate::Ruby::get().unwrap(), $expr, $expected)
+    }};
+    ($expr:literal, $expected:expr, $($bindings:tt)*) => {{
+        $crate::assert_ruby_eq!($crate::Ruby::get().unwrap(), $expr, $expected, $($bindings)*)
+    }};
+    ($ruby:expr, $expr:literal, $expected:expr) => {{
+        $crate::assert_ruby_eq!($ruby, $expr, $expected,)
+    }};
+    ($ruby:expr, $expr:literal, $expected:expr, $($bindings:tt)*) => {{
+        let msg: Option<String> = $crate::eval!($ruby, r#"
+            __actual__ = binding.eval(__assert_exp__)
+            unless __actual__ == __expected__
+              "assertion `#{__assert_exp__} == expected` failed\n" \
+                "  actual: #{__actual__.inspect}\n" \
+                "expected: #{__expected__.inspect}"
+            end
+        "#, __assert_exp__ = $expr, __expected__ = $expected, $($bindings)*).unwrap();
+        if let Some(msg) = msg {
+            panic!("{}", msg)
+        };
+    }};
+}
+
 /// # Globals
 ///
 /// Functions for defining global variables, constants, etc, as well as
@@ -2151,34 +2311,36 @@ impl Ruby {
     /// # Examples
     ///
     /// ```
-    /// use magnus::{prelude::*, rb_assert, Error, Ruby};
+    /// use magnus::{rb_assert, Error, Ruby, Value};
     ///
     /// fn example(ruby: &Ruby) -> Result<(), Error> {
     ///     let v = ruby.define_variable("example", 42)?;
     ///     rb_assert!(ruby, "$example == 42");
     ///
-    ///     // safe as long as another thread isn't modifying v
-    ///     unsafe {
-    ///         *v = ruby.str_new("answer").as_value();
-    ///     }
+    ///     v.set(ruby.str_new("answer"));
     ///     rb_assert!(ruby, r#"$example == "answer""#);
     ///
+    ///     let _: Value = ruby.eval(r#"$example = "changed""#)?;
+    ///     assert_eq!(v.get::<String>()?, "changed");
+    ///
     ///     Ok(())
     /// }
     /// # Ruby::init(example).unwrap()
     /// ```
-    pub fn define_variable<T>(&self, name: &str, initial: T) -> Result<*mut Value, Error>
+    pub fn define_variable<T>(&self, name: &str, initial: T) -> Result<GlobalVariable, Error>
     where
         T: IntoValue,
     {
         let initial = self.into_value(initial);
         debug_assert_value!(initial);
         let name = CString::new(name).unwrap();
+        // Ruby keeps a pointer to this for the life of the VM, so it must be
+        // leaked
         let ptr = Box::into_raw(Box::new(initial));
         unsafe {
             rb_define_variable(name.as_ptr(), ptr as *mut VALUE);
+            Ok(GlobalVariable::from_ptr_unchecked(ptr))
         }
-        Ok(ptr)
     }
 
     /// Alias the global variable `src` as `dst`.
@@ -2215,6 +2377,152 @@ impl Ruby {
         Ok(())
     }
 
+    /// Define a virtual global variable, with a value computed by `getter`,
+    /// and assignment handled by `setter`.
+    ///
+    /// As with [`define_variable`](Ruby::define_variable) the preceeding `$`
+    /// of the global variable's name is optional.
+    ///
+    /// Errors with an `ArgumentError` if `name` contains a null byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicI64, Ordering};
+    ///
+    /// use magnus::{rb_assert, Error, Ruby, TryConvert, Value};
+    ///
+    /// static COUNTER: AtomicI64 = AtomicI64::new(0);
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_virtual_variable(
+    ///         "counter",
+    ///         |_ruby| COUNTER.load(Ordering::Relaxed),
+    ///         |_ruby, val: Value| -> Result<(), Error> {
+    ///             COUNTER.store(i64::try_convert(val)?, Ordering::Relaxed);
+    ///             Ok(())
+    ///         },
+    ///     )?;
+    ///     ruby.alias_variable("$count", "$counter")?;
+    ///
+    ///     let _: Value = ruby.eval("$counter = 41; $count += 1")?;
+    ///     assert_eq!(COUNTER.load(Ordering::Relaxed), 42);
+    ///     rb_assert!(ruby, "$counter == 42");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn define_virtual_variable<G, GR, S, SR>(
+        &self,
+        name: &str,
+        mut getter: G,
+        mut setter: S,
+    ) -> Result<(), Error>
+    where
+        G: 'static + Send + FnMut(&Ruby) -> GR,
+        GR: BlockReturn,
+        S: 'static + Send + FnMut(&Ruby, Value) -> SR,
+        SR: BlockReturn,
+    {
+        let getter = self.proc_from_fn(move |ruby, _args, _block| getter(ruby));
+        let setter = self.proc_from_fn(move |ruby, args, _block| setter(ruby, args[0]));
+        self.define_hooked_variable(name, getter, Some(setter))
+    }
+
+    /// Define a read-only virtual global variable, with a value computed by
+    /// `getter`.
+    ///
+    /// Attempting to assign to the variable from Ruby will raise a
+    /// `NameError`.
+    ///
+    /// As with [`define_variable`](Ruby::define_variable) the preceeding `$`
+    /// of the global variable's name is optional.
+    ///
+    /// Errors with an `ArgumentError` if `name` contains a null byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_readonly_virtual_variable("example_name", |ruby| {
+    ///         ruby.str_new("example")
+    ///     })?;
+    ///     rb_assert!(ruby, r#"$example_name == "example""#);
+    ///     rb_assert!(ruby, r#"($example_name = "other" rescue $!).is_a?(NameError)"#);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn define_readonly_virtual_variable<G, R>(
+        &self,
+        name: &str,
+        mut getter: G,
+    ) -> Result<(), Error>
+    where
+        G: 'static + Send + FnMut(&Ruby) -> R,
+        R: BlockReturn,
+    {
+        let getter = self.proc_from_fn(move |ruby, _args, _block| getter(ruby));
+        self.define_hooked_variable(name, getter, None)
+    }
+
+    fn define_hooked_variable(
+        &self,
+        name: &str,
+        getter: Proc,
+        setter: Option<Proc>,
+    ) -> Result<(), Error> {
+        // `data` points to an Array of the getter and setter procs. The
+        // `data` pointer is passed to the getter and setter, even when
+        // accessed through an alias, where the `id` would differ.
+        unsafe extern "C" fn get(_id: ID, data: *mut VALUE) -> VALUE {
+            let procs = RArray::from_rb_value_unchecked(*data);
+            match procs.entry::<Proc>(0).and_then(|p| p.call::<_, Value>(())) {
+                Ok(val) => val.as_rb_value(),
+                Err(e) => raise(e),
+            }
+        }
+
+        unsafe extern "C" fn set(val: VALUE, _id: ID, data: *mut VALUE) {
+            let procs = RArray::from_rb_value_unchecked(*data);
+            let res = procs
+                .entry::<Proc>(1)
+                .and_then(|p| p.call::<_, Value>((Value::new(val),)));
+            if let Err(e) = res {
+                raise(e)
+            }
+        }
+
+        let name = CString::new(name).map_err(|e| {
+            Error::new(
+                self.exception_arg_error(),
+                format!("string contains null byte at {}", e.nul_position()),
+            )
+        })?;
+        let procs = self.ary_new_capa(2);
+        procs.push(getter)?;
+        if let Some(setter) = setter {
+            procs.push(setter)?;
+        }
+        // Ruby keeps a pointer to this for the life of the VM, and marks the
+        // value it points to, so it must be leaked
+        let ptr = Box::into_raw(Box::new(procs.as_rb_value()));
+        let setter_func = if procs.len() > 1 {
+            set as unsafe extern "C" fn(VALUE, ID, *mut VALUE)
+        } else {
+            rb_gvar_readonly_setter
+        };
+        protect(|| {
+            unsafe { rb_define_hooked_variable(name.as_ptr(), ptr, Some(get), Some(setter_func)) };
+            self.qnil()
+        })?;
+        Ok(())
+    }
+
     /// Define a global constant.
     ///
     /// # Examples
@@ -2577,7 +2885,9 @@ pub fn define_variable<T>(name: &str, initial: T) -> Result<*mut Value, Error>
 where
     T: IntoValue,
 {
-    get_ruby!().define_variable(name, initial)
+    get_ruby!()
+        .define_variable(name, initial)
+        .map(GlobalVariable::as_ptr)
 }
 
 /// Define a global constant.
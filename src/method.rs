@@ -4,24 +4,53 @@
 #![allow(clippy::many_single_char_names)]
 #![allow(clippy::missing_safety_doc)]
 
-use std::{ffi::c_void, os::raw::c_int, panic::AssertUnwindSafe, slice};
+use std::{
+    ffi::c_void,
+    os::raw::c_int,
+    panic::{AssertUnwindSafe, UnwindSafe},
+    slice,
+};
 
+use rb_sys::rb_keyword_given_p;
 use seq_macro::seq;
 
 use crate::{
     block::{
-        do_yield_iter, do_yield_splat_iter, do_yield_values_iter, Proc, Yield, YieldSplat,
-        YieldValues,
+        do_yield_iter, do_yield_splat_iter, do_yield_values_iter, do_yield_with_iter, Proc, Yield,
+        YieldControl, YieldSplat, YieldValues, YieldWith,
     },
     error::{raise, Error, IntoError},
     into_value::{ArgList, IntoValue},
     r_array::RArray,
+    r_hash::RHash,
+    trace,
     try_convert::TryConvert,
     value::{ReprValue, Value},
     Ruby,
 };
 
-mod private {
+/// Like [`std::panic::catch_unwind`], but also traces the call.
+fn catch_unwind<F, T>(func: F) -> std::thread::Result<T>
+where
+    F: FnOnce() -> T + UnwindSafe,
+{
+    std::panic::catch_unwind(|| {
+        let _span = trace::method();
+        func()
+    })
+}
+
+/// Convert a method argument to `T`.
+#[inline]
+fn convert<T>(val: Value) -> Result<T, Error>
+where
+    T: TryConvert,
+{
+    let _span = trace::convert::<T>();
+    T::try_convert(val)
+}
+
+pub(crate) mod private {
     use super::*;
 
     pub unsafe trait Method {
@@ -180,6 +209,36 @@ mod private {
         }
     }
 
+    impl<I, T, F, R, E> ReturnValue for Result<YieldWith<I, F>, E>
+    where
+        I: Iterator<Item = T>,
+        T: IntoValue,
+        F: FnMut(Value) -> Result<YieldControl<R>, Error>,
+        R: IntoValue,
+        E: IntoError,
+    {
+        fn into_return_value(self) -> Result<Value, Error> {
+            let ruby = unsafe { Ruby::get_unchecked() };
+            match self {
+                Ok(YieldWith::Iter(iter, func)) => unsafe { do_yield_with_iter(iter, func) },
+                Ok(YieldWith::Enumerator(e)) => Ok(e.into_value_with(&ruby)),
+                Err(err) => Err(err.into_error(&ruby)),
+            }
+        }
+    }
+
+    impl<I, T, F, R> ReturnValue for YieldWith<I, F>
+    where
+        I: Iterator<Item = T>,
+        T: IntoValue,
+        F: FnMut(Value) -> Result<YieldControl<R>, Error>,
+        R: IntoValue,
+    {
+        fn into_return_value(self) -> Result<Value, Error> {
+            Ok::<Self, Error>(self).into_return_value()
+        }
+    }
+
     pub trait InitReturn {
         fn into_init_return(self) -> Result<(), Error>;
     }
@@ -258,22 +317,30 @@ impl<T> Method for T where T: private::Method {}
 /// * [`Yield<I>`]
 /// * [`YieldValues<I>`]
 /// * [`YieldSplat<I>`]
+/// * [`YieldWith<I, F>`]
 /// * `Result<T, magnus::Error>`
 /// * `Result<Yield<I>, magnus::Error>`
 /// * `Result<YieldValues<I>, magnus::Error>`
 /// * `Result<YieldSplat<I>, magnus::Error>`
+/// * `Result<YieldWith<I, F>, magnus::Error>`
 ///
-/// where `I` implements `Iterator<Item = T>` and `T` implements [`IntoValue`].
+/// where `I` implements `Iterator<Item = T>` and `T` implements [`IntoValue`],
+/// and `F` implements `FnMut(Value) -> Result<YieldControl<R>, magnus::Error>`
+/// where `R` implements [`IntoValue`].
 ///
 /// When is `Err(magnus::Error)` returned to Ruby it will be conveted to and
 /// raised as a Ruby exception.
 ///
 /// [`Yield`], [`YieldValues`], and [`YieldSplat`] allow returning a Rust
 /// [`Iterator`] to be bridged to Ruby method that calls a block with the
-/// elements of that [`Iterator`].
+/// elements of that [`Iterator`]. [`YieldWith`] additionally allows
+/// inspecting the block's return value to stop iteration early.
 ///
 /// Note: functions without a specified return value will return `()`. `()`
-/// implements [`IntoValue`] (converting to `nil`).
+/// implements [`IntoValue`] (converting to `nil`). `Option<T>` also
+/// implements [`IntoValue`], with `None` converting to `nil`, so functions
+/// returning `Option<T>`, `Result<Option<T>, magnus::Error>`, or
+/// `Result<(), magnus::Error>` can be wrapped as-is.
 pub trait ReturnValue: private::ReturnValue {}
 
 impl<T> ReturnValue for T where T: private::ReturnValue {}
@@ -399,7 +466,7 @@ where
 
     #[inline]
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, blockarg: Value) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let res = match catch_unwind(AssertUnwindSafe(|| {
             self.call_convert_value(argc, argv, blockarg)
         })) {
             Ok(v) => v,
@@ -436,7 +503,7 @@ where
 
     #[inline]
     unsafe fn call_handle_error(self) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value())) {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value())) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -469,7 +536,7 @@ where
 
     #[inline]
     unsafe fn call_handle_error(self) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value())) {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value())) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -501,18 +568,12 @@ where
 {
     #[inline]
     fn call_convert_value(self, rb_self: Value, args: RArray) -> Result<Value, Error> {
-        (self)(
-            TryConvert::try_convert(rb_self)?,
-            TryConvert::try_convert(args.as_value())?,
-        )
-        .into_return_value()
+        (self)(convert(rb_self)?, convert(args.as_value())?).into_return_value()
     }
 
     #[inline]
     unsafe fn call_handle_error(self, rb_self: Value, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
-            self.call_convert_value(rb_self, args)
-        })) {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(rb_self, args))) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -549,17 +610,15 @@ where
     fn call_convert_value(self, rb_self: Value, args: RArray) -> Result<Value, Error> {
         (self)(
             &Ruby::get_with(rb_self),
-            TryConvert::try_convert(rb_self)?,
-            TryConvert::try_convert(args.as_value())?,
+            convert(rb_self)?,
+            convert(args.as_value())?,
         )
         .into_return_value()
     }
 
     #[inline]
     unsafe fn call_handle_error(self, rb_self: Value, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
-            self.call_convert_value(rb_self, args)
-        })) {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(rb_self, args))) {
             Ok(v) => v,
             Err(e) => Err(Error::from_panic(e)),
         };
@@ -598,12 +657,12 @@ where
         rb_self: Value,
     ) -> Result<Value, Error> {
         let args = slice::from_raw_parts(argv, argc as usize);
-        (self)(TryConvert::try_convert(rb_self)?, args).into_return_value()
+        (self)(convert(rb_self)?, args).into_return_value()
     }
 
     #[inline]
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let res = match catch_unwind(AssertUnwindSafe(|| {
             self.call_convert_value(argc, argv, rb_self)
         })) {
             Ok(v) => v,
@@ -644,17 +703,12 @@ where
         rb_self: Value,
     ) -> Result<Value, Error> {
         let args = slice::from_raw_parts(argv, argc as usize);
-        (self)(
-            &Ruby::get_with(rb_self),
-            TryConvert::try_convert(rb_self)?,
-            args,
-        )
-        .into_return_value()
+        (self)(&Ruby::get_with(rb_self), convert(rb_self)?, args).into_return_value()
     }
 
     #[inline]
     unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let res = match catch_unwind(AssertUnwindSafe(|| {
             self.call_convert_value(argc, argv, rb_self)
         })) {
             Ok(v) => v,
@@ -693,15 +747,15 @@ macro_rules! method_n {
                 #[inline]
                 fn call_convert_value(self, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
                     (self)(
-                        TryConvert::try_convert(rb_self)?,
-                        #(TryConvert::try_convert(arg~N)?,)*
+                        convert(rb_self)?,
+                        #(convert(arg~N)?,)*
                     ).into_return_value()
                 }
 
                 #[inline]
                 unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
                     let res =
-                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        match catch_unwind(AssertUnwindSafe(|| {
                             self.call_convert_value(rb_self, #(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -739,15 +793,15 @@ macro_rules! method_n {
                 fn call_convert_value(self, rb_self: Value, #(arg~N: Value,)*) -> Result<Value, Error> {
                     (self)(
                         &Ruby::get_with(rb_self),
-                        TryConvert::try_convert(rb_self)?,
-                        #(TryConvert::try_convert(arg~N)?,)*
+                        convert(rb_self)?,
+                        #(convert(arg~N)?,)*
                     ).into_return_value()
                 }
 
                 #[inline]
                 unsafe fn call_handle_error(self, rb_self: Value, #(arg~N: Value,)*) -> Value {
                     let res =
-                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
+                        match catch_unwind(AssertUnwindSafe(|| {
                             self.call_convert_value(rb_self, #(arg~N,)*)
                         })) {
                             Ok(v) => v,
@@ -775,6 +829,289 @@ seq!(N in 0..=16 {
     method_n!(Method~N, RubyMethod~N, N);
 });
 
+fn splat_rest<'a>(ruby: &Ruby, args: &'a [Value], n: usize) -> Result<&'a [Value], Error> {
+    ruby.check_arity(args.len(), n..)?;
+    Ok(&args[n..])
+}
+
+macro_rules! method_splat_n {
+    ($name:ident, $ruby_name:ident, $n:literal) => {
+        seq!(N in 0..$n {
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// self, N arguments, and a slice of any remaining arguments,
+            /// with type conversions and error handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $name<RbSelf, #(T~N,)* Res>
+            where
+                Self: Sized + Fn(RbSelf, #(T~N,)* &[Value]) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    argc: c_int,
+                    argv: *const Value,
+                    rb_self: Value,
+                ) -> Result<Value, Error> {
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let rest = splat_rest(&Ruby::get_with(rb_self), args, $n)?;
+                    (self)(
+                        convert(rb_self)?,
+                        #(convert(args[N])?,)*
+                        rest,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(argc, argv, rb_self)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* Res> $name<RbSelf, #(T~N,)* Res> for Func
+            where
+                Func: Fn(RbSelf, #(T~N,)* &[Value]) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), self, N arguments, and a slice of any
+            /// remaining arguments, with type conversions and error handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_name<RbSelf, #(T~N,)* Res>
+            where
+                Self: Sized + Fn(&Ruby, RbSelf, #(T~N,)* &[Value]) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    argc: c_int,
+                    argv: *const Value,
+                    rb_self: Value,
+                ) -> Result<Value, Error> {
+                    let ruby = Ruby::get_with(rb_self);
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let rest = splat_rest(&ruby, args, $n)?;
+                    (self)(
+                        &ruby,
+                        convert(rb_self)?,
+                        #(convert(args[N])?,)*
+                        rest,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, argc: c_int, argv: *const Value, rb_self: Value) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(argc, argv, rb_self)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* Res> $ruby_name<RbSelf, #(T~N,)* Res> for Func
+            where
+                Func: Fn(&Ruby, RbSelf, #(T~N,)* &[Value]) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+        });
+    }
+}
+
+seq!(N in 1..=15 {
+    method_splat_n!(MethodSplat~N, RubyMethodSplat~N, N);
+});
+
+// Split the keywords from `args` if `kw`, check the number of remaining
+// arguments is between `req` and the number accepted, and pad with `nil` for
+// any omitted optional arguments. When `kw` the last element of the result
+// is the keywords, or an empty Hash if none were passed.
+fn opt_args<const N: usize>(
+    ruby: &Ruby,
+    req: usize,
+    kw: bool,
+    mut args: &[Value],
+) -> Result<[Value; N], Error> {
+    let mut res = [ruby.qnil().as_value(); N];
+    let max = if kw { N - 1 } else { N };
+    if kw {
+        let mut keywords = None;
+        if unsafe { rb_keyword_given_p() } != 0 {
+            if let Some((last, rest)) = args.split_last() {
+                if let Some(hash) = RHash::from_value(*last) {
+                    keywords = Some(hash);
+                    args = rest;
+                }
+            }
+        }
+        res[max] = keywords.unwrap_or_else(|| ruby.hash_new()).as_value();
+    }
+    ruby.check_arity(args.len(), req..=max)?;
+    res[..args.len()].copy_from_slice(args);
+    Ok(res)
+}
+
+macro_rules! method_opt_n {
+    ($name:ident, $ruby_name:ident, $n:literal) => {
+        seq!(N in 0..$n {
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// self and N arguments, some of which may be optional or keywords,
+            /// with type conversions and error handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $name<RbSelf, #(T~N,)* Res>
+            where
+                Self: Sized + Fn(RbSelf, #(T~N,)*) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value, rb_self: Value,
+                ) -> Result<Value, Error> {
+                    let ruby = Ruby::get_with(rb_self);
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let args = opt_args::<$n>(&ruby, req, kw, args)?;
+                    (self)(
+                        convert(rb_self)?,
+                        #(convert(args[N])?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value, rb_self: Value,
+                ) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(req, kw, argc, argv, rb_self)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* Res> $name<RbSelf, #(T~N,)* Res> for Func
+            where
+                Func: Fn(RbSelf, #(T~N,)*) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), self, and N arguments, some of which may be
+            /// optional or keywords, with type conversions and error handling.
+            ///
+            /// See the [`method`](crate::method!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_name<RbSelf, #(T~N,)* Res>
+            where
+                Self: Sized + Fn(&Ruby, RbSelf, #(T~N,)*) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value, rb_self: Value,
+                ) -> Result<Value, Error> {
+                    let ruby = Ruby::get_with(rb_self);
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let args = opt_args::<$n>(&ruby, req, kw, args)?;
+                    (self)(
+                        &ruby,
+                        convert(rb_self)?,
+                        #(convert(args[N])?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value, rb_self: Value,
+                ) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(req, kw, argc, argv, rb_self)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, RbSelf, #(T~N,)* Res> $ruby_name<RbSelf, #(T~N,)* Res> for Func
+            where
+                Func: Fn(&Ruby, RbSelf, #(T~N,)*) -> Res,
+                RbSelf: TryConvert,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+        });
+    }
+}
+
+seq!(N in 1..=16 {
+    method_opt_n!(MethodOpt~N, RubyMethodOpt~N, N);
+});
+
 /// Wrap a Rust function item with Ruby type conversion and error handling.
 ///
 /// This macro wraps the given function and returns a function pointer
@@ -810,6 +1147,36 @@ seq!(N in 0..=16 {
 /// return value (i.e. return `()`) for a function that returns `nil` to Ruby.
 /// See [`ReturnValue`] for more details on what can be returned.
 ///
+/// Functions taking a fixed number of leading arguments followed by any number
+/// of additional arguments can be wrapped by following an arity between `1`
+/// and `15` with `..`. The leading arguments are converted as above, and any
+/// remaining arguments are passed as a slice of [`Value`]s. At least the
+/// leading arguments must be supplied, otherwise an `ArgumentError` is raised.
+///
+/// | Arity   | Signature                                                              |
+/// |---------|------------------------------------------------------------------------|
+/// | `1, ..` | `fn(rb_self: T, arg1: U, rest: &[Value]) -> Result<R, Error>`          |
+/// | `2, ..` | `fn(rb_self: T, arg1: U, arg2: V, rest: &[Value]) -> Result<R, Error>` |
+/// |     ... | ...                                                                    |
+///
+/// Functions with optional trailing arguments can be wrapped with an arity
+/// range of `required..=max`, with `max` between `1` and `16` and
+/// `required` no greater than `max` (checked at compile time). Omitted
+/// optional arguments are converted from `nil`, so should be types such as
+/// `Option<T>`. Following the range with `kw` passes any keywords as an extra
+/// final argument, converted from a Hash (empty if no keywords were passed),
+/// for example to an [`RHash`](crate::RHash) or a
+/// [`KeywordArgs<T>`](crate::scan_args::KeywordArgs). An `ArgumentError` is
+/// raised if the number of positional arguments is outside the range.
+///
+/// | Arity       | Signature                                                             |
+/// |-------------|-----------------------------------------------------------------------|
+/// | `0..=1`     | `fn(rb_self: T, arg1: Option<U>) -> Result<R, Error>`                 |
+/// | `1..=2`     | `fn(rb_self: T, arg1: U, arg2: Option<V>) -> Result<R, Error>`        |
+/// | `1..=1, kw` | `fn(rb_self: T, arg1: U, kw: K) -> Result<R, Error>`                  |
+/// | `1..=2, kw` | `fn(rb_self: T, arg1: U, arg2: Option<V>, kw: K) -> Result<R, Error>` |
+/// |         ... | ...                                                                   |
+///
 /// See the [`function`](crate::function!) macro for cases where there is no
 /// need to handle the `self` argument.
 ///
@@ -831,6 +1198,73 @@ seq!(N in 0..=16 {
 /// # let cleanup = unsafe { magnus::embed::init() };
 /// # init(&cleanup).unwrap();
 /// ```
+///
+/// ```
+/// use magnus::{method, prelude::*, rb_assert, Error, Ruby, Value};
+///
+/// fn rb_tag(_rb_self: Value, name: String, attrs: &[Value]) -> Result<String, Error> {
+///     let attrs = attrs
+///         .iter()
+///         .map(|v| v.to_r_string().and_then(|s| s.to_string()))
+///         .collect::<Result<Vec<_>, _>>()?;
+///     Ok(format!("<{} {}>", name, attrs.join(" ")))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.class_object()
+///         .define_method("tag", method!(rb_tag, 1, ..))?;
+///     rb_assert!(ruby, r#"tag("a", "href", "title") == "<a href title>""#);
+///     rb_assert!(ruby, r#"begin; tag; false; rescue ArgumentError; true; end"#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+///
+/// ```
+/// use magnus::{
+///     method, prelude::*, r_hash::FromRHash, rb_assert, scan_args::KeywordArgs, Error, RHash,
+///     Ruby,
+/// };
+///
+/// struct PadOptions {
+///     with: Option<String>,
+/// }
+///
+/// impl FromRHash for PadOptions {
+///     fn from_r_hash(hash: RHash) -> Result<Self, Error> {
+///         let ruby = Ruby::get_with(hash);
+///         Ok(Self {
+///             with: hash.lookup(ruby.to_symbol("with"))?,
+///         })
+///     }
+/// }
+///
+/// fn rb_pad(
+///     rb_self: String,
+///     width: usize,
+///     left: Option<bool>,
+///     kw: KeywordArgs<PadOptions>,
+/// ) -> String {
+///     let fill = kw.0.with.unwrap_or_else(|| String::from(" "));
+///     let padding = fill.repeat(width.saturating_sub(rb_self.len()));
+///     if left.unwrap_or(false) {
+///         padding + &rb_self
+///     } else {
+///         rb_self + &padding
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.class_string().define_method("pad", method!(rb_pad, 1..=2, kw))?;
+///     rb_assert!(ruby, r#""ab".pad(4) == "ab  ""#);
+///     rb_assert!(ruby, r#""ab".pad(4, true, with: "-") == "--ab""#);
+///     rb_assert!(ruby, r#"("ab".pad rescue $!).is_a?(ArgumentError)"#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 #[macro_export]
 macro_rules! method {
     ($name:expr, -2) => {{
@@ -1300,262 +1734,1226 @@ macro_rules! method {
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, $arity:expr) => {
-        compile_error!("arity must be an integer literal between -2..=16")
-    };
-}
-
-/// Helper trait for wrapping a function as a Ruby method ignoring self and
-/// taking a Ruby array of arguments, with type conversions and error handling.
-///
-/// See the [`function`](crate::function!) macro.
-#[doc(hidden)]
-pub trait FunctionRbAry<Args, Res>
-where
-    Self: Sized + Fn(Args) -> Res,
-    Args: TryConvert,
-    Res: ReturnValue,
-{
-    #[inline]
-    fn call_convert_value(self, args: RArray) -> Result<Value, Error> {
-        (self)(TryConvert::try_convert(args.as_value())?).into_return_value()
-    }
-
-    #[inline]
-    unsafe fn call_handle_error(self, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args)))
-        {
-            Ok(v) => v,
-            Err(e) => Err(Error::from_panic(e)),
-        };
-        match res {
-            Ok(v) => v,
-            Err(e) => raise(e),
+    ($name:expr, 1, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat1, RubyMethodSplat1};
+            $name.call_handle_error(argc, argv, rb_self)
         }
-    }
-}
-
-impl<Func, Args, Res> FunctionRbAry<Args, Res> for Func
-where
-    Func: Fn(Args) -> Res,
-    Args: TryConvert,
-    Res: ReturnValue,
-{
-}
-
-/// Helper trait for wrapping a function as a Ruby method taking
-/// [`&Ruby`](Ruby), ignoring self, and taking a Ruby array of arguments, with
-/// type conversions and error handling.
-///
-/// See the [`function`](crate::function!) macro.
-#[doc(hidden)]
-pub trait RubyFunctionRbAry<Args, Res>
-where
-    Self: Sized + Fn(&Ruby, Args) -> Res,
-    Args: TryConvert,
-    Res: ReturnValue,
-{
-    #[inline]
-    fn call_convert_value(self, args: RArray) -> Result<Value, Error> {
-        (self)(
-            &Ruby::get_with(args),
-            TryConvert::try_convert(args.as_value())?,
-        )
-        .into_return_value()
-    }
-
-    #[inline]
-    unsafe fn call_handle_error(self, args: RArray) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args)))
-        {
-            Ok(v) => v,
-            Err(e) => Err(Error::from_panic(e)),
-        };
-        match res {
-            Ok(v) => v,
-            Err(e) => raise(e),
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 2, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat2, RubyMethodSplat2};
+            $name.call_handle_error(argc, argv, rb_self)
         }
-    }
-}
-
-impl<Func, Args, Res> RubyFunctionRbAry<Args, Res> for Func
-where
-    Func: Fn(&Ruby, Args) -> Res,
-    Args: TryConvert,
-    Res: ReturnValue,
-{
-}
-
-/// Helper trait for wrapping a function as a Ruby method ignoring self and
-/// taking a slice of arguments, with type conversions and error handling.
-///
-/// See the [`function`](crate::function!) macro.
-#[doc(hidden)]
-pub trait FunctionCAry<Res>
-where
-    Self: Sized + Fn(&[Value]) -> Res,
-    Res: ReturnValue,
-{
-    #[inline]
-    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
-        let args = slice::from_raw_parts(argv, argc as usize);
-        (self)(args).into_return_value()
-    }
-
-    #[inline]
-    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
-            self.call_convert_value(argc, argv)
-        })) {
-            Ok(v) => v,
-            Err(e) => Err(Error::from_panic(e)),
-        };
-        match res {
-            Ok(v) => v,
-            Err(e) => raise(e),
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 3, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat3, RubyMethodSplat3};
+            $name.call_handle_error(argc, argv, rb_self)
         }
-    }
-}
-
-impl<Func, Res> FunctionCAry<Res> for Func
-where
-    Func: Fn(&[Value]) -> Res,
-    Res: ReturnValue,
-{
-}
-
-/// Helper trait for wrapping a function as a Ruby method taking
-/// [`&Ruby`](Ruby), ignoring self, and taking a slice of arguments, with type
-/// conversions and error handling.
-///
-/// See the [`function`](crate::function!) macro.
-#[doc(hidden)]
-pub trait RubyFunctionCAry<Res>
-where
-    Self: Sized + Fn(&Ruby, &[Value]) -> Res,
-    Res: ReturnValue,
-{
-    #[inline]
-    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
-        let args = slice::from_raw_parts(argv, argc as usize);
-        (self)(&Ruby::get_unchecked(), args).into_return_value()
-    }
-
-    #[inline]
-    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
-        let res = match std::panic::catch_unwind(AssertUnwindSafe(|| {
-            self.call_convert_value(argc, argv)
-        })) {
-            Ok(v) => v,
-            Err(e) => Err(Error::from_panic(e)),
-        };
-        match res {
-            Ok(v) => v,
-            Err(e) => raise(e),
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 4, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat4, RubyMethodSplat4};
+            $name.call_handle_error(argc, argv, rb_self)
         }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 5, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat5, RubyMethodSplat5};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 6, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat6, RubyMethodSplat6};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 7, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat7, RubyMethodSplat7};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 8, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat8, RubyMethodSplat8};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 9, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat9, RubyMethodSplat9};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 10, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat10, RubyMethodSplat10};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 11, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat11, RubyMethodSplat11};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 12, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat12, RubyMethodSplat12};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 13, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat13, RubyMethodSplat13};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 14, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat14, RubyMethodSplat14};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 15, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodSplat15, RubyMethodSplat15};
+            $name.call_handle_error(argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 1) => {{
+        const _: () = assert!($req <= 1, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt1, RubyMethodOpt1};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 2) => {{
+        const _: () = assert!($req <= 2, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt2, RubyMethodOpt2};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 3) => {{
+        const _: () = assert!($req <= 3, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt3, RubyMethodOpt3};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 4) => {{
+        const _: () = assert!($req <= 4, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt4, RubyMethodOpt4};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 5) => {{
+        const _: () = assert!($req <= 5, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt5, RubyMethodOpt5};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 6) => {{
+        const _: () = assert!($req <= 6, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt6, RubyMethodOpt6};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 7) => {{
+        const _: () = assert!($req <= 7, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt7, RubyMethodOpt7};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 8) => {{
+        const _: () = assert!($req <= 8, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt8, RubyMethodOpt8};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 9) => {{
+        const _: () = assert!($req <= 9, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt9, RubyMethodOpt9};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 10) => {{
+        const _: () = assert!($req <= 10, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt10, RubyMethodOpt10};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 11) => {{
+        const _: () = assert!($req <= 11, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt11, RubyMethodOpt11};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 12) => {{
+        const _: () = assert!($req <= 12, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt12, RubyMethodOpt12};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 13) => {{
+        const _: () = assert!($req <= 13, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt13, RubyMethodOpt13};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 14) => {{
+        const _: () = assert!($req <= 14, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt14, RubyMethodOpt14};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 15) => {{
+        const _: () = assert!($req <= 15, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt15, RubyMethodOpt15};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 16) => {{
+        const _: () = assert!($req <= 16, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt16, RubyMethodOpt16};
+            $name.call_handle_error($req, false, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 0, kw) => {{
+        const _: () = assert!($req <= 0, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt1, RubyMethodOpt1};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 1, kw) => {{
+        const _: () = assert!($req <= 1, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt2, RubyMethodOpt2};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 2, kw) => {{
+        const _: () = assert!($req <= 2, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt3, RubyMethodOpt3};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 3, kw) => {{
+        const _: () = assert!($req <= 3, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt4, RubyMethodOpt4};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 4, kw) => {{
+        const _: () = assert!($req <= 4, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt5, RubyMethodOpt5};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 5, kw) => {{
+        const _: () = assert!($req <= 5, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt6, RubyMethodOpt6};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 6, kw) => {{
+        const _: () = assert!($req <= 6, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt7, RubyMethodOpt7};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 7, kw) => {{
+        const _: () = assert!($req <= 7, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt8, RubyMethodOpt8};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 8, kw) => {{
+        const _: () = assert!($req <= 8, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt9, RubyMethodOpt9};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 9, kw) => {{
+        const _: () = assert!($req <= 9, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt10, RubyMethodOpt10};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 10, kw) => {{
+        const _: () = assert!($req <= 10, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt11, RubyMethodOpt11};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 11, kw) => {{
+        const _: () = assert!($req <= 11, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt12, RubyMethodOpt12};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 12, kw) => {{
+        const _: () = assert!($req <= 12, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt13, RubyMethodOpt13};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 13, kw) => {{
+        const _: () = assert!($req <= 13, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt14, RubyMethodOpt14};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 14, kw) => {{
+        const _: () = assert!($req <= 14, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt15, RubyMethodOpt15};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 15, kw) => {{
+        const _: () = assert!($req <= 15, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{MethodOpt16, RubyMethodOpt16};
+            $name.call_handle_error($req, true, argc, argv, rb_self)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= $max:expr $(, kw)?) => {
+        compile_error!(
+            "maximum arity must be an integer literal between 1..=16, or 0..=15 with `kw`"
+        )
+    };
+    ($name:expr, $arity:expr, ..) => {
+        compile_error!("arity must be an integer literal between 1..=15 when followed by `..`")
+    };
+    ($name:expr, $arity:expr) => {
+        compile_error!("arity must be an integer literal between -2..=16")
+    };
+}
+
+/// Helper trait for wrapping a function as a Ruby method ignoring self and
+/// taking a Ruby array of arguments, with type conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait FunctionRbAry<Args, Res>
+where
+    Self: Sized + Fn(Args) -> Res,
+    Args: TryConvert,
+    Res: ReturnValue,
+{
+    #[inline]
+    fn call_convert_value(self, args: RArray) -> Result<Value, Error> {
+        (self)(convert(args.as_value())?).into_return_value()
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, args: RArray) -> Value {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args))) {
+            Ok(v) => v,
+            Err(e) => Err(Error::from_panic(e)),
+        };
+        match res {
+            Ok(v) => v,
+            Err(e) => raise(e),
+        }
+    }
+}
+
+impl<Func, Args, Res> FunctionRbAry<Args, Res> for Func
+where
+    Func: Fn(Args) -> Res,
+    Args: TryConvert,
+    Res: ReturnValue,
+{
+}
+
+/// Helper trait for wrapping a function as a Ruby method taking
+/// [`&Ruby`](Ruby), ignoring self, and taking a Ruby array of arguments, with
+/// type conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait RubyFunctionRbAry<Args, Res>
+where
+    Self: Sized + Fn(&Ruby, Args) -> Res,
+    Args: TryConvert,
+    Res: ReturnValue,
+{
+    #[inline]
+    fn call_convert_value(self, args: RArray) -> Result<Value, Error> {
+        (self)(&Ruby::get_with(args), convert(args.as_value())?).into_return_value()
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, args: RArray) -> Value {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(args))) {
+            Ok(v) => v,
+            Err(e) => Err(Error::from_panic(e)),
+        };
+        match res {
+            Ok(v) => v,
+            Err(e) => raise(e),
+        }
+    }
+}
+
+impl<Func, Args, Res> RubyFunctionRbAry<Args, Res> for Func
+where
+    Func: Fn(&Ruby, Args) -> Res,
+    Args: TryConvert,
+    Res: ReturnValue,
+{
+}
+
+/// Helper trait for wrapping a function as a Ruby method ignoring self and
+/// taking a slice of arguments, with type conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait FunctionCAry<Res>
+where
+    Self: Sized + Fn(&[Value]) -> Res,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+        let args = slice::from_raw_parts(argv, argc as usize);
+        (self)(args).into_return_value()
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(argc, argv))) {
+            Ok(v) => v,
+            Err(e) => Err(Error::from_panic(e)),
+        };
+        match res {
+            Ok(v) => v,
+            Err(e) => raise(e),
+        }
+    }
+}
+
+impl<Func, Res> FunctionCAry<Res> for Func
+where
+    Func: Fn(&[Value]) -> Res,
+    Res: ReturnValue,
+{
+}
+
+/// Helper trait for wrapping a function as a Ruby method taking
+/// [`&Ruby`](Ruby), ignoring self, and taking a slice of arguments, with type
+/// conversions and error handling.
+///
+/// See the [`function`](crate::function!) macro.
+#[doc(hidden)]
+pub trait RubyFunctionCAry<Res>
+where
+    Self: Sized + Fn(&Ruby, &[Value]) -> Res,
+    Res: ReturnValue,
+{
+    #[inline]
+    unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+        let args = slice::from_raw_parts(argv, argc as usize);
+        (self)(&Ruby::get_unchecked(), args).into_return_value()
+    }
+
+    #[inline]
+    unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+        let res = match catch_unwind(AssertUnwindSafe(|| self.call_convert_value(argc, argv))) {
+            Ok(v) => v,
+            Err(e) => Err(Error::from_panic(e)),
+        };
+        match res {
+            Ok(v) => v,
+            Err(e) => raise(e),
+        }
+    }
+}
+
+impl<Func, Res> RubyFunctionCAry<Res> for Func
+where
+    Func: Fn(&Ruby, &[Value]) -> Res,
+    Res: ReturnValue,
+{
+}
+
+macro_rules! function_n {
+    ($name:ident, $ruby_name:ident, $n:literal) => {
+        seq!(N in 0..$n {
+            /// Helper trait for wrapping a function as a Ruby method ignoring
+            /// self and taking N arguments, with type conversions and error
+            /// handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(#(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        #(convert(arg~N)?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(#(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(#(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), ignoring self, and taking N arguments, with
+            /// type conversions and error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(&Ruby, #(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> {
+                    (self)(
+                        &Ruby::get_unchecked(),
+                        #(convert(arg~N)?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(#(arg~N,)*)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $ruby_name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(&Ruby, #(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+        });
+    }
+}
+
+seq!(N in 0..=16 {
+    function_n!(Function~N, RubyFunction~N, N);
+});
+
+macro_rules! function_splat_n {
+    ($name:ident, $ruby_name:ident, $n:literal) => {
+        seq!(N in 0..$n {
+            /// Helper trait for wrapping a function as a Ruby method ignoring
+            /// self and taking N arguments and a slice of any remaining
+            /// arguments, with type conversions and error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(#(T~N,)* &[Value]) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let rest = splat_rest(&Ruby::get_unchecked(), args, $n)?;
+                    (self)(
+                        #(convert(args[N])?,)*
+                        rest,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(argc, argv)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(#(T~N,)* &[Value]) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), ignoring self, and taking N arguments and a
+            /// slice of any remaining arguments, with type conversions and
+            /// error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(&Ruby, #(T~N,)* &[Value]) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(self, argc: c_int, argv: *const Value) -> Result<Value, Error> {
+                    let ruby = Ruby::get_unchecked();
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let rest = splat_rest(&ruby, args, $n)?;
+                    (self)(
+                        &ruby,
+                        #(convert(args[N])?,)*
+                        rest,
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(self, argc: c_int, argv: *const Value) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(argc, argv)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $ruby_name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(&Ruby, #(T~N,)* &[Value]) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+        });
+    }
+}
+
+seq!(N in 1..=15 {
+    function_splat_n!(FunctionSplat~N, RubyFunctionSplat~N, N);
+});
+
+macro_rules! function_opt_n {
+    ($name:ident, $ruby_name:ident, $n:literal) => {
+        seq!(N in 0..$n {
+            /// Helper trait for wrapping a function as a Ruby method ignoring
+            /// self and taking N arguments, some of which may be optional or
+            /// keywords, with type conversions and error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(#(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Result<Value, Error> {
+                    let ruby = Ruby::get_unchecked();
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let args = opt_args::<$n>(&ruby, req, kw, args)?;
+                    (self)(
+                        #(convert(args[N])?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(req, kw, argc, argv)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(#(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+
+            /// Helper trait for wrapping a function as a Ruby method taking
+            /// [`&Ruby`](Ruby), ignoring self, and taking N arguments, some of
+            /// which may be optional or keywords, with type conversions and
+            /// error handling.
+            ///
+            /// See the [`function`](crate::function!) macro.
+            #[doc(hidden)]
+            pub trait $ruby_name<#(T~N,)* Res>
+            where
+                Self: Sized + Fn(&Ruby, #(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {
+                #[inline]
+                unsafe fn call_convert_value(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Result<Value, Error> {
+                    let ruby = Ruby::get_unchecked();
+                    let args = slice::from_raw_parts(argv, argc as usize);
+                    let args = opt_args::<$n>(&ruby, req, kw, args)?;
+                    (self)(
+                        &ruby,
+                        #(convert(args[N])?,)*
+                    ).into_return_value()
+                }
+
+                #[inline]
+                unsafe fn call_handle_error(
+                    self,
+                    req: usize,
+                    kw: bool,
+                    argc: c_int,
+                    argv: *const Value,
+                ) -> Value {
+                    let res =
+                        match catch_unwind(AssertUnwindSafe(|| {
+                            self.call_convert_value(req, kw, argc, argv)
+                        })) {
+                            Ok(v) => v,
+                            Err(e) => Err(Error::from_panic(e)),
+                        };
+                    match res {
+                        Ok(v) => v,
+                        Err(e) => raise(e),
+                    }
+                }
+            }
+
+            impl<Func, #(T~N,)* Res> $ruby_name<#(T~N,)* Res> for Func
+            where
+                Func: Fn(&Ruby, #(T~N,)*) -> Res,
+                #(T~N: TryConvert,)*
+                Res: ReturnValue,
+            {}
+        });
     }
 }
 
-impl<Func, Res> RubyFunctionCAry<Res> for Func
-where
-    Func: Fn(&Ruby, &[Value]) -> Res,
-    Res: ReturnValue,
-{
-}
-
-macro_rules! function_n {
-    ($name:ident, $ruby_name:ident, $n:literal) => {
-        seq!(N in 0..$n {
-            /// Helper trait for wrapping a function as a Ruby method ignoring
-            /// self and taking N arguments, with type conversions and error
-            /// handling.
-            ///
-            /// See the [`function`](crate::function!) macro.
-            #[doc(hidden)]
-            pub trait $name<#(T~N,)* Res>
-            where
-                Self: Sized + Fn(#(T~N,)*) -> Res,
-                #(T~N: TryConvert,)*
-                Res: ReturnValue,
-            {
-                #[inline]
-                fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> {
-                    (self)(
-                        #(TryConvert::try_convert(arg~N)?,)*
-                    ).into_return_value()
-                }
-
-                #[inline]
-                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
-                    let res =
-                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
-                            self.call_convert_value(#(arg~N,)*)
-                        })) {
-                            Ok(v) => v,
-                            Err(e) => Err(Error::from_panic(e)),
-                        };
-                    match res {
-                        Ok(v) => v,
-                        Err(e) => raise(e),
-                    }
-                }
-            }
-
-            impl<Func, #(T~N,)* Res> $name<#(T~N,)* Res> for Func
-            where
-                Func: Fn(#(T~N,)*) -> Res,
-                #(T~N: TryConvert,)*
-                Res: ReturnValue,
-            {}
-
-            /// Helper trait for wrapping a function as a Ruby method taking
-            /// [`&Ruby`](Ruby), ignoring self, and taking N arguments, with
-            /// type conversions and error handling.
-            ///
-            /// See the [`function`](crate::function!) macro.
-            #[doc(hidden)]
-            pub trait $ruby_name<#(T~N,)* Res>
-            where
-                Self: Sized + Fn(&Ruby, #(T~N,)*) -> Res,
-                #(T~N: TryConvert,)*
-                Res: ReturnValue,
-            {
-                #[inline]
-                unsafe fn call_convert_value(self, #(arg~N: Value,)*) -> Result<Value, Error> {
-                    (self)(
-                        &Ruby::get_unchecked(),
-                        #(TryConvert::try_convert(arg~N)?,)*
-                    ).into_return_value()
-                }
-
-                #[inline]
-                unsafe fn call_handle_error(self, #(arg~N: Value,)*) -> Value {
-                    let res =
-                        match std::panic::catch_unwind(AssertUnwindSafe(|| {
-                            self.call_convert_value(#(arg~N,)*)
-                        })) {
-                            Ok(v) => v,
-                            Err(e) => Err(Error::from_panic(e)),
-                        };
-                    match res {
-                        Ok(v) => v,
-                        Err(e) => raise(e),
-                    }
-                }
-            }
-
-            impl<Func, #(T~N,)* Res> $ruby_name<#(T~N,)* Res> for Func
-            where
-                Func: Fn(&Ruby, #(T~N,)*) -> Res,
-                #(T~N: TryConvert,)*
-                Res: ReturnValue,
-            {}
-        });
-    }
-}
-
-seq!(N in 0..=16 {
-    function_n!(Function~N, RubyFunction~N, N);
+seq!(N in 1..=16 {
+    function_opt_n!(FunctionOpt~N, RubyFunctionOpt~N, N);
 });
 
 /// Wrap a Rust function item with Ruby type conversion and error handling,
@@ -1593,6 +2991,35 @@ seq!(N in 0..=16 {
 /// return value (i.e. return `()`) for a function that returns `nil` to Ruby.
 /// See [`ReturnValue`] for more details on what can be returned.
 ///
+/// Functions taking a fixed number of leading arguments followed by any number
+/// of additional arguments can be wrapped by following an arity between `1`
+/// and `15` with `..`. The leading arguments are converted as above, and any
+/// remaining arguments are passed as a slice of [`Value`]s.
+///
+/// | Arity   | Signature                                                  |
+/// |---------|------------------------------------------------------------|
+/// | `1, ..` | `fn(arg1: T, rest: &[Value]) -> Result<R, Error>`          |
+/// | `2, ..` | `fn(arg1: T, arg2: U, rest: &[Value]) -> Result<R, Error>` |
+/// |     ... | ...                                                        |
+///
+/// Functions with optional trailing arguments can be wrapped with an arity
+/// range of `required..=max`, with `max` between `1` and `16` and
+/// `required` no greater than `max` (checked at compile time). Omitted
+/// optional arguments are converted from `nil`, so should be types such as
+/// `Option<T>`. Following the range with `kw` passes any keywords as an extra
+/// final argument, converted from a Hash (empty if no keywords were passed),
+/// for example to an [`RHash`](crate::RHash) or a
+/// [`KeywordArgs<T>`](crate::scan_args::KeywordArgs). An `ArgumentError` is
+/// raised if the number of positional arguments is outside the range.
+///
+/// | Arity       | Signature                                                 |
+/// |-------------|-----------------------------------------------------------|
+/// | `0..=1`     | `fn(arg1: Option<T>) -> Result<R, Error>`                 |
+/// | `1..=2`     | `fn(arg1: T, arg2: Option<U>) -> Result<R, Error>`        |
+/// | `0..=0, kw` | `fn(kw: K) -> Result<R, Error>`                           |
+/// | `1..=2, kw` | `fn(arg1: T, arg2: Option<U>, kw: K) -> Result<R, Error>` |
+/// |         ... | ...                                                       |
+///
 /// See the [`method`](crate::method!) macro for cases where the `self`
 /// argument is required.
 ///
@@ -1610,6 +3037,56 @@ seq!(N in 0..=16 {
 /// # let cleanup = unsafe { magnus::embed::init() };
 /// # init(&cleanup);
 /// ```
+///
+/// ```
+/// use magnus::{function, rb_assert, Error, Ruby, TryConvert, Value};
+///
+/// fn sum(first: i64, rest: &[Value]) -> Result<i64, Error> {
+///     rest.iter()
+///         .try_fold(first, |acc, v| Ok(acc + i64::try_convert(*v)?))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("sum", function!(sum, 1, ..));
+///     rb_assert!(ruby, "sum(1) == 1");
+///     rb_assert!(ruby, "sum(1, 2, 3) == 6");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+///
+/// ```
+/// use magnus::{function, rb_assert, Error, RHash, Ruby};
+///
+/// fn greet(
+///     ruby: &Ruby,
+///     name: String,
+///     greeting: Option<String>,
+///     kw: RHash,
+/// ) -> Result<String, Error> {
+///     let punctuation: Option<String> = kw.lookup(ruby.to_symbol("punctuation"))?;
+///     Ok(format!(
+///         "{}, {}{}",
+///         greeting.as_deref().unwrap_or("Hello"),
+///         name,
+///         punctuation.as_deref().unwrap_or("."),
+///     ))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("greet", function!(greet, 1..=2, kw));
+///     rb_assert!(ruby, r#"greet("Alice") == "Hello, Alice.""#);
+///     rb_assert!(ruby, r#"greet("Bob", "Hi", punctuation: "!") == "Hi, Bob!""#);
+///     rb_assert!(
+///         ruby,
+///         r#"(greet rescue $!).message == "wrong number of arguments (given 0, expected 1..2)""#,
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
 #[macro_export]
 macro_rules! function {
     ($name:expr, -2) => {{
@@ -1617,16 +3094,782 @@ macro_rules! function {
             use $crate::method::{FunctionRbAry, RubyFunctionRbAry};
             $name.call_handle_error(args)
         }
-        anon as unsafe extern "C" fn($crate::Value, $crate::RArray) -> $crate::Value
+        anon as unsafe extern "C" fn($crate::Value, $crate::RArray) -> $crate::Value
+    }};
+    ($name:expr, -1) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionCAry, RubyFunctionCAry};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 0) => {{
+        unsafe extern "C" fn anon(rb_self: $crate::Value) -> $crate::Value {
+            use $crate::method::{Function0, RubyFunction0};
+            $name.call_handle_error()
+        }
+        anon as unsafe extern "C" fn($crate::Value) -> $crate::Value
+    }};
+    ($name:expr, 1) => {{
+        unsafe extern "C" fn anon(rb_self: $crate::Value, a: $crate::Value) -> $crate::Value {
+            use $crate::method::{Function1, RubyFunction1};
+            $name.call_handle_error(a)
+        }
+        anon as unsafe extern "C" fn($crate::Value, $crate::Value) -> $crate::Value
+    }};
+    ($name:expr, 2) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function2, RubyFunction2};
+            $name.call_handle_error(a, b)
+        }
+        anon as unsafe extern "C" fn($crate::Value, $crate::Value, $crate::Value) -> $crate::Value
+    }};
+    ($name:expr, 3) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function3, RubyFunction3};
+            $name.call_handle_error(a, b, c)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 4) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function4, RubyFunction4};
+            $name.call_handle_error(a, b, c, d)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 5) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function5, RubyFunction5};
+            $name.call_handle_error(a, b, c, d, e)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 6) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function6, RubyFunction6};
+            $name.call_handle_error(a, b, c, d, e, f)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 7) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function7, RubyFunction7};
+            $name.call_handle_error(a, b, c, d, e, f, g)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 8) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function8, RubyFunction8};
+            $name.call_handle_error(a, b, c, d, e, f, g, h)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 9) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function9, RubyFunction9};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 10) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function10, RubyFunction10};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 11) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function11, RubyFunction11};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 12) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+            l: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function12, RubyFunction12};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 13) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+            l: $crate::Value,
+            m: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function13, RubyFunction13};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 14) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+            l: $crate::Value,
+            m: $crate::Value,
+            n: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function14, RubyFunction14};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 15) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+            l: $crate::Value,
+            m: $crate::Value,
+            n: $crate::Value,
+            o: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function15, RubyFunction15};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 16) => {{
+        unsafe extern "C" fn anon(
+            rb_self: $crate::Value,
+            a: $crate::Value,
+            b: $crate::Value,
+            c: $crate::Value,
+            d: $crate::Value,
+            e: $crate::Value,
+            f: $crate::Value,
+            g: $crate::Value,
+            h: $crate::Value,
+            i: $crate::Value,
+            j: $crate::Value,
+            k: $crate::Value,
+            l: $crate::Value,
+            m: $crate::Value,
+            n: $crate::Value,
+            o: $crate::Value,
+            p: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{Function16, RubyFunction16};
+            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p)
+        }
+        anon as unsafe extern "C" fn(
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 1, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat1, RubyFunctionSplat1};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 2, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat2, RubyFunctionSplat2};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 3, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat3, RubyFunctionSplat3};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 4, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat4, RubyFunctionSplat4};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 5, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat5, RubyFunctionSplat5};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 6, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat6, RubyFunctionSplat6};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 7, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat7, RubyFunctionSplat7};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 8, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat8, RubyFunctionSplat8};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 9, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat9, RubyFunctionSplat9};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 10, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat10, RubyFunctionSplat10};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 11, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat11, RubyFunctionSplat11};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 12, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat12, RubyFunctionSplat12};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 13, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat13, RubyFunctionSplat13};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 14, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat14, RubyFunctionSplat14};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, 15, ..) => {{
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionSplat15, RubyFunctionSplat15};
+            $name.call_handle_error(argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 1) => {{
+        const _: () = assert!($req <= 1, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt1, RubyFunctionOpt1};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 2) => {{
+        const _: () = assert!($req <= 2, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt2, RubyFunctionOpt2};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 3) => {{
+        const _: () = assert!($req <= 3, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt3, RubyFunctionOpt3};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 4) => {{
+        const _: () = assert!($req <= 4, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt4, RubyFunctionOpt4};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 5) => {{
+        const _: () = assert!($req <= 5, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt5, RubyFunctionOpt5};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
     }};
-    ($name:expr, -1) => {{
+    ($name:expr, $req:literal ..= 6) => {{
+        const _: () = assert!($req <= 6, "more required arguments than accepted");
         unsafe extern "C" fn anon(
             argc: std::os::raw::c_int,
             argv: *const $crate::Value,
             rb_self: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{FunctionCAry, RubyFunctionCAry};
-            $name.call_handle_error(argc, argv)
+            use $crate::method::{FunctionOpt6, RubyFunctionOpt6};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
             std::os::raw::c_int,
@@ -1634,451 +3877,430 @@ macro_rules! function {
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 0) => {{
-        unsafe extern "C" fn anon(rb_self: $crate::Value) -> $crate::Value {
-            use $crate::method::{Function0, RubyFunction0};
-            $name.call_handle_error()
+    ($name:expr, $req:literal ..= 7) => {{
+        const _: () = assert!($req <= 7, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt7, RubyFunctionOpt7};
+            $name.call_handle_error($req, false, argc, argv)
         }
-        anon as unsafe extern "C" fn($crate::Value) -> $crate::Value
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
     }};
-    ($name:expr, 1) => {{
-        unsafe extern "C" fn anon(rb_self: $crate::Value, a: $crate::Value) -> $crate::Value {
-            use $crate::method::{Function1, RubyFunction1};
-            $name.call_handle_error(a)
+    ($name:expr, $req:literal ..= 8) => {{
+        const _: () = assert!($req <= 8, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt8, RubyFunctionOpt8};
+            $name.call_handle_error($req, false, argc, argv)
         }
-        anon as unsafe extern "C" fn($crate::Value, $crate::Value) -> $crate::Value
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
     }};
-    ($name:expr, 2) => {{
+    ($name:expr, $req:literal ..= 9) => {{
+        const _: () = assert!($req <= 9, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function2, RubyFunction2};
-            $name.call_handle_error(a, b)
+            use $crate::method::{FunctionOpt9, RubyFunctionOpt9};
+            $name.call_handle_error($req, false, argc, argv)
         }
-        anon as unsafe extern "C" fn($crate::Value, $crate::Value, $crate::Value) -> $crate::Value
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
+            $crate::Value,
+        ) -> $crate::Value
     }};
-    ($name:expr, 3) => {{
+    ($name:expr, $req:literal ..= 10) => {{
+        const _: () = assert!($req <= 10, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function3, RubyFunction3};
-            $name.call_handle_error(a, b, c)
+            use $crate::method::{FunctionOpt10, RubyFunctionOpt10};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 4) => {{
+    ($name:expr, $req:literal ..= 11) => {{
+        const _: () = assert!($req <= 11, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function4, RubyFunction4};
-            $name.call_handle_error(a, b, c, d)
+            use $crate::method::{FunctionOpt11, RubyFunctionOpt11};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 5) => {{
+    ($name:expr, $req:literal ..= 12) => {{
+        const _: () = assert!($req <= 12, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function5, RubyFunction5};
-            $name.call_handle_error(a, b, c, d, e)
+            use $crate::method::{FunctionOpt12, RubyFunctionOpt12};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 6) => {{
+    ($name:expr, $req:literal ..= 13) => {{
+        const _: () = assert!($req <= 13, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function6, RubyFunction6};
-            $name.call_handle_error(a, b, c, d, e, f)
+            use $crate::method::{FunctionOpt13, RubyFunctionOpt13};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 7) => {{
+    ($name:expr, $req:literal ..= 14) => {{
+        const _: () = assert!($req <= 14, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function7, RubyFunction7};
-            $name.call_handle_error(a, b, c, d, e, f, g)
+            use $crate::method::{FunctionOpt14, RubyFunctionOpt14};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 15) => {{
+        const _: () = assert!($req <= 15, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt15, RubyFunctionOpt15};
+            $name.call_handle_error($req, false, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 8) => {{
+    ($name:expr, $req:literal ..= 16) => {{
+        const _: () = assert!($req <= 16, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function8, RubyFunction8};
-            $name.call_handle_error(a, b, c, d, e, f, g, h)
+            use $crate::method::{FunctionOpt16, RubyFunctionOpt16};
+            $name.call_handle_error($req, false, argc, argv)
         }
         anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 0, kw) => {{
+        const _: () = assert!($req <= 0, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt1, RubyFunctionOpt1};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 1, kw) => {{
+        const _: () = assert!($req <= 1, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt2, RubyFunctionOpt2};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 2, kw) => {{
+        const _: () = assert!($req <= 2, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt3, RubyFunctionOpt3};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 3, kw) => {{
+        const _: () = assert!($req <= 3, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt4, RubyFunctionOpt4};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 4, kw) => {{
+        const _: () = assert!($req <= 4, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt5, RubyFunctionOpt5};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 5, kw) => {{
+        const _: () = assert!($req <= 5, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt6, RubyFunctionOpt6};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 6, kw) => {{
+        const _: () = assert!($req <= 6, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt7, RubyFunctionOpt7};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
+        ) -> $crate::Value
+    }};
+    ($name:expr, $req:literal ..= 7, kw) => {{
+        const _: () = assert!($req <= 7, "more required arguments than accepted");
+        unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
+            rb_self: $crate::Value,
+        ) -> $crate::Value {
+            use $crate::method::{FunctionOpt8, RubyFunctionOpt8};
+            $name.call_handle_error($req, true, argc, argv)
+        }
+        anon as unsafe extern "C" fn(
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 9) => {{
+    ($name:expr, $req:literal ..= 8, kw) => {{
+        const _: () = assert!($req <= 8, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function9, RubyFunction9};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i)
+            use $crate::method::{FunctionOpt9, RubyFunctionOpt9};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 10) => {{
+    ($name:expr, $req:literal ..= 9, kw) => {{
+        const _: () = assert!($req <= 9, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function10, RubyFunction10};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j)
+            use $crate::method::{FunctionOpt10, RubyFunctionOpt10};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 11) => {{
+    ($name:expr, $req:literal ..= 10, kw) => {{
+        const _: () = assert!($req <= 10, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function11, RubyFunction11};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k)
+            use $crate::method::{FunctionOpt11, RubyFunctionOpt11};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 12) => {{
+    ($name:expr, $req:literal ..= 11, kw) => {{
+        const _: () = assert!($req <= 11, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
-            l: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function12, RubyFunction12};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l)
+            use $crate::method::{FunctionOpt12, RubyFunctionOpt12};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 13) => {{
+    ($name:expr, $req:literal ..= 12, kw) => {{
+        const _: () = assert!($req <= 12, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
-            l: $crate::Value,
-            m: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function13, RubyFunction13};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m)
+            use $crate::method::{FunctionOpt13, RubyFunctionOpt13};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 14) => {{
+    ($name:expr, $req:literal ..= 13, kw) => {{
+        const _: () = assert!($req <= 13, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
-            l: $crate::Value,
-            m: $crate::Value,
-            n: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function14, RubyFunction14};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n)
+            use $crate::method::{FunctionOpt14, RubyFunctionOpt14};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 15) => {{
+    ($name:expr, $req:literal ..= 14, kw) => {{
+        const _: () = assert!($req <= 14, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
-            l: $crate::Value,
-            m: $crate::Value,
-            n: $crate::Value,
-            o: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function15, RubyFunction15};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o)
+            use $crate::method::{FunctionOpt15, RubyFunctionOpt15};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
-    ($name:expr, 16) => {{
+    ($name:expr, $req:literal ..= 15, kw) => {{
+        const _: () = assert!($req <= 15, "more required arguments than accepted");
         unsafe extern "C" fn anon(
+            argc: std::os::raw::c_int,
+            argv: *const $crate::Value,
             rb_self: $crate::Value,
-            a: $crate::Value,
-            b: $crate::Value,
-            c: $crate::Value,
-            d: $crate::Value,
-            e: $crate::Value,
-            f: $crate::Value,
-            g: $crate::Value,
-            h: $crate::Value,
-            i: $crate::Value,
-            j: $crate::Value,
-            k: $crate::Value,
-            l: $crate::Value,
-            m: $crate::Value,
-            n: $crate::Value,
-            o: $crate::Value,
-            p: $crate::Value,
         ) -> $crate::Value {
-            use $crate::method::{Function16, RubyFunction16};
-            $name.call_handle_error(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p)
+            use $crate::method::{FunctionOpt16, RubyFunctionOpt16};
+            $name.call_handle_error($req, true, argc, argv)
         }
         anon as unsafe extern "C" fn(
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
-            $crate::Value,
+            std::os::raw::c_int,
+            *const $crate::Value,
             $crate::Value,
         ) -> $crate::Value
     }};
+    ($name:expr, $req:literal ..= $max:expr $(, kw)?) => {
+        compile_error!(
+            "maximum arity must be an integer literal between 1..=16, or 0..=15 with `kw`"
+        )
+    };
+    ($name:expr, $arity:expr, ..) => {
+        compile_error!("arity must be an integer literal between 1..=15 when followed by `..`")
+    };
     ($name:expr, $arity:expr) => {
         compile_error!("arity must be an integer literal between -2..=16")
     };
@@ -0,0 +1,154 @@
+//! Splitting a Ruby extension across multiple Rust crates.
+//!
+//! Only one function in an extension can be marked with
+//! [`#[magnus::init]`](crate::init), as Ruby will only call a single init
+//! function per library. Larger projects that want to split their bindings
+//! over a number of crates can have each crate export a function that adds
+//! its init functions to a [`MethodTable`], and then run the merged table
+//! from the extension's single init function.
+//!
+//! ```
+//! use magnus::{function, method_table::MethodTable, prelude::*, Error, Ruby};
+//!
+//! // in crate `geometry`
+//! pub fn register_geometry(table: &mut MethodTable) {
+//!     table.register("geometry", init_geometry);
+//! }
+//!
+//! fn init_geometry(ruby: &Ruby) -> Result<(), Error> {
+//!     let module = ruby.define_module("Geometry")?;
+//!     module.define_module_function("area", function!(|w: f64, h: f64| w * h, 2))?;
+//!     Ok(())
+//! }
+//!
+//! // in crate `text`, which also uses `geometry`
+//! pub fn register_text(table: &mut MethodTable) {
+//!     register_geometry(table);
+//!     table.register("text", init_text);
+//! }
+//!
+//! fn init_text(ruby: &Ruby) -> Result<(), Error> {
+//!     let module = ruby.define_module("Text")?;
+//!     module.define_module_function("shout", function!(|s: String| s.to_uppercase(), 1))?;
+//!     Ok(())
+//! }
+//!
+//! // in the extension crate
+//! // #[magnus::init]
+//! fn init(ruby: &Ruby) -> Result<(), Error> {
+//!     let mut table = MethodTable::new();
+//!     register_geometry(&mut table);
+//!     register_text(&mut table);
+//!     // `geometry` was registered twice, but will only be run once
+//!     assert_eq!(table.len(), 2);
+//!     table.init(ruby)
+//! }
+//! # Ruby::init(|ruby| {
+//! #     init(ruby)?;
+//! #     magnus::rb_assert!(ruby, r#"Geometry.area(2.0, 3.0) == 6.0"#);
+//! #     magnus::rb_assert!(ruby, r#"Text.shout("hi") == "HI""#);
+//! #     Ok(())
+//! # }).unwrap()
+//! ```
+
+use crate::{error::Error, Ruby};
+
+/// A function that defines classes, modules, and methods.
+pub type InitFn = fn(&Ruby) -> Result<(), Error>;
+
+/// A collection of named init functions, to be run from a single
+/// [`#[magnus::init]`](crate::init) function.
+///
+/// Functions are run in the order they were first registered. Each name is
+/// only registered once, so crates can register their dependencies without
+/// worrying about those dependencies being initialised twice.
+///
+/// See the [module level documentation](self) for an example.
+#[derive(Clone, Debug, Default)]
+pub struct MethodTable {
+    entries: Vec<(&'static str, InitFn)>,
+}
+
+impl MethodTable {
+    /// Create a new, empty, `MethodTable`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `func` to the table under `name`.
+    ///
+    /// `name` should uniquely identify the crate or component registering
+    /// `func`, e.g. the crate's name. If `name` has already been registered
+    /// this does nothing.
+    pub fn register(&mut self, name: &'static str, func: InitFn) -> &mut Self {
+        if !self.contains(name) {
+            self.entries.push((name, func));
+        }
+        self
+    }
+
+    /// Add all the entries from `other` to the table.
+    ///
+    /// Entries with names already in the table are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{method_table::MethodTable, Error, Ruby};
+    ///
+    /// fn init_a(_ruby: &Ruby) -> Result<(), Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// fn init_b(_ruby: &Ruby) -> Result<(), Error> {
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut table = MethodTable::new();
+    /// table.register("a", init_a);
+    ///
+    /// let mut other = MethodTable::new();
+    /// other.register("a", init_a).register("b", init_b);
+    ///
+    /// table.merge(other);
+    /// assert_eq!(table.names().collect::<Vec<_>>(), ["a", "b"]);
+    /// ```
+    pub fn merge(&mut self, other: MethodTable) -> &mut Self {
+        for (name, func) in other.entries {
+            self.register(name, func);
+        }
+        self
+    }
+
+    /// Returns whether `name` has been registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.iter().any(|(n, _)| *n == name)
+    }
+
+    /// Returns the number of registered init functions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the registered names, in the order they will
+    /// be run.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.entries.iter().map(|(name, _)| *name)
+    }
+
+    /// Run each registered init function in turn.
+    ///
+    /// Stops at the first function to return an error, returning that error
+    /// with the name of the function's entry prepended to the message.
+    pub fn init(self, ruby: &Ruby) -> Result<(), Error> {
+        for (name, func) in self.entries {
+            func(ruby).map_err(|e| e.with_context(name))?;
+        }
+        Ok(())
+    }
+}
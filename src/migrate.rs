@@ -0,0 +1,492 @@
+//! Generate Rust skeletons for porting Ruby classes and modules to Magnus.
+//!
+//! Porting an existing pure-Ruby class to Rust starts with a lot of
+//! boilerplate: a wrapped struct, a stub for each method, and an init
+//! function defining them. [`skeleton`] inspects a loaded class or module and
+//! generates this boilerplate, with method signatures inferred from each
+//! method's [parameters](crate::module::Module::instance_method_info).
+//!
+//! The generated code is a starting point to be edited by hand. Every method
+//! body is `todo!()`, and every argument and return value is a [`Value`].
+//! Methods taking only required positional arguments get a matching number of
+//! `Value` arguments, all others take `&[Value]` to be parsed with
+//! [`scan_args`](crate::scan_args::scan_args).
+//!
+//! Generating a skeleton requires a running Ruby VM, so is intended to be
+//! done from a build script or `cargo xtask`-style binary with the `embed`
+//! feature enabled, e.g.:
+//!
+//! ```ignore
+//! fn main() {
+//!     let ruby = unsafe { magnus::embed::init() };
+//!     let code = magnus::migrate::skeleton_from_file(&ruby, "lib/point.rb", "Point").unwrap();
+//!     std::fs::write("src/point.rs", code).unwrap();
+//! }
+//! ```
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+};
+
+use crate::{
+    class::{Class, RClass},
+    error::Error,
+    module::{MethodInfo, Module, ParameterKind, RModule},
+    object::Object,
+    r_array::RArray,
+    symbol::Symbol,
+    try_convert::TryConvert,
+    value::{ReprValue, Value},
+    Ruby,
+};
+
+/// Generate Rust skeleton code for porting `module` to Magnus.
+///
+/// For a class this generates a struct wrapped with
+/// [`wrap`](macro@crate::wrap), an `impl` block with a stub for each method
+/// defined directly on the class (with `initialize` becoming `new`), and an
+/// `init` function defining the class and its methods. For a module this
+/// generates free functions in place of the struct and `impl` block.
+///
+/// Only methods defined directly on `module` are included, not those
+/// inherited or from included modules. Private methods other than
+/// `initialize` are skipped.
+///
+/// Errors if `module` is anonymous.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{migrate, Error, RClass, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class: RClass = ruby.eval(
+///         r#"
+///           class Point
+///             def initialize(x, y)
+///               @x, @y = x, y
+///             end
+///
+///             def self.origin
+///               new(0, 0)
+///             end
+///
+///             def distance(other = Point.origin)
+///             end
+///
+///             def ==(other)
+///             end
+///
+///             self
+///           end
+///         "#,
+///     )?;
+///
+///     let code = migrate::skeleton(class)?;
+///     assert!(code.contains(r#"#[magnus::wrap(class = "Point")]"#));
+///     assert!(code.contains("fn new(x: Value, y: Value) -> Result<Self, Error>"));
+///     assert!(code.contains("fn origin() -> Result<Value, Error>"));
+///     assert!(code.contains("fn distance(&self, args: &[Value]) -> Result<Value, Error>"));
+///     assert!(code.contains("fn eq(&self, other: Value) -> Result<Value, Error>"));
+///     assert!(code.contains(r#"define_singleton_method("new", function!(Point::new, 2))"#));
+///     assert!(code.contains(r#"class.define_method("==", method!(Point::eq, 1))?;"#));
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn skeleton<T>(module: T) -> Result<String, Error>
+where
+    T: Module,
+{
+    let ruby = Ruby::get_with(module);
+    let name: Option<String> = module.funcall("name", ())?;
+    let name = name.ok_or_else(|| {
+        Error::new(
+            ruby.exception_arg_error(),
+            "can't generate skeleton for anonymous module",
+        )
+    })?;
+    let class = RClass::from_value(module.as_value());
+
+    let mut used = HashSet::new();
+    let mut singletons = Vec::new();
+    if let Some(class) = class {
+        if defines_initialize(class)? {
+            let info = class.instance_method_info("initialize")?;
+            singletons.push(Def::new(&mut used, "new", "initialize", info));
+        }
+    }
+    let singleton_class = module.singleton_class()?;
+    for name in sorted_names(module.singleton_methods(false))? {
+        let info = singleton_class.instance_method_info(&name)?;
+        singletons.push(Def::new(&mut used, &name, &name, info));
+    }
+    let mut instance = Vec::new();
+    let names: RArray = module.funcall("instance_methods", (false,))?;
+    for name in sorted_names(names)? {
+        let info = module.instance_method_info(&name)?;
+        instance.push(Def::new(&mut used, &name, &name, info));
+    }
+
+    let mut out = String::new();
+    write_skeleton(&mut out, &name, class, &singletons, &instance)
+        .expect("write to String can't fail");
+    Ok(out)
+}
+
+/// Load the Ruby file at `path` and generate Rust skeleton code for the class
+/// or module `name` it defines.
+///
+/// `name` may be a nested constant path, e.g. `"MyGem::Point"`. See
+/// [`skeleton`] for details of the generated code.
+///
+/// Errors if the file can't be loaded, or `name` is not a class or module.
+pub fn skeleton_from_file(ruby: &Ruby, path: &str, name: &str) -> Result<String, Error> {
+    let _: Value = ruby.module_kernel().funcall("load", (path,))?;
+    let val: Value = ruby.class_object().funcall("const_get", (name,))?;
+    match RClass::from_value(val) {
+        Some(class) => skeleton(class),
+        None => skeleton(RModule::try_convert(val)?),
+    }
+}
+
+fn defines_initialize(class: RClass) -> Result<bool, Error> {
+    if !class.funcall::<_, _, bool>("private_method_defined?", ("initialize", false))? {
+        return Ok(false);
+    }
+    let owner: Value = class
+        .funcall::<_, _, Value>("instance_method", ("initialize",))?
+        .funcall("owner", ())?;
+    owner.equal(class)
+}
+
+fn sorted_names(names: RArray) -> Result<Vec<String>, Error> {
+    let mut names = names
+        .to_vec::<Symbol>()?
+        .into_iter()
+        .map(|sym| sym.name().map(|name| name.into_owned()))
+        .collect::<Result<Vec<_>, _>>()?;
+    names.sort();
+    Ok(names)
+}
+
+// A method to generate a stub for.
+struct Def {
+    rust_name: String,
+    ruby_name: String,
+    info: MethodInfo,
+}
+
+impl Def {
+    fn new(used: &mut HashSet<String>, rust_name: &str, ruby_name: &str, info: MethodInfo) -> Self {
+        let mut rust_name = rust_ident(rust_name);
+        while !used.insert(rust_name.clone()) {
+            rust_name.push('_');
+        }
+        Self {
+            rust_name,
+            ruby_name: ruby_name.to_owned(),
+            info,
+        }
+    }
+
+    // The arity to define the method with, or `None` for a variable number
+    // of arguments.
+    fn arity(&self) -> Option<usize> {
+        let simple = self
+            .info
+            .parameters
+            .iter()
+            .all(|p| matches!(p.kind, ParameterKind::Req | ParameterKind::Block));
+        if simple {
+            Some(self.info.arity as usize)
+        } else {
+            None
+        }
+    }
+
+    fn args(&self) -> Vec<String> {
+        if self.arity().is_none() {
+            return vec![String::from("args: &[Value]")];
+        }
+        self.info
+            .parameters
+            .iter()
+            .filter(|p| p.kind == ParameterKind::Req)
+            .enumerate()
+            .map(|(i, p)| match &p.name {
+                Some(name) => format!("{}: Value", rust_ident(name)),
+                None => format!("arg{}: Value", i),
+            })
+            .collect()
+    }
+
+    // The method's Ruby parameter list, e.g. `a, b = ..., *c, d:, &e`.
+    fn ruby_params(&self) -> String {
+        let params = self.info.parameters.iter().enumerate().map(|(i, p)| {
+            let name = p.name.clone().unwrap_or_else(|| format!("arg{}", i));
+            match p.kind {
+                ParameterKind::Req => name,
+                ParameterKind::Opt => format!("{} = ...", name),
+                ParameterKind::Rest => format!("*{}", name),
+                ParameterKind::KeyReq => format!("{}:", name),
+                ParameterKind::Key => format!("{}: ...", name),
+                ParameterKind::KeyRest => format!("**{}", name),
+                ParameterKind::NoKey => String::from("**nil"),
+                ParameterKind::Block => format!("&{}", name),
+            }
+        });
+        params.collect::<Vec<_>>().join(", ")
+    }
+
+    fn write_doc(&self, out: &mut String, indent: &str, owner: &str, sep: &str) -> fmt::Result {
+        write!(
+            out,
+            "{}/// Port of `{}{}{}`",
+            indent, owner, sep, self.ruby_name
+        )?;
+        if let Some((file, line)) = &self.info.source_location {
+            write!(out, " ({}:{})", file, line)?;
+        }
+        writeln!(out, ".")?;
+        if self.arity().is_none() {
+            writeln!(out, "{}///", indent)?;
+            writeln!(
+                out,
+                "{}/// Ruby parameters: `({})`, parse `args` with `scan_args`.",
+                indent,
+                self.ruby_params()
+            )?;
+        } else if self
+            .info
+            .parameters
+            .iter()
+            .any(|p| p.kind == ParameterKind::Block)
+        {
+            writeln!(out, "{}///", indent)?;
+            writeln!(out, "{}/// Takes a block, see `Ruby::block_proc`.", indent)?;
+        }
+        Ok(())
+    }
+
+    fn arity_arg(&self) -> String {
+        match self.arity() {
+            Some(n) => n.to_string(),
+            None => String::from("-1"),
+        }
+    }
+}
+
+fn write_skeleton(
+    out: &mut String,
+    name: &str,
+    class: Option<RClass>,
+    singletons: &[Def],
+    instance: &[Def],
+) -> fmt::Result {
+    let short_name = name.rsplit("::").next().unwrap_or(name);
+    let type_name = rust_type_name(short_name);
+
+    writeln!(out, "// Generated by magnus::migrate from {}.", name)?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "use magnus::{{function, method, prelude::*, Error, Ruby, Value}};"
+    )?;
+    writeln!(out)?;
+
+    let fn_prefix = if class.is_some() {
+        writeln!(out, "#[magnus::wrap(class = \"{}\")]", name)?;
+        writeln!(out, "struct {} {{}}", type_name)?;
+        writeln!(out)?;
+        writeln!(out, "#[allow(unused_variables)]")?;
+        writeln!(out, "impl {} {{", type_name)?;
+        for (i, def) in singletons.iter().chain(instance).enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            let is_singleton = i < singletons.len();
+            let sep = if is_singleton && def.ruby_name != "initialize" {
+                "."
+            } else {
+                "#"
+            };
+            def.write_doc(out, "    ", name, sep)?;
+            let mut args = def.args();
+            if !is_singleton {
+                args.insert(0, String::from("&self"));
+            }
+            let ret = if is_singleton && def.ruby_name == "initialize" {
+                "Self"
+            } else {
+                "Value"
+            };
+            writeln!(
+                out,
+                "    fn {}({}) -> Result<{}, Error> {{",
+                def.rust_name,
+                args.join(", "),
+                ret
+            )?;
+            writeln!(out, "        todo!()")?;
+            writeln!(out, "    }}")?;
+        }
+        writeln!(out, "}}")?;
+        writeln!(out)?;
+        format!("{}::", type_name)
+    } else {
+        for (i, def) in singletons.iter().chain(instance).enumerate() {
+            let is_singleton = i < singletons.len();
+            let sep = if is_singleton { "." } else { "#" };
+            def.write_doc(out, "", name, sep)?;
+            let mut args = def.args();
+            if !is_singleton {
+                args.insert(0, String::from("rb_self: Value"));
+            }
+            writeln!(out, "#[allow(unused_variables)]")?;
+            writeln!(
+                out,
+                "fn {}({}) -> Result<Value, Error> {{",
+                def.rust_name,
+                args.join(", ")
+            )?;
+            writeln!(out, "    todo!()")?;
+            writeln!(out, "}}")?;
+            writeln!(out)?;
+        }
+        String::new()
+    };
+    writeln!(out, "pub fn init(ruby: &Ruby) -> Result<(), Error> {{")?;
+    let parent = match name.rfind("::") {
+        Some(i) => format!(
+            "ruby.class_object().const_get::<_, magnus::RModule>(\"{}\")?",
+            &name[..i]
+        ),
+        None => String::from("ruby"),
+    };
+    let var = match class {
+        Some(class) => {
+            let superclass = match class.superclass()? {
+                Some(superclass) if superclass.equal(Ruby::get_with(class).class_object())? => {
+                    String::from("ruby.class_object()")
+                }
+                Some(superclass) => format!(
+                    "ruby.class_object().const_get::<_, magnus::RClass>(\"{}\")?",
+                    superclass
+                ),
+                None => String::from("ruby.class_object()"),
+            };
+            writeln!(
+                out,
+                "    let class = {}.define_class(\"{}\", {})?;",
+                parent, short_name, superclass
+            )?;
+            "class"
+        }
+        None => {
+            writeln!(
+                out,
+                "    let module = {}.define_module(\"{}\")?;",
+                parent, short_name
+            )?;
+            "module"
+        }
+    };
+    for def in singletons {
+        writeln!(
+            out,
+            "    {}.define_singleton_method(\"{}\", function!({}{}, {}))?;",
+            var,
+            if def.ruby_name == "initialize" {
+                "new"
+            } else {
+                &def.ruby_name
+            },
+            fn_prefix,
+            def.rust_name,
+            def.arity_arg()
+        )?;
+    }
+    for def in instance {
+        writeln!(
+            out,
+            "    {}.define_method(\"{}\", method!({}{}, {}))?;",
+            var,
+            def.ruby_name,
+            fn_prefix,
+            def.rust_name,
+            def.arity_arg()
+        )?;
+    }
+    writeln!(out, "    Ok(())")?;
+    writeln!(out, "}}")
+}
+
+// Convert a Ruby method or parameter name to a valid Rust identifier.
+fn rust_ident(name: &str) -> String {
+    let operator = match name {
+        "+" => "add",
+        "-" => "sub",
+        "*" => "mul",
+        "/" => "div",
+        "%" => "rem",
+        "**" => "pow",
+        "+@" => "pos",
+        "-@" => "neg",
+        "~" => "not",
+        "!" => "bang",
+        "==" => "eq",
+        "!=" => "ne",
+        "===" => "case_eq",
+        "=~" => "match_",
+        "<=>" => "cmp",
+        "<" => "lt",
+        "<=" => "le",
+        ">" => "gt",
+        ">=" => "ge",
+        "<<" => "shl",
+        ">>" => "shr",
+        "&" => "bitand",
+        "|" => "bitor",
+        "^" => "bitxor",
+        "[]" => "aref",
+        "[]=" => "aset",
+        _ => "",
+    };
+    if !operator.is_empty() {
+        return operator.to_owned();
+    }
+    let ident = if let Some(base) = name.strip_suffix('?') {
+        format!("is_{}", base)
+    } else if let Some(base) = name.strip_suffix('!') {
+        format!("{}_bang", base)
+    } else if let Some(base) = name.strip_suffix('=') {
+        format!("set_{}", base)
+    } else {
+        name.to_owned()
+    };
+    match ident.as_str() {
+        "self" | "Self" | "super" | "crate" => format!("{}_", ident),
+        "as" | "async" | "await" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+        | "extern" | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match"
+        | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait"
+        | "true" | "type" | "unsafe" | "use" | "where" | "while" => format!("r#{}", ident),
+        _ => ident,
+    }
+}
+
+// Ruby constant names are already CamelCase, but may contain characters not
+// valid in Rust identifiers.
+fn rust_type_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
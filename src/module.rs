@@ -2,29 +2,37 @@
 //!
 //! See also [`Ruby`](Ruby#core-modules) for more module related methods.
 
-use std::{ffi::CString, fmt, mem::transmute, os::raw::c_int};
+use std::{borrow::Cow, cmp::Ordering, ffi::CString, fmt, mem::transmute, os::raw::c_int};
 
 use rb_sys::{
-    rb_alias, rb_attr, rb_class_inherited_p, rb_const_get, rb_const_set, rb_define_class_id_under,
-    rb_define_method_id, rb_define_module_function, rb_define_module_id_under,
-    rb_define_private_method, rb_define_protected_method, rb_include_module, rb_mComparable,
+    rb_alias, rb_attr, rb_class_inherited_p, rb_class_path, rb_const_get, rb_const_set,
+    rb_define_class_id_under, rb_define_method_id, rb_define_module_function,
+    rb_define_module_id_under, rb_define_private_method, rb_define_protected_method,
+    rb_funcall_with_block_kw, rb_include_module, rb_ivar_set, rb_keyword_given_p, rb_mComparable,
     rb_mEnumerable, rb_mErrno, rb_mFileTest, rb_mGC, rb_mKernel, rb_mMath, rb_mProcess,
-    rb_mWaitReadable, rb_mWaitWritable, rb_mod_ancestors, rb_module_new, rb_prepend_module,
-    ruby_value_type, VALUE,
+    rb_mWaitReadable, rb_mWaitWritable, rb_mod_ancestors, rb_mod_include_p, rb_module_new,
+    rb_prepend_module, ruby_value_type, VALUE,
 };
 
 use crate::{
+    block::{block_call_closure, Proc},
     class::{Class, RClass},
+    doc,
     error::{protect, Error},
     exception::ExceptionClass,
-    into_value::IntoValue,
-    method::Method,
+    into_value::{kw_splat, ArgList, IntoValue},
+    method::{self, BlockReturn, Method},
     object::Object,
     r_array::RArray,
+    r_hash::RHash,
+    r_method::UnboundMethod,
+    r_string::RString,
+    symbol::Symbol,
     try_convert::TryConvert,
+    typed_data::TypedData,
     value::{
         private::{self, ReprValue as _},
-        IntoId, NonZeroValue, ReprValue, Value,
+        Id, IntoId, NonZeroValue, Opaque, OpaqueId, ReprValue, Value,
     },
     Ruby,
 };
@@ -125,6 +133,16 @@ impl RModule {
     /// will be visible as a public 'class' method on the module and a private
     /// instance method on any object including the module.
     ///
+    /// This works the same for nested modules, such as those created with
+    /// [`Module::define_module`], and `func` can use any of the arities
+    /// supported by [`function!`](crate::function!), including optional,
+    /// keyword, and splat arguments. A block passed to the function can be
+    /// retrieved with [`Ruby::block_proc`].
+    ///
+    /// To define a method only on the module, and not as an instance method,
+    /// use [`Object::define_singleton_method`]. The module's singleton class
+    /// is available from [`Object::singleton_class`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -152,6 +170,43 @@ impl RModule {
     /// }
     /// # Ruby::init(example).unwrap()
     /// ```
+    ///
+    /// With a nested module and optional and keyword arguments:
+    ///
+    /// ```
+    /// use magnus::{function, prelude::*, rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn greet(
+    ///     ruby: &Ruby,
+    ///     name: String,
+    ///     greeting: Option<String>,
+    ///     kw: RHash,
+    /// ) -> Result<String, Error> {
+    ///     let greeting = greeting.unwrap_or_else(|| String::from("Hello"));
+    ///     let mut s = format!("{}, {}", greeting, name);
+    ///     if kw.lookup::<_, Option<bool>>(ruby.to_symbol("shout"))? == Some(true) {
+    ///         s = s.to_uppercase();
+    ///     }
+    ///     Ok(s)
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let outer = ruby.define_module("Outer")?;
+    ///     let inner = outer.define_module("Greeting")?;
+    ///     inner.define_module_function("greet", function!(greet, 1..=2, kw))?;
+    ///
+    ///     rb_assert!(ruby, r#"Outer::Greeting.greet("Bob") == "Hello, Bob""#);
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"Outer::Greeting.greet("Bob", "Hi", shout: true) == "HI, BOB""#
+    ///     );
+    ///     rb_assert!(ruby, "Outer::Greeting.private_instance_methods.include?(:greet)");
+    ///     rb_assert!(ruby, "inner.singleton_class.public_method_defined?(:greet)", inner);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
     pub fn define_module_function<M>(self, name: &str, func: M) -> Result<(), Error>
     where
         M: Method,
@@ -216,6 +271,12 @@ impl TryConvert for RModule {
 pub trait Module: Object + ReprValue + Copy {
     /// Define a class in `self`'s scope.
     ///
+    /// If the class is already defined it is returned, provided it has the
+    /// same superclass.
+    ///
+    /// Errors if a constant `name` already exists in `self`'s scope and is
+    /// not a class, or is a class with a different superclass.
+    ///
     /// # Examples
     ///
     /// ```
@@ -230,6 +291,30 @@ pub trait Module: Object + ReprValue + Copy {
     /// }
     /// # Ruby::init(example).unwrap()
     /// ```
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let outer = ruby.define_module("Outer")?;
+    ///     let inner = outer.define_class("Inner", ruby.class_object())?;
+    ///     assert!(outer
+    ///         .define_class("Inner", ruby.class_object())?
+    ///         .equal(inner)?);
+    ///
+    ///     let err = outer
+    ///         .define_class("Inner", ruby.class_string())
+    ///         .unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_type_error()));
+    ///     assert_eq!(err.to_string(), "superclass mismatch for class Inner");
+    ///
+    ///     outer.const_set("CONST", 1)?;
+    ///     assert!(outer.define_class("CONST", ruby.class_object()).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
     fn define_class<T>(self, name: T, superclass: RClass) -> Result<RClass, Error>
     where
         T: IntoId,
@@ -247,6 +332,43 @@ pub trait Module: Object + ReprValue + Copy {
         })
     }
 
+    /// Define a class in `self`'s scope for the wrapped type `T`, registering
+    /// its [`DataType`](crate::typed_data::DataType) with
+    /// [`Ruby::register_data_type`].
+    ///
+    /// Errors if a different `DataType` has already been registered with the
+    /// same name, as happens when two libraries wrap types under the same
+    /// name, or the same library has been loaded twice. Otherwise errors for
+    /// the same reasons as [`define_class`](Module::define_class).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Outer::Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let outer = ruby.define_module("Outer")?;
+    ///     outer.define_wrapped_class::<Point>("Point", ruby.class_object())?;
+    ///     rb_assert!(ruby, "Outer::Point.is_a?(Class)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_wrapped_class<T>(self, name: &str, superclass: RClass) -> Result<RClass, Error>
+    where
+        T: TypedData,
+    {
+        Ruby::get_with(self).register_data_type::<T>()?;
+        self.define_class(name, superclass)
+    }
+
     /// Define a module in `self`'s scope.
     ///
     /// # Examples
@@ -383,6 +505,324 @@ pub trait Module: Object + ReprValue + Copy {
         Ok(())
     }
 
+    /// Returns information about the instance method `name`, such as its
+    /// arity, parameters, and where it was defined.
+    ///
+    /// Private and protected methods are included.
+    ///
+    /// Returns `Err` containing a `NameError` if `self` does not define the
+    /// method `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{
+    ///     eval,
+    ///     module::{Parameter, ParameterKind},
+    ///     prelude::*,
+    ///     Error, RClass, Ruby,
+    /// };
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class: RClass = eval!(
+    ///         ruby,
+    ///         r#"
+    ///           class Example
+    ///             def example(a, b = 1, *c, d:, &e)
+    ///             end
+    ///             self
+    ///           end
+    ///         "#
+    ///     )?;
+    ///
+    ///     let info = class.instance_method_info("example")?;
+    ///     assert_eq!(info.arity, -3);
+    ///     assert_eq!(
+    ///         info.parameters,
+    ///         vec![
+    ///             Parameter::new(ParameterKind::Req, Some("a")),
+    ///             Parameter::new(ParameterKind::Opt, Some("b")),
+    ///             Parameter::new(ParameterKind::Rest, Some("c")),
+    ///             Parameter::new(ParameterKind::KeyReq, Some("d")),
+    ///             Parameter::new(ParameterKind::Block, Some("e")),
+    ///         ]
+    ///     );
+    ///     assert_eq!(info.source_location.map(|(file, _)| file).as_deref(), Some("(eval)"));
+    ///     assert_eq!(info.owner, "Example");
+    ///
+    ///     let info = ruby.class_string().instance_method_info("length")?;
+    ///     assert_eq!(info.arity, 0);
+    ///     assert!(info.source_location.is_none());
+    ///
+    ///     let info = ruby.class_string().instance_method_info("then")?;
+    ///     assert_eq!(info.owner, "Kernel");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn instance_method_info(self, name: &str) -> Result<MethodInfo, Error> {
+        let method: Value = self.funcall("instance_method", (name,))?;
+        let params: RArray = method.funcall("parameters", ())?;
+        let mut parameters = Vec::with_capacity(params.len());
+        for i in 0..params.len() {
+            let param: RArray = params.entry(i as isize)?;
+            let kind: Symbol = param.entry(0)?;
+            let name: Option<Symbol> = param.entry(1)?;
+            parameters.push(Parameter {
+                kind: ParameterKind::from_symbol(kind)?,
+                name: name.map(|n| n.name().map(Cow::into_owned)).transpose()?,
+            });
+        }
+        let owner: Value = method.funcall("owner", ())?;
+        Ok(MethodInfo {
+            arity: method.funcall("arity", ())?,
+            parameters,
+            source_location: method.funcall("source_location", ())?,
+            owner: owner.funcall("to_s", ())?,
+        })
+    }
+
+    /// Wrap the instance method `name` with `func`.
+    ///
+    /// This defines a new anonymous module with a method `name` that calls
+    /// `func`, and prepends it to `self`. `func` receives the
+    /// [`Original`] method, the receiver, and the arguments, and is
+    /// responsible for calling the original method, if required. The
+    /// anonymous module is returned.
+    ///
+    /// Keyword arguments and blocks are passed through to the original
+    /// method, and the wrapper keeps the original method's visibility. This
+    /// is useful for instrumentation, such as tracing or timing method calls.
+    ///
+    /// The original method is looked up once, when the wrapper is defined, so
+    /// later redefinitions of `name` in `self` are not called by the wrapper.
+    /// `func` may be called again before it returns, e.g. if the method
+    /// recurses, so must be [`Fn`].
+    ///
+    /// Returns `Err` containing a `NameError` if `self` does not define the
+    /// method `name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{
+    ///     atomic::{AtomicUsize, Ordering},
+    ///     Arc,
+    /// };
+    ///
+    /// use magnus::{eval, prelude::*, rb_assert, Error, RClass, RString, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class: RClass = eval!(
+    ///         ruby,
+    ///         r#"
+    ///           class Greeter
+    ///             def greet(name, punctuation: "!")
+    ///               yield "Hello, #{name}#{punctuation}"
+    ///             end
+    ///             self
+    ///           end
+    ///         "#
+    ///     )?;
+    ///
+    ///     let calls = Arc::new(AtomicUsize::new(0));
+    ///     let counter = calls.clone();
+    ///     class.prepend_wrapper("greet", move |original, _rb_self, args| {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///         let res: RString = original.call(args)?;
+    ///         res.funcall("upcase", ())
+    ///     })?;
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"Greeter.new.greet("world", punctuation: "?") { |s| s * 2 } == "HELLO, WORLD?HELLO, WORLD?""#
+    ///     );
+    ///     assert_eq!(calls.load(Ordering::Relaxed), 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn prepend_wrapper<F>(self, name: &str, func: F) -> Result<RModule, Error>
+    where
+        F: 'static + Send + Fn(Original, Value, &[Value]) -> Result<Value, Error>,
+    {
+        let ruby = Ruby::get_with(self);
+        let id = ruby.intern(name);
+        let private = self.funcall::<_, _, bool>("private_method_defined?", (id,))?;
+        let protected = self.funcall::<_, _, bool>("protected_method_defined?", (id,))?;
+        if !private && !protected && !self.funcall::<_, _, bool>("method_defined?", (id,))? {
+            return Err(Error::new(
+                ruby.exception_name_error(),
+                format!("undefined method `{}' for {}", name, self.as_value()),
+            ));
+        }
+
+        let original: UnboundMethod = self.funcall("instance_method", (id,))?;
+        let module = ruby.module_new();
+        // the closure only holds an Opaque, so keep the original method alive
+        // for as long as the module
+        module.ivar_set("__magnus_original", original)?;
+        let opaque_original = Opaque::from(original);
+        // the method may recurse, or the original may call back in to the
+        // method, so the closure must be callable re-entrantly
+        let wrapper = ruby.proc_from_shared_fn(move |ruby, args, block| {
+            let keywords = if unsafe { rb_keyword_given_p() } != 0 {
+                args.last().and_then(|v| RHash::from_value(*v))
+            } else {
+                None
+            };
+            let rb_self: Value = ruby.current_receiver()?;
+            let original = Original {
+                method: ruby.get_inner(opaque_original),
+                receiver: rb_self,
+                block,
+                keywords,
+            };
+            func(original, rb_self, args)
+        });
+        // the method holds a copy of the proc, but the closure is owned by
+        // the original, so keep that alive for as long as the module
+        module.ivar_set("__magnus_wrapper", wrapper)?;
+        let _: Value = module.funcall("define_method", (id, wrapper))?;
+        if private {
+            let _: Value = module.funcall("private", (id,))?;
+        } else if protected {
+            let _: Value = module.funcall("protected", (id,))?;
+        }
+        self.prepend_module(module)?;
+        Ok(module)
+    }
+
+    /// Include each of `modules` into `self`, in order.
+    ///
+    /// As with Ruby's `include`, when including several modules at once the
+    /// module last in `modules` will be closest to `self` in the ancestor
+    /// chain. To include in the same order as Ruby's `include A, B` (where
+    /// `A` is closest to `self`) reverse `modules`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.module_new();
+    ///     let b = ruby.module_new();
+    ///
+    ///     let class = RClass::new(ruby.class_object())?;
+    ///     class.include_modules(&[a, b])?;
+    ///
+    ///     rb_assert!(ruby, "class.ancestors[1..2] == [b, a]", class, a, b);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn include_modules(self, modules: &[RModule]) -> Result<(), Error> {
+        for module in modules {
+            self.include_module(*module)?;
+        }
+        Ok(())
+    }
+
+    /// Include Ruby's `Comparable` module into `self`.
+    ///
+    /// `Comparable` requires a `<=>` method, and this checks that `self`
+    /// defines one (other than the default `Object#<=>`), so it must be
+    /// defined before calling this function. Returns `Err` containing a
+    /// `NotImplementedError` if `<=>` is not defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    ///
+    /// use magnus::{function, method, prelude::*, rb_assert, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Version")]
+    /// struct Version(u32);
+    ///
+    /// impl Version {
+    ///     fn new(v: u32) -> Self {
+    ///         Self(v)
+    ///     }
+    ///
+    ///     fn cmp(&self, other: &Self) -> i8 {
+    ///         match self.0.cmp(&other.0) {
+    ///             Ordering::Less => -1,
+    ///             Ordering::Equal => 0,
+    ///             Ordering::Greater => 1,
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Version", ruby.class_object())?;
+    ///     assert!(class.include_comparable().is_err());
+    ///
+    ///     class.define_singleton_method("new", function!(Version::new, 1))?;
+    ///     class.define_method("<=>", method!(Version::cmp, 1))?;
+    ///     class.include_comparable()?;
+    ///
+    ///     rb_assert!(ruby, "Version.new(1) < Version.new(2)");
+    ///     rb_assert!(ruby, "Version.new(3).between?(Version.new(2), Version.new(4))");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn include_comparable(self) -> Result<(), Error> {
+        check_instance_method(self, "<=>", "Comparable")?;
+        self.include_module(Ruby::get_with(self).module_comparable())
+    }
+
+    /// Include Ruby's `Enumerable` module into `self`.
+    ///
+    /// `Enumerable` requires an `each` method, and this checks that `self`
+    /// defines one, so it must be defined before calling this function.
+    /// Returns `Err` containing a `NotImplementedError` if `each` is not
+    /// defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{block::Yield, function, method, prelude::*, rb_assert, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Countdown")]
+    /// struct Countdown(u32);
+    ///
+    /// impl Countdown {
+    ///     fn new(from: u32) -> Self {
+    ///         Self(from)
+    ///     }
+    ///
+    ///     fn each(&self) -> Yield<impl Iterator<Item = u32>> {
+    ///         Yield::Iter((1..=self.0).rev())
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Countdown", ruby.class_object())?;
+    ///     assert!(class.include_enumerable().is_err());
+    ///
+    ///     class.define_singleton_method("new", function!(Countdown::new, 1))?;
+    ///     class.define_method("each", method!(Countdown::each, 0))?;
+    ///     class.include_enumerable()?;
+    ///
+    ///     rb_assert!(ruby, "Countdown.new(3).to_a == [3, 2, 1]");
+    ///     rb_assert!(ruby, "Countdown.new(3).map { |i| i * 2 } == [6, 4, 2]");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn include_enumerable(self) -> Result<(), Error> {
+        check_instance_method(self, "each", "Enumerable")?;
+        self.include_module(Ruby::get_with(self).module_enumerable())
+    }
+
     /// Set the value for the constant `name` within `self`'s scope.
     ///
     /// # Examples
@@ -414,6 +854,68 @@ pub trait Module: Object + ReprValue + Copy {
         Ok(())
     }
 
+    /// Set the values for each of the constants in `consts` within `self`'s
+    /// scope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Module, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let module = ruby.define_module("Levels")?;
+    ///     module.define_consts(&[("LOW", 1), ("HIGH", 2)])?;
+    ///
+    ///     rb_assert!(ruby, "Levels::LOW == 1 && Levels::HIGH == 2");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_consts<T>(self, consts: &[(&str, T)]) -> Result<(), Error>
+    where
+        T: IntoValue + Copy,
+    {
+        for (name, value) in consts {
+            self.const_set(*name, *value)?;
+        }
+        Ok(())
+    }
+
+    /// Define a constant within `self`'s scope for each variant of the enum
+    /// `T`.
+    ///
+    /// See [`ConstEnum`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, ConstEnum, Error, Module, Ruby};
+    ///
+    /// #[derive(ConstEnum)]
+    /// enum Level {
+    ///     Low = 1,
+    ///     High = 2,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let module = ruby.define_module("Level")?;
+    ///     module.define_enum_consts::<Level>()?;
+    ///
+    ///     rb_assert!(ruby, "Level::LOW == 1 && Level::HIGH == 2");
+    ///     assert!(matches!(Level::from_const(ruby.eval("Level::HIGH")?)?, Level::High));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_enum_consts<T>(self) -> Result<(), Error>
+    where
+        T: ConstEnum,
+    {
+        self.define_consts(T::CONSTS)
+    }
+
     /// Get the value for the constant `name` within `self`'s scope.
     ///
     /// # Examples
@@ -481,6 +983,160 @@ pub trait Module: Object + ReprValue + Copy {
         }
     }
 
+    /// Returns whether `self` is a strict descendant of `other`, as with
+    /// Ruby's `self < other`.
+    ///
+    /// Classes including a module are considered descendants of that module.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = RClass::new(ruby.class_object())?;
+    ///     let b = RClass::new(a)?;
+    ///     assert!(b.is_subclass_of(a));
+    ///     assert!(!a.is_subclass_of(b));
+    ///     assert!(!a.is_subclass_of(a));
+    ///     assert!(ruby.class_integer().is_subclass_of(ruby.module_comparable()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn is_subclass_of<T>(self, other: T) -> bool
+    where
+        T: ReprValue + Module,
+    {
+        self.module_cmp(other) == Some(Ordering::Less)
+    }
+
+    /// Returns whether `other` is a strict descendant of `self`, as with
+    /// Ruby's `self > other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = RClass::new(ruby.class_object())?;
+    ///     let b = RClass::new(a)?;
+    ///     assert!(a.is_superclass_of(b));
+    ///     assert!(!b.is_superclass_of(a));
+    ///     assert!(!a.is_superclass_of(a));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn is_superclass_of<T>(self, other: T) -> bool
+    where
+        T: ReprValue + Module,
+    {
+        self.module_cmp(other) == Some(Ordering::Greater)
+    }
+
+    /// Compare the positions of `self` and `other` in the class hierarchy,
+    /// as with Ruby's `Module#<=>`.
+    ///
+    /// Returns `Less` if `self` is a descendant of `other`, `Greater` if
+    /// `other` is a descendant of `self`, `Equal` if they are the same
+    /// module, and `None` if they are unrelated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    ///
+    /// use magnus::{prelude::*, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = RClass::new(ruby.class_object())?;
+    ///     let b = RClass::new(a)?;
+    ///     assert_eq!(b.module_cmp(a), Some(Ordering::Less));
+    ///     assert_eq!(a.module_cmp(b), Some(Ordering::Greater));
+    ///     assert_eq!(a.module_cmp(a), Some(Ordering::Equal));
+    ///     assert_eq!(a.module_cmp(ruby.class_string()), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn module_cmp<T>(self, other: T) -> Option<Ordering>
+    where
+        T: ReprValue + Module,
+    {
+        if self.as_rb_value() == other.as_rb_value() {
+            return Some(Ordering::Equal);
+        }
+        // can't raise, as both arguments are guaranteed to be modules
+        let res = unsafe {
+            Value::new(rb_class_inherited_p(
+                self.as_rb_value(),
+                other.as_rb_value(),
+            ))
+        };
+        if res.is_nil() {
+            None
+        } else if res.to_bool() {
+            Some(Ordering::Less)
+        } else {
+            Some(Ordering::Greater)
+        }
+    }
+
+    /// Returns whether or not `self` includes `module`, either directly or
+    /// via an ancestor.
+    ///
+    /// Unlike [`is_inherited`](Module::is_inherited) this only considers
+    /// modules, not superclasses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.class_array().includes_module(ruby.module_enumerable()));
+    ///     assert!(ruby.class_integer().includes_module(ruby.module_comparable()));
+    ///     assert!(!ruby.class_integer().includes_module(ruby.module_enumerable()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn includes_module(self, module: RModule) -> bool {
+        unsafe { Value::new(rb_mod_include_p(self.as_rb_value(), module.as_rb_value())).to_bool() }
+    }
+
+    /// Return the fully qualified path of `self`, e.g. `"Outer::Inner"`.
+    ///
+    /// Unlike Ruby's `Module#name`, anonymous classes and modules will
+    /// return a string such as `"#<Class:0x000055d6a8b0c0c8>"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let outer = ruby.define_module("Outer")?;
+    ///     let inner = outer.define_class("Inner", ruby.class_object())?;
+    ///     assert_eq!(inner.to_path().to_string()?, "Outer::Inner");
+    ///
+    ///     let anon = ruby.module_new();
+    ///     assert!(anon.to_path().to_string()?.starts_with("#<Module:"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn to_path(self) -> RString {
+        unsafe { RString::from_rb_value_unchecked(rb_class_path(self.as_rb_value())) }
+    }
+
     /// Return the classes and modules `self` inherits, includes, or prepends.
     ///
     /// # Examples
@@ -717,6 +1373,73 @@ pub trait Module: Object + ReprValue + Copy {
         Ok(())
     }
 
+    /// Define public accessor methods for the attribute `name`, where values
+    /// written must convert to `T`.
+    ///
+    /// `name` should be **without** the preceding `@`.
+    ///
+    /// The reader method is the same as with
+    /// [`define_attr`](Module::define_attr). The writer method converts the
+    /// value with `T`'s [`TryConvert`] implementation, raising the error
+    /// (usually a `TypeError`) if that fails. If `coerce` is `true` the
+    /// converted value is converted back to a Ruby object and stored,
+    /// otherwise the original value is stored.
+    ///
+    /// The writer raises a `FrozenError` if the receiver is frozen, before
+    /// attempting any conversion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Attr, Error, RClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = RClass::new(ruby.class_object())?;
+    ///     class.define_typed_attr::<i64, _>("count", Attr::ReadWrite, false)?;
+    ///     class.define_typed_attr::<f64, _>("ratio", Attr::ReadWrite, true)?;
+    ///
+    ///     let obj = class.new_instance(())?;
+    ///     rb_assert!(ruby, "obj.count = 42; obj.count == 42", obj);
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"begin; obj.count = "42"; false; rescue TypeError; obj.count == 42; end"#,
+    ///         obj
+    ///     );
+    ///     rb_assert!(ruby, "obj.ratio = 1; obj.ratio.eql?(1.0)", obj);
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "obj.freeze; begin; obj.count = 1; false; rescue FrozenError; true; end",
+    ///         obj
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_typed_attr<T, N>(self, name: N, rw: Attr, coerce: bool) -> Result<(), Error>
+    where
+        T: 'static + TryConvert + IntoValue,
+        N: IntoId,
+    {
+        let handle = Ruby::get_with(self);
+        let id = name.into_id_with(&handle);
+        if rw.is_read() {
+            self.define_attr(id, Attr::Read)?;
+        }
+        if rw.is_write() {
+            let name = id.name()?;
+            let setter = format!("{}=", name);
+            let ivar = OpaqueId::from(handle.intern(&format!("@{}", name)));
+            let proc = handle.proc_from_fn(move |ruby, args, _block| {
+                ruby.check_arity(args.len(), 1..=1)?;
+                let rb_self: Value = ruby.current_receiver()?;
+                typed_attr_write::<T>(ruby, rb_self, args[0], ivar.into_id_with(ruby), coerce)
+            });
+            let _: Value = self.funcall("define_method", (setter, proc))?;
+        }
+        Ok(())
+    }
+
     /// Alias the method `src` of `self` as `dst`.
     ///
     /// # Examples
@@ -754,6 +1477,352 @@ pub trait Module: Object + ReprValue + Copy {
         })?;
         Ok(())
     }
+
+    /// Register `doc` as the documentation for the method `name` of
+    /// `self`.
+    ///
+    /// This doesn't change the behaviour of the method, it only records the
+    /// documentation for use by Ruby tooling. See the [`doc`](crate::doc)
+    /// module for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, prelude::*, rb_assert, Error, RClass, Ruby};
+    ///
+    /// fn answer() -> i64 {
+    ///     42
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = RClass::new(ruby.class_object())?;
+    ///     class.define_method("answer", function!(answer, 0))?;
+    ///     class.document_method("answer", "Returns the answer.")?;
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"$__magnus_docs__.last == {"owner" => klass, "name" => "answer", "singleton" => false, "doc" => "Returns the answer."}"#,
+    ///         klass = class,
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn document_method(self, name: &str, doc: &str) -> Result<(), Error> {
+        doc::register(self, name, false, doc)
+    }
+
+    /// Register `doc` as the documentation for the singleton method `name`
+    /// of `self`.
+    ///
+    /// This doesn't change the behaviour of the method, it only records the
+    /// documentation for use by Ruby tooling. See the [`doc`](crate::doc)
+    /// module for details.
+    fn document_singleton_method(self, name: &str, doc: &str) -> Result<(), Error> {
+        doc::register(self, name, true, doc)
+    }
+
+    /// Run `func` as the block to Ruby's `class_eval` on `self`.
+    ///
+    /// `func` is called with `self`, and runs with `self` as Ruby's `self`,
+    /// allowing DSL-style configuration of a class or module from Rust
+    /// without generating strings of Ruby code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, prelude::*, rb_assert, Error, Ruby, Value};
+    ///
+    /// fn answer() -> i64 {
+    ///     42
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let class = ruby.define_class("Example", ruby.class_object())?;
+    ///     let name: String = class.class_eval_closure(|class| {
+    ///         class.define_singleton_method("answer", function!(answer, 0))?;
+    ///         let _: Value = class.funcall("private_class_method", ("answer",))?;
+    ///         class.funcall::<_, _, String>("name", ())
+    ///     })?;
+    ///     assert_eq!(name, "Example");
+    ///     rb_assert!(ruby, "Example.send(:answer) == 42");
+    ///     rb_assert!(ruby, "(Example.answer rescue $!).is_a?(NoMethodError)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn class_eval_closure<F, R, T>(self, mut func: F) -> Result<T, Error>
+    where
+        F: FnMut(Self) -> R,
+        R: BlockReturn,
+        T: TryConvert,
+    {
+        block_call_closure(self.as_value(), "class_eval", (), |_, _, _| func(self))
+    }
+
+    /// Define a singleton method in `self`'s scope, implemented by the
+    /// closure `func`.
+    ///
+    /// Unlike [`define_singleton_method`](Object::define_singleton_method),
+    /// `func` can capture state, such as configuration shared with the rest
+    /// of the extension. It is called with the receiver (which may be a
+    /// subclass of `self` if `self` is a class), the arguments, and the block
+    /// passed to the method, if any.
+    ///
+    /// `func` is kept alive for as long as the method is defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use magnus::{prelude::*, rb_assert, Error, RModule, Ruby};
+    ///
+    /// #[derive(Default)]
+    /// struct Config {
+    ///     verbose: bool,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let config = Arc::new(Mutex::new(Config::default()));
+    ///
+    ///     let module = ruby.define_module("MyGem")?;
+    ///     let config_ref = config.clone();
+    ///     module.define_singleton_method_closure(
+    ///         "configure",
+    ///         move |_ruby, module: RModule, _args, block| -> Result<(), Error> {
+    ///             if let Some(block) = block {
+    ///                 let _: bool = block.call((module,))?;
+    ///                 config_ref.lock().unwrap().verbose = module.ivar_get("@verbose")?;
+    ///             }
+    ///             Ok(())
+    ///         },
+    ///     )?;
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "MyGem.configure { |c| c.instance_variable_set(:@verbose, true) }.nil?"
+    ///     );
+    ///     assert!(config.lock().unwrap().verbose);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn define_singleton_method_closure<T, F, R>(self, name: T, mut func: F) -> Result<(), Error>
+    where
+        Self: TryConvert,
+        T: IntoValue,
+        F: 'static + Send + FnMut(&Ruby, Self, &[Value], Option<Proc>) -> R,
+        R: BlockReturn,
+    {
+        let handle = Ruby::get_with(self);
+        let proc = handle.proc_from_fn(move |ruby, args, block| {
+            let recv = ruby.current_receiver::<Self>()?;
+            method::private::BlockReturn::into_block_return(func(ruby, recv, args, block))
+        });
+        let _: Value = self.funcall("define_singleton_method", (name, proc))?;
+        Ok(())
+    }
+}
+
+// Writer for `Module::define_typed_attr`.
+fn typed_attr_write<T>(
+    handle: &Ruby,
+    rb_self: Value,
+    val: Value,
+    ivar: Id,
+    coerce: bool,
+) -> Result<Value, Error>
+where
+    T: TryConvert + IntoValue,
+{
+    if rb_self.is_frozen() {
+        let msg = format!(
+            "can't modify frozen {}: {}",
+            unsafe { rb_self.classname() },
+            rb_self.inspect()
+        );
+        return Err(handle.frozen_error_with(rb_self, msg));
+    }
+    let converted = T::try_convert(val)?;
+    let val = if coerce {
+        handle.into_value(converted)
+    } else {
+        val
+    };
+    protect(|| unsafe {
+        Value::new(rb_ivar_set(
+            rb_self.as_rb_value(),
+            ivar.as_rb_id(),
+            val.as_rb_value(),
+        ))
+    })
+}
+
+// Check `module` defines the instance method `name` (excluding the defaults
+// from Kernel), as required to include `mixin`.
+fn check_instance_method<T>(module: T, name: &str, mixin: &str) -> Result<(), Error>
+where
+    T: Module,
+{
+    let ruby = Ruby::get_with(module);
+    let defined = module.funcall::<_, _, bool>("method_defined?", (name,))?
+        || module.funcall::<_, _, bool>("private_method_defined?", (name,))?;
+    if defined {
+        let owner: Value = module
+            .funcall::<_, _, Value>("instance_method", (name,))?
+            .funcall("owner", ())?;
+        if !owner.equal_id(ruby.module_kernel()) {
+            return Ok(());
+        }
+    }
+    Err(Error::new(
+        ruby.exception_not_imp_error(),
+        format!(
+            "{} must define #{} to include {}",
+            module.as_value(),
+            name,
+            mixin
+        ),
+    ))
+}
+
+/// Information about a method, returned by
+/// [`Module::instance_method_info`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct MethodInfo {
+    /// The method's arity, as with Ruby's `Method#arity`.
+    ///
+    /// For methods taking a fixed number of arguments this is that number,
+    /// for methods taking a variable number of arguments this is `-n-1`,
+    /// where `n` is the number of required arguments.
+    pub arity: i64,
+    /// The method's parameters.
+    pub parameters: Vec<Parameter>,
+    /// The file and line number where the method was defined, or `None` for
+    /// methods not defined in Ruby code.
+    pub source_location: Option<(String, usize)>,
+    /// The name of the class or module that defines the method, as with
+    /// Ruby's `Module#to_s`.
+    pub owner: String,
+}
+
+/// A method parameter, as described by Ruby's `Method#parameters`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parameter {
+    /// The kind of parameter.
+    pub kind: ParameterKind,
+    /// The parameter's name, or `None` if it is anonymous.
+    pub name: Option<String>,
+}
+
+impl Parameter {
+    /// Create a new `Parameter`.
+    pub fn new(kind: ParameterKind, name: Option<&str>) -> Self {
+        Self {
+            kind,
+            name: name.map(String::from),
+        }
+    }
+}
+
+/// The kind of a method [`Parameter`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ParameterKind {
+    /// A required positional parameter, e.g. `a`.
+    Req,
+    /// An optional positional parameter, e.g. `a = 1`.
+    Opt,
+    /// A splat parameter, e.g. `*a`.
+    Rest,
+    /// A required keyword parameter, e.g. `a:`.
+    KeyReq,
+    /// An optional keyword parameter, e.g. `a: 1`.
+    Key,
+    /// A keyword splat parameter, e.g. `**a`.
+    KeyRest,
+    /// No keywords accepted, i.e. `**nil`.
+    NoKey,
+    /// A block parameter, e.g. `&a`.
+    Block,
+}
+
+impl ParameterKind {
+    fn from_symbol(sym: Symbol) -> Result<Self, Error> {
+        Ok(match &*sym.name()? {
+            "req" => Self::Req,
+            "opt" => Self::Opt,
+            "rest" => Self::Rest,
+            "keyreq" => Self::KeyReq,
+            "key" => Self::Key,
+            "keyrest" => Self::KeyRest,
+            "nokey" => Self::NoKey,
+            "block" => Self::Block,
+            name => {
+                return Err(Error::new(
+                    Ruby::get_with(sym).exception_arg_error(),
+                    format!("unknown parameter kind :{}", name),
+                ))
+            }
+        })
+    }
+}
+
+/// The original method wrapped by [`Module::prepend_wrapper`].
+///
+/// As with other Ruby types, an `Original` must not be stored on the heap.
+#[derive(Clone, Copy)]
+pub struct Original {
+    method: UnboundMethod,
+    receiver: Value,
+    block: Option<Proc>,
+    keywords: Option<RHash>,
+}
+
+impl Original {
+    /// Call the original method with `args`.
+    ///
+    /// The block given to the wrapper method is passed on to the original
+    /// method. Keyword arguments received by the wrapper method are passed
+    /// on when they are the last element of `args`.
+    pub fn call<A, T>(self, args: A) -> Result<T, Error>
+    where
+        A: ArgList,
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self.method);
+        let mut kw_splat = kw_splat(&args);
+        let args = args.into_arg_list_with(&ruby);
+        let slice = args.as_ref();
+        if let (Some(keywords), Some(last)) = (self.keywords, slice.last()) {
+            if keywords.as_value().equal_id(*last) {
+                kw_splat = rb_sys::RB_PASS_KEYWORDS;
+            }
+        }
+        let mut bind_args = Vec::with_capacity(slice.len() + 1);
+        bind_args.push(self.receiver);
+        bind_args.extend_from_slice(slice);
+        let bind_call = ruby.intern("bind_call");
+        let block = self.block.map_or(ruby.qnil().as_value(), |b| b.as_value());
+        unsafe {
+            protect(|| {
+                Value::new(rb_funcall_with_block_kw(
+                    self.method.as_rb_value(),
+                    bind_call.as_rb_id(),
+                    bind_args.len() as c_int,
+                    bind_args.as_ptr() as *const VALUE,
+                    block.as_rb_value(),
+                    kw_splat as c_int,
+                ))
+            })
+            .and_then(TryConvert::try_convert)
+        }
+    }
 }
 
 /// Argument for [`define_attr`](Module::define_attr).
@@ -783,6 +1852,71 @@ impl Attr {
     }
 }
 
+/// A fieldless enum that maps to a set of Integer Ruby constants.
+///
+/// This is intended to be derived with [`ConstEnum`](macro@crate::ConstEnum),
+/// and is used by [`Module::define_enum_consts`] to define the constants.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{ConstEnum, Error, Ruby};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Mode {
+///     Read,
+///     Write,
+/// }
+///
+/// impl ConstEnum for Mode {
+///     const CONSTS: &'static [(&'static str, i64)] = &[("READ", 0), ("WRITE", 1)];
+///
+///     fn from_i64(val: i64) -> Option<Self> {
+///         match val {
+///             0 => Some(Self::Read),
+///             1 => Some(Self::Write),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     assert_eq!(Mode::from_const(ruby.eval("1")?)?, Mode::Write);
+///     assert!(Mode::from_const(ruby.eval("2")?).is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait ConstEnum: Sized {
+    /// The name and value of the constant for each variant.
+    const CONSTS: &'static [(&'static str, i64)];
+
+    /// Returns the variant with the constant value `val`, or `None` if there
+    /// is no such variant.
+    fn from_i64(val: i64) -> Option<Self>;
+
+    /// Convert `val`, the value of one of the constants, to the matching
+    /// variant.
+    ///
+    /// Returns `Err` if `val` is not an Integer, or is not the value of any
+    /// of the constants.
+    fn from_const(val: Value) -> Result<Self, Error> {
+        let i = i64::try_convert(val)?;
+        Self::from_i64(i).ok_or_else(|| {
+            let expected = Self::CONSTS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            Error::new(
+                Ruby::get_with(val).exception_arg_error(),
+                format!("invalid value {}, expected one of {}", i, expected),
+            )
+        })
+    }
+}
+
 /// # Core Modules
 ///
 /// Functions to access Ruby's built-in modules.
@@ -0,0 +1,355 @@
+//! Conversions between Ruby objects and [MessagePack] bytes.
+//!
+//! Serializing a large payload to MessagePack in native code allows it to be
+//! passed between Rust and Ruby as a single String, rather than converting
+//! each object across the boundary.
+//!
+//! | MessagePack   | Ruby                                            |
+//! |---------------|-------------------------------------------------|
+//! | nil           | `nil`                                           |
+//! | bool          | `true`/`false`                                  |
+//! | int           | `Integer`                                       |
+//! | float         | `Float`                                         |
+//! | str           | `String` (UTF-8), `Symbol` (encode only)        |
+//! | bin           | `String` (ASCII-8BIT/binary)                    |
+//! | array         | `Array`                                         |
+//! | map           | `Hash`                                          |
+//!
+//! Extension types are not supported.
+//!
+//! [MessagePack]: https://msgpack.org
+
+use crate::{
+    encoding::EncodingCapable,
+    error::Error,
+    float::Float,
+    integer::Integer,
+    r_array::RArray,
+    r_hash::{ForEach, RHash},
+    r_string::RString,
+    symbol::Symbol,
+    value::{Qfalse, Qtrue, ReprValue, Value},
+    Ruby,
+};
+
+/// The maximum depth of nested arrays/maps converted by [`encode`] and
+/// [`decode`].
+///
+/// This guards against overflowing the stack when converting a recursive
+/// data structure or malicious input.
+pub const MAX_NESTING: usize = 128;
+
+/// Serialize the Ruby object `val` to MessagePack bytes.
+///
+/// Errors if `val` or any nested value is of an unsupported type, if an
+/// Integer is out of range of `i64`/`u64`, or if arrays/hashes are nested
+/// more than [`MAX_NESTING`] levels deep.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{msgpack, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val: Value = ruby.eval(r#"{"a" => [1, nil, true]}"#)?;
+///     assert_eq!(
+///         msgpack::encode(val)?,
+///         vec![0x81, 0xa1, b'a', 0x93, 0x01, 0xc0, 0xc3]
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn encode(val: Value) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::new();
+    encode_nested(&Ruby::get_with(val), val, &mut buf, 0)?;
+    Ok(buf)
+}
+
+fn encode_nested(ruby: &Ruby, val: Value, buf: &mut Vec<u8>, depth: usize) -> Result<(), Error> {
+    if val.is_nil() {
+        buf.push(0xc0);
+    } else if Qfalse::from_value(val).is_some() {
+        buf.push(0xc2);
+    } else if Qtrue::from_value(val).is_some() {
+        buf.push(0xc3);
+    } else if let Some(i) = Integer::from_value(val) {
+        if let Ok(n) = i.to_i64() {
+            encode_int(n, buf);
+        } else if let Ok(n) = i.to_u64() {
+            buf.push(0xcf);
+            buf.extend_from_slice(&n.to_be_bytes());
+        } else {
+            return Err(Error::new(
+                ruby.exception_range_error(),
+                format!("integer {} too big to convert to MessagePack", i),
+            ));
+        }
+    } else if let Some(f) = Float::from_value(val) {
+        buf.push(0xcb);
+        buf.extend_from_slice(&f.to_f64().to_be_bytes());
+    } else if let Some(s) = RString::from_value(val) {
+        if s.enc_get() == ruby.ascii8bit_encindex() {
+            // safe as the slice is copied before Ruby can run
+            let bytes = unsafe { s.as_slice() };
+            encode_len(ruby, bytes.len(), [0, 0xc4, 0xc5, 0xc6], None, buf)?;
+            buf.extend_from_slice(bytes);
+        } else {
+            encode_str(ruby, &s.to_string()?, buf)?;
+        }
+    } else if let Some(s) = Symbol::from_value(val) {
+        encode_str(ruby, &s.name()?, buf)?;
+    } else if depth >= MAX_NESTING {
+        return Err(nesting_error(ruby, depth));
+    } else if let Some(ary) = RArray::from_value(val) {
+        encode_len(ruby, ary.len(), [0, 0, 0xdc, 0xdd], Some((0x90, 16)), buf)?;
+        let len = ary.len();
+        for i in 0..len {
+            encode_nested(ruby, ary.entry(i as isize)?, buf, depth + 1)?;
+        }
+        if ary.len() != len {
+            return Err(Error::new(
+                ruby.exception_runtime_error(),
+                "array modified during MessagePack encoding",
+            ));
+        }
+    } else if let Some(hash) = RHash::from_value(val) {
+        encode_len(ruby, hash.len(), [0, 0, 0xde, 0xdf], Some((0x80, 16)), buf)?;
+        hash.foreach(|k: Value, v: Value| {
+            encode_nested(ruby, k, buf, depth + 1)?;
+            encode_nested(ruby, v, buf, depth + 1)?;
+            Ok(ForEach::Continue)
+        })?;
+    } else {
+        return Err(Error::new(
+            ruby.exception_type_error(),
+            format!("no implicit conversion of {} into MessagePack", val.class()),
+        ));
+    }
+    Ok(())
+}
+
+fn encode_int(n: i64, buf: &mut Vec<u8>) {
+    if (0..=0x7f).contains(&n) || (-32..0).contains(&n) {
+        // positive/negative fixint
+        buf.push(n as u8);
+    } else if n >= 0 {
+        if let Ok(n) = u8::try_from(n) {
+            buf.push(0xcc);
+            buf.push(n);
+        } else if let Ok(n) = u16::try_from(n) {
+            buf.push(0xcd);
+            buf.extend_from_slice(&n.to_be_bytes());
+        } else if let Ok(n) = u32::try_from(n) {
+            buf.push(0xce);
+            buf.extend_from_slice(&n.to_be_bytes());
+        } else {
+            buf.push(0xcf);
+            buf.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    } else if let Ok(n) = i8::try_from(n) {
+        buf.push(0xd0);
+        buf.push(n as u8);
+    } else if let Ok(n) = i16::try_from(n) {
+        buf.push(0xd1);
+        buf.extend_from_slice(&n.to_be_bytes());
+    } else if let Ok(n) = i32::try_from(n) {
+        buf.push(0xd2);
+        buf.extend_from_slice(&n.to_be_bytes());
+    } else {
+        buf.push(0xd3);
+        buf.extend_from_slice(&n.to_be_bytes());
+    }
+}
+
+fn encode_str(ruby: &Ruby, s: &str, buf: &mut Vec<u8>) -> Result<(), Error> {
+    encode_len(ruby, s.len(), [0, 0xd9, 0xda, 0xdb], Some((0xa0, 32)), buf)?;
+    buf.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+/// Write the header for a value of length `len`.
+///
+/// `markers` are the markers for 8, 16, and 32 bit lengths (the first element
+/// is unused, 0 for no marker of that size), and `fix` is the marker and
+/// exclusive upper bound for the 'fix' format, if there is one.
+fn encode_len(
+    ruby: &Ruby,
+    len: usize,
+    markers: [u8; 4],
+    fix: Option<(u8, usize)>,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error> {
+    match fix {
+        Some((marker, max)) if len < max => buf.push(marker | len as u8),
+        _ if markers[1] != 0 && len <= u8::MAX as usize => {
+            buf.push(markers[1]);
+            buf.push(len as u8);
+        }
+        _ if len <= u16::MAX as usize => {
+            buf.push(markers[2]);
+            buf.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        _ if len <= u32::MAX as usize => {
+            buf.push(markers[3]);
+            buf.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            return Err(Error::new(
+                ruby.exception_range_error(),
+                format!("length {} too long for MessagePack", len),
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Deserialize MessagePack `bytes` to a Ruby object.
+///
+/// Errors if `bytes` is not valid MessagePack, contains extension types,
+/// contains trailing bytes after the first value, or arrays/maps are nested
+/// more than [`MAX_NESTING`] levels deep.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{msgpack, rb_assert, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val = msgpack::decode(ruby, &[0x81, 0xa1, b'a', 0x93, 0x01, 0xc0, 0xc3])?;
+///     rb_assert!(ruby, r#"val == {"a" => [1, nil, true]}"#, val);
+///
+///     assert!(msgpack::decode(ruby, &[0x93, 0x01]).is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn decode(ruby: &Ruby, bytes: &[u8]) -> Result<Value, Error> {
+    let mut decoder = Decoder { ruby, bytes };
+    let val = decoder.decode(0)?;
+    if !decoder.bytes.is_empty() {
+        return Err(decoder.error("trailing bytes"));
+    }
+    Ok(val)
+}
+
+struct Decoder<'a> {
+    ruby: &'a Ruby,
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, msg: &str) -> Error {
+        Error::new(
+            self.ruby.exception_arg_error(),
+            format!("invalid MessagePack: {}", msg),
+        )
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < n {
+            return Err(self.error("unexpected end of input"));
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn take_len(&mut self, size: usize) -> Result<usize, Error> {
+        Ok(match size {
+            1 => u8::from_be_bytes(self.take_array()?) as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn decode(&mut self, depth: usize) -> Result<Value, Error> {
+        let ruby = self.ruby;
+        let marker = self.take(1)?[0];
+        let val = match marker {
+            0x00..=0x7f => ruby.into_value(marker),
+            0x80..=0x8f => return self.decode_map((marker & 0x0f) as usize, depth),
+            0x90..=0x9f => return self.decode_array((marker & 0x0f) as usize, depth),
+            0xa0..=0xbf => return self.decode_str((marker & 0x1f) as usize),
+            0xc0 => ruby.qnil().as_value(),
+            0xc2 => ruby.qfalse().as_value(),
+            0xc3 => ruby.qtrue().as_value(),
+            0xc4..=0xc6 => {
+                let len = self.take_len(1 << (marker - 0xc4))?;
+                ruby.str_from_slice(self.take(len)?).as_value()
+            }
+            0xca => ruby.into_value(f32::from_be_bytes(self.take_array()?)),
+            0xcb => ruby.into_value(f64::from_be_bytes(self.take_array()?)),
+            0xcc => ruby.into_value(u8::from_be_bytes(self.take_array()?)),
+            0xcd => ruby.into_value(u16::from_be_bytes(self.take_array()?)),
+            0xce => ruby.into_value(u32::from_be_bytes(self.take_array()?)),
+            0xcf => ruby.into_value(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => ruby.into_value(i8::from_be_bytes(self.take_array()?)),
+            0xd1 => ruby.into_value(i16::from_be_bytes(self.take_array()?)),
+            0xd2 => ruby.into_value(i32::from_be_bytes(self.take_array()?)),
+            0xd3 => ruby.into_value(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.take_len(1 << (marker - 0xd9))?;
+                return self.decode_str(len);
+            }
+            0xdc | 0xdd => {
+                let len = self.take_len(2 << (marker - 0xdc))?;
+                return self.decode_array(len, depth);
+            }
+            0xde | 0xdf => {
+                let len = self.take_len(2 << (marker - 0xde))?;
+                return self.decode_map(len, depth);
+            }
+            0xe0..=0xff => ruby.into_value(marker as i8),
+            0xc7..=0xc9 | 0xd4..=0xd8 => return Err(self.error("extension types not supported")),
+            0xc1 => return Err(self.error("reserved marker 0xc1")),
+        };
+        Ok(val)
+    }
+
+    fn decode_str(&mut self, len: usize) -> Result<Value, Error> {
+        let bytes = self.take(len)?;
+        let s = std::str::from_utf8(bytes).map_err(|_| self.error("str is not valid UTF-8"))?;
+        Ok(self.ruby.str_new(s).as_value())
+    }
+
+    fn decode_array(&mut self, len: usize, depth: usize) -> Result<Value, Error> {
+        if depth >= MAX_NESTING {
+            return Err(nesting_error(self.ruby, depth));
+        }
+        // each element is at least 1 byte, don't trust len for the capacity
+        let ary = self.ruby.ary_new_capa(len.min(self.bytes.len()));
+        for _ in 0..len {
+            ary.push(self.decode(depth + 1)?)?;
+        }
+        Ok(ary.as_value())
+    }
+
+    fn decode_map(&mut self, len: usize, depth: usize) -> Result<Value, Error> {
+        if depth >= MAX_NESTING {
+            return Err(nesting_error(self.ruby, depth));
+        }
+        let hash = self.ruby.hash_new();
+        for _ in 0..len {
+            let key = self.decode(depth + 1)?;
+            let val = self.decode(depth + 1)?;
+            hash.aset(key, val)?;
+        }
+        Ok(hash.as_value())
+    }
+}
+
+fn nesting_error(ruby: &Ruby, depth: usize) -> Error {
+    Error::new(
+        ruby.exception_arg_error(),
+        format!("nesting of {} is too deep", depth + 1),
+    )
+}
@@ -1,7 +1,8 @@
 use std::{ffi::CString, mem::transmute};
 
 use rb_sys::{
-    rb_define_singleton_method, rb_extend_object, rb_ivar_get, rb_ivar_set, rb_singleton_class,
+    rb_define_singleton_method, rb_extend_object, rb_ivar_get, rb_ivar_set,
+    rb_obj_singleton_methods, rb_singleton_class,
 };
 
 use crate::{
@@ -10,6 +11,7 @@ use crate::{
     into_value::IntoValue,
     method::Method,
     module::RModule,
+    r_array::RArray,
     try_convert::TryConvert,
     value::{private::ReprValue as _, IntoId, ReprValue, Value},
     Ruby,
@@ -234,4 +236,80 @@ pub trait Object: ReprValue + Copy {
         })?;
         Ok(())
     }
+
+    /// Extend `self` with `modules`, as with Ruby's `Object#extend`.
+    ///
+    /// Unlike [`extend_object`](Object::extend_object) this calls each
+    /// module's `extend_object` and `extended` hooks. Modules are applied in
+    /// reverse order, so the first module in `modules` will be checked first
+    /// when looking up methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, RModule, RObject, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let module: RModule = ruby.eval(
+    ///         r#"
+    ///             Module.new do
+    ///               def self.extended(obj)
+    ///                 obj.instance_variable_set(:@extended, true)
+    ///               end
+    ///
+    ///               def test
+    ///                 42
+    ///               end
+    ///             end
+    ///         "#,
+    ///     )?;
+    ///
+    ///     let obj = RObject::try_convert(ruby.class_object().new_instance(())?)?;
+    ///     obj.extend(&[module])?;
+    ///     rb_assert!(ruby, "obj.test == 42", obj);
+    ///     rb_assert!(ruby, "obj.instance_variable_get(:@extended)", obj);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn extend(self, modules: &[RModule]) -> Result<(), Error> {
+        let _: Value = self.funcall("extend", modules)?;
+        Ok(())
+    }
+
+    /// Returns an Array of the names of the singleton methods of `self`, as
+    /// with Ruby's `Object#singleton_methods`.
+    ///
+    /// If `all` is `true` this includes methods from modules `self` has been
+    /// extended with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, prelude::*, Error, RObject, Ruby, Symbol};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let obj = RObject::try_convert(ruby.class_object().new_instance(())?)?;
+    ///     obj.define_singleton_method("answer", function!(|| 42, 0))?;
+    ///     obj.extend(&[ruby.module_comparable()])?;
+    ///
+    ///     let methods = obj.singleton_methods(false).to_vec::<Symbol>()?;
+    ///     assert_eq!(methods.len(), 1);
+    ///     assert_eq!(methods[0].name()?, "answer");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn singleton_methods(self, all: bool) -> RArray {
+        let all = Ruby::get_with(self).into_value(all);
+        unsafe {
+            RArray::from_rb_value_unchecked(rb_obj_singleton_methods(
+                1,
+                &all.as_rb_value(),
+                self.as_rb_value(),
+            ))
+        }
+    }
 }
@@ -1,6 +1,9 @@
 //! Types and functions for working with Ruby’s Array class.
 
-use std::{cmp::Ordering, convert::Infallible, fmt, marker::PhantomData, os::raw::c_long, slice};
+use std::{
+    borrow::Cow, cmp::Ordering, convert::Infallible, fmt, marker::PhantomData, os::raw::c_long,
+    slice,
+};
 
 #[cfg(ruby_gte_3_2)]
 use rb_sys::rb_ary_hidden_new;
@@ -11,9 +14,8 @@ use rb_sys::{
     rb_ary_delete_at, rb_ary_entry, rb_ary_includes, rb_ary_join, rb_ary_new, rb_ary_new_capa,
     rb_ary_new_from_values, rb_ary_plus, rb_ary_pop, rb_ary_push, rb_ary_rassoc, rb_ary_replace,
     rb_ary_resize, rb_ary_reverse, rb_ary_rotate, rb_ary_shared_with_p, rb_ary_shift,
-    rb_ary_sort_bang, rb_ary_store, rb_ary_subseq, rb_ary_to_ary, rb_ary_unshift,
-    rb_check_array_type, rb_obj_hide, rb_obj_reveal, ruby_value_type, RARRAY_CONST_PTR, RARRAY_LEN,
-    VALUE,
+    rb_ary_sort_bang, rb_ary_store, rb_ary_subseq, rb_ary_to_ary, rb_ary_unshift, rb_obj_hide,
+    rb_obj_reveal, ruby_value_type, RARRAY_CONST_PTR, RARRAY_LEN, VALUE,
 };
 use seq_macro::seq;
 
@@ -23,11 +25,12 @@ use crate::{
     gc,
     into_value::{IntoValue, IntoValueFromNative},
     object::Object,
+    r_hash::{FromRHash, RHash},
     r_string::{IntoRString, RString},
-    try_convert::{TryConvert, TryConvertOwned},
+    try_convert::{to_ary, TryConvert, TryConvertOwned},
     value::{
         private::{self, ReprValue as _},
-        NonZeroValue, ReprValue, Value,
+        LazyId, NonZeroValue, ReprValue, Value,
     },
     Ruby,
 };
@@ -1111,6 +1114,57 @@ impl RArray {
         self.as_slice_unconstrained()
     }
 
+    /// Return `self` as a slice of [`Value`]s, borrowed if `self` is frozen,
+    /// or copied otherwise.
+    ///
+    /// A frozen array can't be modified, so for read-only processing of
+    /// frozen arrays this avoids copying the elements, while still returning
+    /// a copy for arrays that could change.
+    ///
+    /// # Safety
+    ///
+    /// When `self` is frozen this is directly viewing memory owned and
+    /// managed by Ruby. Ruby code can't modify a frozen array, but C code
+    /// can ignore the frozen flag, and Ruby may free or move the memory
+    /// backing the returned slice if `self` is garbage collected or
+    /// compacted. The caller must ensure this does not happen.
+    ///
+    /// When `self` is not frozen the copied [`Value`]s are held in a [`Vec`]
+    /// on the heap, where they are hidden from Ruby's garbage collector.
+    ///
+    /// Ruby must not be allowed to garbage collect `self`, or any of the
+    /// values in the slice, while a refrence to the slice is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use magnus::{prelude::*, Error, RArray, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary: RArray = ruby.eval("[1, 2, 3]")?;
+    ///     // must not call any Ruby api that may modify ary while we have a
+    ///     // refrence to the return value of ::to_cow_slice()
+    ///     unsafe {
+    ///         assert!(matches!(ary.to_cow_slice(), Cow::Owned(_)));
+    ///         ary.freeze();
+    ///         assert!(matches!(ary.to_cow_slice(), Cow::Borrowed(_)));
+    ///         assert_eq!(ary.to_cow_slice().len(), 3);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn to_cow_slice(&self) -> Cow<'_, [Value]> {
+        if self.is_frozen() {
+            Cow::Borrowed(self.as_slice())
+        } else {
+            Cow::Owned(self.as_slice().to_vec())
+        }
+    }
+
     pub(crate) unsafe fn as_slice_unconstrained<'a>(self) -> &'a [Value] {
         debug_assert_value!(self);
         slice::from_raw_parts(
@@ -1148,6 +1202,67 @@ impl RArray {
         unsafe { self.as_slice().iter().map(|v| T::try_convert(*v)).collect() }
     }
 
+    /// Convert `self`, an Array of Hashes, to a Rust vector of `T`s.
+    ///
+    /// Each element is converted to an [`RHash`] (as with [`TryConvert`])
+    /// and then to `T` with [`FromRHash`]. Errors if converting any element
+    /// fails, with the index of the element included in the error message.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{r_hash::FromRHash, Error, RArray, RHash, Ruby};
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Point {
+    ///     x: i64,
+    ///     y: i64,
+    /// }
+    ///
+    /// impl FromRHash for Point {
+    ///     fn from_r_hash(hash: RHash) -> Result<Self, Error> {
+    ///         Ok(Self {
+    ///             x: hash.fetch_field("x")?,
+    ///             y: hash.fetch_field("y")?,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary: RArray = ruby.eval("[{x: 1, y: 2}, {x: 3, y: 4}]")?;
+    ///     assert_eq!(
+    ///         ary.to_vec_of::<Point>()?,
+    ///         vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }]
+    ///     );
+    ///
+    ///     let ary: RArray = ruby.eval(r#"[{x: 1, y: 2}, {x: 3, y: "4"}]"#)?;
+    ///     let err = ary.to_vec_of::<Point>().unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_type_error()));
+    ///     assert!(err.to_string().contains("element 1: field `y`: "));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_vec_of<T>(self) -> Result<Vec<T>, Error>
+    where
+        T: FromRHash,
+    {
+        let mut vec = Vec::with_capacity(self.len());
+        // conversion may run arbitrary Ruby code, which could modify the
+        // array, so don't hold a slice and re-check the length each time
+        let mut i = 0;
+        while i < self.len() {
+            vec.push(
+                self.entry::<RHash>(i as isize)
+                    .and_then(T::from_r_hash)
+                    .map_err(|e| e.with_context(format!("element {}", i)))?,
+            );
+            i += 1;
+        }
+        Ok(vec)
+    }
+
     /// Convert `self` to a Rust array of [`Value`]s, of length `N`.
     ///
     /// Errors if the Ruby array is not of length `N`.
@@ -1355,6 +1470,148 @@ impl RArray {
         self.enumeratorize("each", ())
     }
 
+    /// Returns a new array containing the elements of `self` for which `func`
+    /// returns `true`.
+    ///
+    /// Elements are converted to `T` before being passed to `func`. Unlike
+    /// calling Ruby's `Array#select` with a block this does not create a
+    /// Ruby `Proc` or yield to Ruby.
+    ///
+    /// Errors if converting an element fails, or `func` returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3, 4, 5]);
+    ///     let even = ary.select(|i: i64| Ok(i % 2 == 0))?;
+    ///     rb_assert!(ruby, "even == [2, 4]", even);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn select<T, F>(self, mut func: F) -> Result<Self, Error>
+    where
+        T: TryConvert,
+        F: FnMut(T) -> Result<bool, Error>,
+    {
+        let result = Ruby::get_with(self).ary_new();
+        // `func` may modify `self`, so don't hold a slice and re-check the
+        // length each time
+        let mut i = 0;
+        while i < self.len() {
+            let val: Value = self.entry(i as isize)?;
+            if func(T::try_convert(val)?)? {
+                result.push(val)?;
+            }
+            i += 1;
+        }
+        Ok(result)
+    }
+
+    /// Returns a new array containing the elements of `self` for which `func`
+    /// returns `false`.
+    ///
+    /// The inverse of [`select`](RArray::select).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3, 4, 5]);
+    ///     let odd = ary.reject(|i: i64| Ok(i % 2 == 0))?;
+    ///     rb_assert!(ruby, "odd == [1, 3, 5]", odd);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn reject<T, F>(self, mut func: F) -> Result<Self, Error>
+    where
+        T: TryConvert,
+        F: FnMut(T) -> Result<bool, Error>,
+    {
+        self.select(|val| func(val).map(|b| !b))
+    }
+
+    /// Removes the elements of `self` for which `func` returns `false`, in
+    /// place.
+    ///
+    /// This is the equivalent of Ruby's `Array#select!`/`Array#keep_if`, with
+    /// `func` called natively as with [`select`](RArray::select).
+    ///
+    /// Errors if `self` is frozen, converting an element fails, or `func`
+    /// returns an error. In the case of an error `self` is left unmodified.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3, 4, 5]);
+    ///     ary.retain(|i: i64| Ok(i > 2))?;
+    ///     rb_assert!(ruby, "ary == [3, 4, 5]", ary);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn retain<T, F>(self, func: F) -> Result<(), Error>
+    where
+        T: TryConvert,
+        F: FnMut(T) -> Result<bool, Error>,
+    {
+        self.check_frozen()?;
+        let kept = self.select(func)?;
+        self.replace(kept)
+    }
+
+    /// Replaces each element of `self` with the result of calling `func` on
+    /// that element, in place.
+    ///
+    /// This is the equivalent of Ruby's `Array#map!`, with `func` called
+    /// natively as with [`select`](RArray::select).
+    ///
+    /// Errors if `self` is frozen, converting an element fails, or `func`
+    /// returns an error. In the case of an error elements before the one
+    /// that errored will have been replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3]);
+    ///     ary.map_in_place(|i: i64| Ok(i * 10))?;
+    ///     rb_assert!(ruby, "ary == [10, 20, 30]", ary);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn map_in_place<T, U, F>(self, mut func: F) -> Result<(), Error>
+    where
+        T: TryConvert,
+        U: IntoValue,
+        F: FnMut(T) -> Result<U, Error>,
+    {
+        self.check_frozen()?;
+        let mut i = 0;
+        while i < self.len() {
+            let val = func(self.entry(i as isize)?)?;
+            self.store(i as isize, val)?;
+            i += 1;
+        }
+        Ok(())
+    }
+
     /// Returns true if both `self` and `other` share the same backing storage.
     ///
     /// It is possible for two Ruby Arrays to share the same backing storage,
@@ -1571,6 +1828,136 @@ impl RArray {
             .and_then(<Option<i64>>::try_convert)
             .map(|opt| opt.map(|i| i.cmp(&0)))
     }
+
+    /// Return a new array with the elements of `self` and any nested arrays,
+    /// recursively, as with Ruby's `Array#flatten`.
+    ///
+    /// If `depth` is `Some`, only that many levels of nesting are flattened.
+    ///
+    /// Errors with an `ArgumentError` if `self` contains itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary: magnus::RArray = ruby.eval("[1, [2, [3, [4]]]]")?;
+    ///     rb_assert!(ruby, "a == [1, 2, 3, 4]", a = ary.flatten(None)?);
+    ///     rb_assert!(ruby, "a == [1, 2, [3, [4]]]", a = ary.flatten(Some(1))?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn flatten(self, depth: Option<usize>) -> Result<Self, Error> {
+        static FLATTEN: LazyId = LazyId::new("flatten");
+        match depth {
+            Some(depth) => self.funcall(*FLATTEN, (depth,)),
+            None => self.funcall(*FLATTEN, ()),
+        }
+    }
+
+    /// Return a new array with duplicate elements of `self` removed, as with
+    /// Ruby's `Array#uniq`.
+    ///
+    /// Elements are compared with `hash` and `eql?`, and the first occurrence
+    /// of each is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 1, 3, 2]);
+    ///     assert_eq!(ary.uniq()?.to_vec::<i64>()?, vec![1, 2, 3]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn uniq(self) -> Result<Self, Error> {
+        static UNIQ: LazyId = LazyId::new("uniq");
+        self.funcall(*UNIQ, ())
+    }
+
+    /// Return a new array with the `nil` elements of `self` removed, as with
+    /// Ruby's `Array#compact`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![Some(1), None, Some(2), None]);
+    ///     assert_eq!(ary.compact()?.to_vec::<i64>()?, vec![1, 2]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn compact(self) -> Result<Self, Error> {
+        static COMPACT: LazyId = LazyId::new("compact");
+        self.funcall(*COMPACT, ())
+    }
+
+    /// Return a random element of `self`, as with Ruby's `Array#sample`.
+    ///
+    /// Ruby's default random number generator is used, so results are
+    /// affected by `Kernel#srand`. Returns `nil` if `self` is empty, so `T`
+    /// should be an [`Option`] if `self` may be empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3]);
+    ///     let n: i64 = ary.sample()?;
+    ///     assert!((1..=3).contains(&n));
+    ///
+    ///     assert_eq!(ruby.ary_new().sample::<Option<i64>>()?, None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn sample<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        static SAMPLE: LazyId = LazyId::new("sample");
+        self.funcall(*SAMPLE, ())
+    }
+
+    /// Return a new array with the elements of `self` in a random order, as
+    /// with Ruby's `Array#shuffle`.
+    ///
+    /// Ruby's default random number generator is used, so results are
+    /// affected by `Kernel#srand`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary = ruby.ary_from_vec(vec![1, 2, 3, 4, 5]);
+    ///     let mut shuffled = ary.shuffle()?.to_vec::<i64>()?;
+    ///     shuffled.sort();
+    ///     assert_eq!(shuffled, vec![1, 2, 3, 4, 5]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn shuffle(self) -> Result<Self, Error> {
+        static SHUFFLE: LazyId = LazyId::new("shuffle");
+        self.funcall(*SHUFFLE, ())
+    }
 }
 
 impl fmt::Display for RArray {
@@ -1704,19 +2091,12 @@ impl ReprValue for RArray {}
 
 impl TryConvert for RArray {
     fn try_convert(val: Value) -> Result<Self, Error> {
-        if let Some(v) = Self::from_value(val) {
-            return Ok(v);
-        }
-        unsafe {
-            protect(|| Value::new(rb_check_array_type(val.as_rb_value()))).and_then(|res| {
-                Self::from_value(res).ok_or_else(|| {
-                    Error::new(
-                        Ruby::get_with(val).exception_type_error(),
-                        format!("no implicit conversion of {} into Array", val.class()),
-                    )
-                })
-            })
-        }
+        to_ary(val)?.ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into Array", val.class()),
+            )
+        })
     }
 }
 
@@ -3,9 +3,13 @@ use std::{
     os::raw::{c_long, c_longlong, c_ulong, c_ulonglong},
 };
 
+#[cfg(feature = "capi-strict")]
+use rb_sys::rb_big_sign;
+#[cfg(not(feature = "capi-strict"))]
+use rb_sys::ruby_fl_type;
 use rb_sys::{
-    rb_ll2inum, rb_num2ll, rb_num2long, rb_num2ull, rb_num2ulong, rb_ull2inum, ruby_fl_type,
-    ruby_value_type, VALUE,
+    rb_ll2inum, rb_num2ll, rb_num2long, rb_num2ull, rb_num2ulong, rb_ull2inum, ruby_value_type,
+    VALUE,
 };
 
 use crate::{
@@ -421,6 +425,10 @@ impl RBignum {
     /// ```
     pub fn is_positive(self) -> bool {
         debug_assert_value!(self);
+        #[cfg(feature = "capi-strict")]
+        return unsafe { rb_big_sign(self.as_rb_value()) != 0 };
+
+        #[cfg(not(feature = "capi-strict"))]
         unsafe {
             let r_basic = self.r_basic_unchecked();
             r_basic.as_ref().flags & (ruby_fl_type::RUBY_FL_USER1 as VALUE) != 0
@@ -18,10 +18,68 @@ use crate::{
     Ruby,
 };
 
+/// # `RComplex`
+///
+/// Functions that can be used to create Ruby `Complex`s.
+///
+/// See also the [`RComplex`] type.
+impl Ruby {
+    /// Create a new `RComplex`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let complex = ruby.complex_new(ruby.integer_from_i64(2), ruby.integer_from_i64(1));
+    ///     assert_eq!(complex.to_string(), "2+1i");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn complex_new<T, U>(&self, real: T, imag: U) -> RComplex
+    where
+        T: Numeric,
+        U: Numeric,
+    {
+        RComplex::new(real, imag)
+    }
+
+    /// Create a new `RComplex` using polar representation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let complex =
+    ///         ruby.complex_new_polar(ruby.integer_from_i64(2), ruby.integer_from_i64(3))?;
+    ///     assert_eq!(
+    ///         complex.to_string(),
+    ///         "-1.9799849932008908+0.2822400161197344i"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn complex_new_polar<T, U>(&self, real: T, imag: U) -> Result<RComplex, Error>
+    where
+        T: Numeric,
+        U: Numeric,
+    {
+        RComplex::polar(real, imag)
+    }
+}
+
 /// A Value pointer to a RComplex struct, Ruby's internal representation of
 /// complex numbers.
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
+/// See [`Ruby`](Ruby#rcomplex) for methods to create an `RComplex`.
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct RComplex(NonZeroValue);
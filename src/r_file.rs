@@ -1,15 +1,20 @@
-use std::fmt;
 #[cfg(ruby_lt_3_3)]
 use std::ptr::NonNull;
+use std::{
+    ffi::CString,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 #[cfg(ruby_gte_3_3)]
 use rb_sys::rb_io_descriptor;
-use rb_sys::ruby_value_type;
+use rb_sys::{rb_file_open_str, rb_io_close, rb_io_flush, ruby_value_type};
 
 use crate::{
-    error::Error,
+    error::{protect, Error},
     into_value::IntoValue,
     object::Object,
+    r_string::IntoRString,
     try_convert::TryConvert,
     value::{
         private::{self, ReprValue as _},
@@ -18,6 +23,50 @@ use crate::{
     Ruby,
 };
 
+/// # `RFile`
+///
+/// Functions that can be used to open Ruby `File`s.
+///
+/// See also the [`RFile`] type.
+impl Ruby {
+    /// Open the file at `path` with `mode`, as with Ruby's `File.open`.
+    ///
+    /// `mode` is a Ruby mode string, such as `"r"`, `"w+"`, or `"ab"`.
+    ///
+    /// Errors if `mode` contains a null byte, or if the file can not be
+    /// opened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = std::env::temp_dir().join("magnus_file_open.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     assert_eq!(file.path()?, Some(path));
+    ///     file.close()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn file_open<P>(&self, path: P, mode: &str) -> Result<RFile, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().into_r_string_with(self);
+        let mode = CString::new(mode)
+            .map_err(|e| Error::new(self.exception_arg_error(), e.to_string()))?;
+        protect(|| unsafe {
+            RFile::from_value_unchecked(Value::new(rb_file_open_str(
+                path.as_rb_value(),
+                mode.as_c_str().as_ptr(),
+            )))
+        })
+    }
+}
+
 /// A Value pointer to a RFile struct, Ruby's internal representation of IO.
 ///
 /// See the [`ReprValue`] and [`Object`] traits for additional methods
@@ -52,6 +101,132 @@ impl RFile {
         }
     }
 
+    /// Returns the path the file was opened with, or `None` if the IO does
+    /// not have a path.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = std::env::temp_dir().join("magnus_file_path.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     assert_eq!(file.path()?, Some(path));
+    ///     file.close()?;
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn path(self) -> Result<Option<PathBuf>, Error> {
+        // IO#path is only defined from Ruby 3.2, File#path is always available
+        self.check_funcall("path", ())
+            .transpose()
+            .map(Option::flatten)
+    }
+
+    /// Returns the file descriptor of the file.
+    ///
+    /// Unlike [`as_raw_fd`](fd::AsRawFd::as_raw_fd) this returns an error if
+    /// the file is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RFile, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let stderr: RFile = ruby.eval("STDERR")?;
+    ///     assert_eq!(stderr.fileno()?, 2);
+    ///
+    ///     let path = std::env::temp_dir().join("magnus_file_fileno.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     file.close()?;
+    ///     assert!(file.fileno().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn fileno(self) -> Result<fd::RawFd, Error> {
+        self.funcall("fileno", ())
+    }
+
+    /// Returns whether the file is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = std::env::temp_dir().join("magnus_file_is_closed.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     assert!(!file.is_closed()?);
+    ///     file.close()?;
+    ///     assert!(file.is_closed()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn is_closed(self) -> Result<bool, Error> {
+        self.funcall("closed?", ())
+    }
+
+    /// Close the file, flushing any pending writes.
+    ///
+    /// Closing an already closed file has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = std::env::temp_dir().join("magnus_file_close.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     file.close()?;
+    ///     file.close()?;
+    ///     assert!(file.is_closed()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn close(self) -> Result<(), Error> {
+        protect(|| unsafe { Value::new(rb_io_close(self.as_rb_value())) })?;
+        Ok(())
+    }
+
+    /// Flush any buffered data to the operating system.
+    ///
+    /// Errors if the file is closed or not open for writing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let path = std::env::temp_dir().join("magnus_file_flush.txt");
+    ///     let file = ruby.file_open(&path, "w")?;
+    ///     let _: usize = file.funcall("write", ("hello",))?;
+    ///     file.flush()?;
+    ///     assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello");
+    ///     file.close()?;
+    ///     assert!(file.flush().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn flush(self) -> Result<(), Error> {
+        protect(|| unsafe { Value::new(rb_io_flush(self.as_rb_value())) })?;
+        Ok(())
+    }
+
     #[cfg(ruby_lt_3_3)]
     fn as_internal(self) -> NonNull<rb_sys::RFile> {
         // safe as inner value is NonZero
@@ -1,34 +1,43 @@
 //! Types and functions for working with Ruby’s Hash class.
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::Infallible,
     fmt,
     hash::Hash,
+    marker::PhantomData,
     os::raw::{c_int, c_long},
     panic::AssertUnwindSafe,
 };
 
+#[cfg(ruby_gte_3_0)]
+use rb_sys::rb_enc_interned_str;
 #[cfg(ruby_gte_3_2)]
 use rb_sys::rb_hash_new_capa;
 use rb_sys::{
-    rb_check_hash_type, rb_hash_aref, rb_hash_aset, rb_hash_bulk_insert, rb_hash_clear,
-    rb_hash_delete, rb_hash_fetch, rb_hash_foreach, rb_hash_lookup, rb_hash_lookup2, rb_hash_new,
-    rb_hash_size, rb_hash_size_num, rb_hash_update_by, ruby_value_type, VALUE,
+    rb_hash_aref, rb_hash_aset, rb_hash_bulk_insert, rb_hash_clear, rb_hash_delete, rb_hash_fetch,
+    rb_hash_foreach, rb_hash_lookup, rb_hash_lookup2, rb_hash_new, rb_hash_size, rb_hash_size_num,
+    rb_hash_update_by, rb_str_intern, rb_sym2str, ruby_value_type, VALUE,
 };
 
 use crate::{
+    block::Proc,
     error::{protect, raise, Error},
     into_value::{IntoValue, IntoValueFromNative},
     object::Object,
-    try_convert::{TryConvert, TryConvertOwned},
+    r_array::RArray,
+    r_string::RString,
+    symbol::Symbol,
+    try_convert::{to_hash, TryConvert, TryConvertOwned},
     value::{
         private::{self, ReprValue as _},
-        Fixnum, NonZeroValue, ReprValue, Value, QUNDEF,
+        BoxValue, Fixnum, NonZeroValue, ReprValue, Value, QUNDEF,
     },
     Ruby,
 };
 
+pub use magnus_macros::FromRHash;
+
 /// Iteration state for [`RHash::foreach`].
 #[repr(u32)]
 pub enum ForEach {
@@ -653,6 +662,220 @@ impl RHash {
             .and_then(TryConvert::try_convert)
     }
 
+    /// Return the value for the string key `key` as a [`Value`].
+    ///
+    /// Returns `None` if `key` is missing. This is equivalent to
+    /// [`get`](RHash::get) with a `&str` key, but on Ruby 3.0+ it looks up
+    /// `key` as an interned (frozen, deduplicated) string, which avoids
+    /// allocating a new Ruby string if `key` is already interned, as literal
+    /// and hash key strings often are.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"answer" => 42}"#)?;
+    ///     assert!(hash.get_str("answer").is_some());
+    ///     assert!(hash.get_str("missing").is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get_str(self, key: &str) -> Option<Value> {
+        self.get(str_key(&Ruby::get_with(self), key))
+    }
+
+    /// Set the value `val` for the string key `key`.
+    ///
+    /// This is equivalent to [`aset`](RHash::aset) with a `&str` key, but
+    /// uses an interned string for the key, as with
+    /// [`get_str`](RHash::get_str). Ruby would otherwise copy and freeze a
+    /// string key on insertion.
+    ///
+    /// Errors if `self` is frozen or `val` does not respond to `hash`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash = ruby.hash_new();
+    ///     hash.aset_str("answer", 42)?;
+    ///     rb_assert!(ruby, r#"hash == {"answer" => 42}"#, hash);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn aset_str<V>(self, key: &str, val: V) -> Result<(), Error>
+    where
+        V: IntoValue,
+    {
+        self.aset(str_key(&Ruby::get_with(self), key), val)
+    }
+
+    /// Return the value for the string key `key`, converting it to `U`.
+    ///
+    /// Returns `Err` if `key` is missing. This is equivalent to
+    /// [`fetch`](RHash::fetch) with a `&str` key, but uses an interned string
+    /// for the key, as with [`get_str`](RHash::get_str).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"answer" => 42}"#)?;
+    ///     assert_eq!(hash.fetch_str::<i64>("answer")?, 42);
+    ///     assert!(hash.fetch_str::<i64>("missing").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn fetch_str<U>(self, key: &str) -> Result<U, Error>
+    where
+        U: TryConvert,
+    {
+        self.fetch(str_key(&Ruby::get_with(self), key))
+    }
+
+    /// Return the value for the field `key`, converting it to `T`.
+    ///
+    /// `key` is first looked up as a Symbol, then as a String, so this works
+    /// with records using either, e.g. from a JSON parser or a database
+    /// driver.
+    ///
+    /// Errors with a `KeyError` if `key` is missing, or if the conversion
+    /// fails, with the name of the field included in the error message. This
+    /// is intended for use in implementations of [`FromRHash`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{name: "Alice", "age" => "unknown"}"#)?;
+    ///     assert_eq!(hash.fetch_field::<String>("name")?, "Alice");
+    ///
+    ///     let err = hash.fetch_field::<i64>("age").unwrap_err();
+    ///     assert!(err.to_string().contains("field `age`: "));
+    ///
+    ///     let err = hash.fetch_field::<String>("email").unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_key_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn fetch_field<T>(self, key: &str) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self);
+        let val = self
+            .get(ruby.to_symbol(key))
+            .or_else(|| self.get_str(key))
+            .ok_or_else(|| {
+                Error::new(
+                    ruby.exception_key_error(),
+                    format!("missing field `{}`", key),
+                )
+            })?;
+        T::try_convert(val).map_err(|e| e.with_context(format!("field `{}`", key)))
+    }
+
+    /// Return the value for the field `key`, converting it to `T`, or `None`
+    /// if `key` is missing.
+    ///
+    /// As with [`fetch_field`](RHash::fetch_field), `key` is first looked up
+    /// as a Symbol, then as a String, and errors include the name of the
+    /// field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"name" => "Alice"}"#)?;
+    ///     assert_eq!(hash.get_field::<String>("name")?.as_deref(), Some("Alice"));
+    ///     assert_eq!(hash.get_field::<String>("email")?, None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get_field<T>(self, key: &str) -> Result<Option<T>, Error>
+    where
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self);
+        match self.get(ruby.to_symbol(key)).or_else(|| self.get_str(key)) {
+            Some(val) => T::try_convert(val)
+                .map(Some)
+                .map_err(|e| e.with_context(format!("field `{}`", key))),
+            None => Ok(None),
+        }
+    }
+
+    /// Return `Err` containing a Ruby `ArgumentError` if `self` has any keys
+    /// other than the Symbols or Strings in `fields`.
+    ///
+    /// This is intended for use in implementations of [`FromRHash`], to
+    /// reject unknown keyword arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{name: "Alice", "age" => 42}"#)?;
+    ///     assert!(hash.check_fields(&["name", "age", "email"]).is_ok());
+    ///
+    ///     let err = hash.check_fields(&["name"]).unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_arg_error()));
+    ///     assert_eq!(err.to_string(), r#"ArgumentError: unknown keyword: "age""#);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn check_fields(self, fields: &[&str]) -> Result<(), Error> {
+        let mut unknown = Vec::new();
+        self.foreach(|key: Value, _: Value| {
+            let known = if let Some(sym) = Symbol::from_value(key) {
+                fields.iter().any(|f| sym.eql_str(f))
+            } else if let Some(s) = RString::from_value(key) {
+                fields.iter().any(|f| s.eql_str(f))
+            } else {
+                false
+            };
+            if !known {
+                unknown.push(key.inspect());
+            }
+            Ok(ForEach::Continue)
+        })?;
+        match unknown.len() {
+            0 => Ok(()),
+            1 => Err(Error::new(
+                Ruby::get_with(self).exception_arg_error(),
+                format!("unknown keyword: {}", unknown[0]),
+            )),
+            _ => Err(Error::new(
+                Ruby::get_with(self).exception_arg_error(),
+                format!("unknown keywords: {}", unknown.join(", ")),
+            )),
+        }
+    }
+
     /// Removes the key `key` from self and returns the associated value,
     /// converting it to `U`.
     ///
@@ -804,6 +1027,48 @@ impl RHash {
         Ok(map)
     }
 
+    /// Return `self` converted to a Rust [`BTreeMap`].
+    ///
+    /// This will only convert to a map of 'owned' Rust native types. The types
+    /// representing Ruby objects can not be stored in a heap-allocated
+    /// datastructure like a [`BTreeMap`] as they are hidden from the mark
+    /// phase of Ruby's garbage collector, and thus may be prematurely garbage
+    /// collected in the following sweep phase.
+    ///
+    /// Errors if the conversion of any key or value fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    ///
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let r_hash: RHash = ruby.eval(r#"{"b" => 2, "a" => 1}"#)?;
+    ///     let map = r_hash.to_btree_map::<String, i64>()?;
+    ///     assert_eq!(
+    ///         map.into_iter().collect::<Vec<_>>(),
+    ///         vec![(String::from("a"), 1), (String::from("b"), 2)]
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_btree_map<K, V>(self) -> Result<BTreeMap<K, V>, Error>
+    where
+        K: TryConvertOwned + Ord,
+        V: TryConvertOwned,
+    {
+        let mut map = BTreeMap::new();
+        self.foreach(|key, value| {
+            map.insert(key, value);
+            Ok(ForEach::Continue)
+        })?;
+        Ok(map)
+    }
+
     /// Convert `self` to a Rust vector of key/value pairs.
     ///
     /// This will only convert to a map of 'owned' Rust native types. The types
@@ -840,6 +1105,335 @@ impl RHash {
         Ok(vec)
     }
 
+    /// Returns an iterator over the keys of `self`, converted to `K`.
+    ///
+    /// Unlike calling Ruby's `Hash#keys` this only buffers a batch of keys at
+    /// a time rather than allocating an Array of all the keys, making it
+    /// cheaper to scan a large hash, especially if iteration stops early. See
+    /// [`HashIter`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, "bar" => 2, "baz" => 3}"#)?;
+    ///     let keys = hash.keys_iter().collect::<Result<Vec<String>, Error>>()?;
+    ///     assert_eq!(keys, ["foo", "bar", "baz"]);
+    ///
+    ///     let mut iter = hash.keys_iter::<String>();
+    ///     assert_eq!(iter.next().transpose()?, Some(String::from("foo")));
+    ///     hash.aset("qux", 4)?;
+    ///     assert!(iter.next().unwrap().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn keys_iter<K>(self) -> HashIter<K>
+    where
+        K: TryConvert,
+    {
+        HashIter::new(self, false)
+    }
+
+    /// Returns an iterator over the values of `self`, converted to `V`.
+    ///
+    /// Unlike calling Ruby's `Hash#values` this only buffers a batch of values
+    /// at a time rather than allocating an Array of all the values, making it
+    /// cheaper to scan a large hash, especially if iteration stops early. See
+    /// [`HashIter`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, "bar" => 2, "baz" => 3}"#)?;
+    ///     let sum = hash
+    ///         .values_iter::<i64>()
+    ///         .sum::<Result<i64, Error>>()?;
+    ///     assert_eq!(sum, 6);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn values_iter<V>(self) -> HashIter<V>
+    where
+        V: TryConvert,
+    {
+        HashIter::new(self, true)
+    }
+
+    /// Returns whether `func` returns `true` for any entry of `self`.
+    ///
+    /// Stops iterating as soon as `func` returns `true` or an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, "bar" => 2, "baz" => 3}"#)?;
+    ///     assert!(hash.any(|_: String, v: i64| Ok(v > 2))?);
+    ///     assert!(!hash.any(|k: String, _: i64| Ok(k.is_empty()))?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn any<F, K, V>(self, mut func: F) -> Result<bool, Error>
+    where
+        F: FnMut(K, V) -> Result<bool, Error>,
+        K: TryConvert,
+        V: TryConvert,
+    {
+        let mut res = false;
+        self.foreach(|key, value| {
+            if func(key, value)? {
+                res = true;
+                Ok(ForEach::Stop)
+            } else {
+                Ok(ForEach::Continue)
+            }
+        })?;
+        Ok(res)
+    }
+
+    /// Returns whether `func` returns `true` for every entry of `self`.
+    ///
+    /// Stops iterating as soon as `func` returns `false` or an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, "bar" => 2, "baz" => 3}"#)?;
+    ///     assert!(hash.all(|k: String, _: i64| Ok(k.len() == 3))?);
+    ///     assert!(!hash.all(|_: String, v: i64| Ok(v > 2))?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn all<F, K, V>(self, mut func: F) -> Result<bool, Error>
+    where
+        F: FnMut(K, V) -> Result<bool, Error>,
+        K: TryConvert,
+        V: TryConvert,
+    {
+        let mut res = true;
+        self.foreach(|key, value| {
+            if func(key, value)? {
+                Ok(ForEach::Continue)
+            } else {
+                res = false;
+                Ok(ForEach::Stop)
+            }
+        })?;
+        Ok(res)
+    }
+
+    /// Returns the first entry of `self` for which `func` returns `true`.
+    ///
+    /// Stops iterating as soon as `func` returns `true` or an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, "bar" => 2, "baz" => 3}"#)?;
+    ///     let res = hash.find(|_: &String, v: &i64| Ok(*v > 1))?;
+    ///     assert_eq!(res, Some((String::from("bar"), 2)));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn find<F, K, V>(self, mut func: F) -> Result<Option<(K, V)>, Error>
+    where
+        F: FnMut(&K, &V) -> Result<bool, Error>,
+        K: TryConvert,
+        V: TryConvert,
+    {
+        let mut res = None;
+        self.foreach(|key, value| {
+            if func(&key, &value)? {
+                res = Some((key, value));
+                Ok(ForEach::Stop)
+            } else {
+                Ok(ForEach::Continue)
+            }
+        })?;
+        Ok(res)
+    }
+
+    /// Return a new hash with the `String` keys of `self` converted to
+    /// `Symbol`s.
+    ///
+    /// Keys of other types are left unchanged. If a string key and a symbol
+    /// key would collide, the later entry in `self` wins. The default
+    /// value/proc and `compare_by_identity` setting of `self` are kept. Nested
+    /// hashes are not converted, see [`deep_symbolize_keys`](RHash::deep_symbolize_keys).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => 1, :bar => {"baz" => 2}, 3 => 4}"#)?;
+    ///     let res = hash.symbolize_keys()?;
+    ///     rb_assert!(ruby, r#"res == {foo: 1, bar: {"baz" => 2}, 3 => 4}"#, res);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn symbolize_keys(self) -> Result<RHash, Error> {
+        self.transform_keys(false, symbolize_key)
+    }
+
+    /// Return a new hash with the `String` keys of `self` converted to
+    /// `Symbol`s, recursively converting any hashes nested in values,
+    /// including those within arrays.
+    ///
+    /// The default value/proc and `compare_by_identity` setting of `self` are
+    /// kept, but not those of nested hashes.
+    ///
+    /// Errors if a hash or array contains itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{"foo" => {"bar" => [{"baz" => 1}]}}"#)?;
+    ///     let res = hash.deep_symbolize_keys()?;
+    ///     rb_assert!(ruby, "res == {foo: {bar: [{baz: 1}]}}", res);
+    ///
+    ///     let hash: RHash = ruby.eval(r#"h = {"foo" => []}; h["foo"] << h; h"#)?;
+    ///     assert!(hash.deep_symbolize_keys().is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn deep_symbolize_keys(self) -> Result<RHash, Error> {
+        self.transform_keys(true, symbolize_key)
+    }
+
+    /// Return a new hash with the `Symbol` keys of `self` converted to
+    /// `String`s.
+    ///
+    /// Keys of other types are left unchanged. If a string key and a symbol
+    /// key would collide, the later entry in `self` wins. The default
+    /// value/proc and `compare_by_identity` setting of `self` are kept. Nested
+    /// hashes are not converted, see [`deep_stringify_keys`](RHash::deep_stringify_keys).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval(r#"{foo: 1, "bar" => {baz: 2}, 3 => 4}"#)?;
+    ///     let res = hash.stringify_keys()?;
+    ///     rb_assert!(ruby, r#"res == {"foo" => 1, "bar" => {baz: 2}, 3 => 4}"#, res);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn stringify_keys(self) -> Result<RHash, Error> {
+        self.transform_keys(false, stringify_key)
+    }
+
+    /// Return a new hash with the `Symbol` keys of `self` converted to
+    /// `String`s, recursively converting any hashes nested in values,
+    /// including those within arrays.
+    ///
+    /// The default value/proc and `compare_by_identity` setting of `self` are
+    /// kept, but not those of nested hashes.
+    ///
+    /// Errors if a hash or array contains itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, RHash, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let hash: RHash = ruby.eval("{foo: {bar: [{baz: 1}]}}")?;
+    ///     let res = hash.deep_stringify_keys()?;
+    ///     rb_assert!(ruby, r#"res == {"foo" => {"bar" => [{"baz" => 1}]}}"#, res);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn deep_stringify_keys(self) -> Result<RHash, Error> {
+        self.transform_keys(true, stringify_key)
+    }
+
+    // The default value/proc and `compare_by_identity` setting of `self` are
+    // copied to the new hash. This is only done for the outermost hash, as
+    // it takes several method calls per hash.
+    fn transform_keys(
+        self,
+        deep: bool,
+        func: fn(Value) -> Result<Value, Error>,
+    ) -> Result<RHash, Error> {
+        let hash = Ruby::get_with(self).hash_new_capa(self.len());
+        if self.funcall::<_, _, bool>("compare_by_identity?", ())? {
+            let _: Value = hash.funcall("compare_by_identity", ())?;
+        }
+        if let Some(default_proc) = self.funcall::<_, _, Option<Proc>>("default_proc", ())? {
+            let _: Value = hash.funcall("default_proc=", (default_proc,))?;
+        } else {
+            let default: Value = self.funcall("default", ())?;
+            if !default.is_nil() {
+                let _: Value = hash.funcall("default=", (default,))?;
+            }
+        }
+        self.transform_keys_into(hash, deep, func, &mut Vec::new())?;
+        Ok(hash)
+    }
+
+    // `seen` holds the hashes/arrays currently being converted, to detect
+    // recursive structures. These are all reachable from the outermost hash,
+    // so don't need to be marked for the GC.
+    fn transform_keys_into(
+        self,
+        hash: RHash,
+        deep: bool,
+        func: fn(Value) -> Result<Value, Error>,
+        seen: &mut Vec<Value>,
+    ) -> Result<(), Error> {
+        let ruby = Ruby::get_with(self);
+        seen.push(self.as_value());
+        self.foreach(|key: Value, value: Value| {
+            let value = if deep {
+                transform_keys_nested(&ruby, value, func, seen)?
+            } else {
+                value
+            };
+            hash.aset(func(key)?, value)?;
+            Ok(ForEach::Continue)
+        })?;
+        seen.pop();
+        Ok(())
+    }
+
     /// Return the number of entries in `self` as a Ruby [`Fixnum`].
     ///
     /// # Examples
@@ -900,6 +1494,233 @@ impl RHash {
     }
 }
 
+/// An iterator over the keys or values of an [`RHash`].
+///
+/// See [`RHash::keys_iter`] and [`RHash::values_iter`].
+///
+/// Entries are read in to a single reused Ruby Array in batches with
+/// `rb_hash_foreach`. As `rb_hash_foreach` can't be resumed, each batch
+/// restarts iteration from the beginning of the hash and skips the entries
+/// already seen. Each batch is as large as the number of entries already
+/// seen, so the total cost of iteration remains linear in the size of the
+/// hash.
+///
+/// The hash and buffer are protected from the garbage collector for the
+/// lifetime of the iterator, so it is safe to store it on the heap (e.g. as a
+/// `Box<dyn Iterator>`).
+///
+/// If entries are added to or removed from the hash while iterating, the
+/// next call to [`next`](Iterator::next) returns a `RuntimeError`.
+pub struct HashIter<T> {
+    hash: BoxValue<RHash>,
+    buf: Option<BoxValue<RArray>>,
+    len: usize,
+    values: bool,
+    seen: usize,
+    index: usize,
+    done: bool,
+    phantom: PhantomData<T>,
+}
+
+impl<T> HashIter<T> {
+    const MIN_BATCH: usize = 16;
+
+    fn new(hash: RHash, values: bool) -> Self {
+        Self {
+            hash: BoxValue::new(hash),
+            buf: None,
+            len: hash.len(),
+            values,
+            seen: 0,
+            index: 0,
+            done: false,
+            phantom: PhantomData,
+        }
+    }
+
+    fn fill(&mut self) -> Result<RArray, Error> {
+        let batch = self.seen.max(Self::MIN_BATCH);
+        let buf = match &self.buf {
+            Some(buf) => {
+                buf.clear()?;
+                **buf
+            }
+            None => {
+                let buf = Ruby::get_with(*self.hash).ary_new_capa(batch);
+                self.buf = Some(BoxValue::new(buf));
+                buf
+            }
+        };
+        let mut skip = self.seen;
+        let values = self.values;
+        self.hash.foreach(|key: Value, value: Value| {
+            if skip > 0 {
+                skip -= 1;
+                return Ok(ForEach::Continue);
+            }
+            buf.push(if values { value } else { key })?;
+            if buf.len() < batch {
+                Ok(ForEach::Continue)
+            } else {
+                Ok(ForEach::Stop)
+            }
+        })?;
+        if buf.len() < batch {
+            self.done = true;
+        }
+        self.index = 0;
+        Ok(buf)
+    }
+}
+
+impl<T> Iterator for HashIter<T>
+where
+    T: TryConvert,
+{
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index != usize::MAX && self.hash.len() != self.len {
+            self.done = true;
+            self.index = usize::MAX;
+            return Some(Err(Error::new(
+                Ruby::get_with(*self.hash).exception_runtime_error(),
+                "hash modified during iteration",
+            )));
+        }
+        let buf = match self.buf.as_deref() {
+            Some(&buf) if self.index < buf.len() => buf,
+            _ if self.done => return None,
+            _ => match self.fill() {
+                Ok(buf) => buf,
+                Err(e) => {
+                    self.done = true;
+                    self.index = usize::MAX;
+                    return Some(Err(e));
+                }
+            },
+        };
+        if self.index >= buf.len() {
+            return None;
+        }
+        let val = buf.entry::<Value>(self.index as isize);
+        self.index += 1;
+        self.seen += 1;
+        Some(val.and_then(T::try_convert))
+    }
+}
+
+// Create a string for use as a hash key, interned where possible to avoid
+// allocating.
+#[cfg(ruby_gte_3_0)]
+fn str_key(ruby: &Ruby, key: &str) -> Value {
+    unsafe {
+        Value::new(rb_enc_interned_str(
+            key.as_ptr() as *const _,
+            key.len() as c_long,
+            ruby.utf8_encoding().as_ptr(),
+        ))
+    }
+}
+
+#[cfg(ruby_lt_3_0)]
+fn str_key(ruby: &Ruby, key: &str) -> Value {
+    ruby.str_new(key).as_value()
+}
+
+fn symbolize_key(key: Value) -> Result<Value, Error> {
+    match RString::from_value(key) {
+        Some(s) => protect(|| unsafe { Value::new(rb_str_intern(s.as_rb_value())) }),
+        None => Ok(key),
+    }
+}
+
+fn stringify_key(key: Value) -> Result<Value, Error> {
+    match Symbol::from_value(key) {
+        Some(sym) => Ok(unsafe { Value::new(rb_sym2str(sym.as_rb_value())) }),
+        None => Ok(key),
+    }
+}
+
+fn transform_keys_nested(
+    ruby: &Ruby,
+    value: Value,
+    func: fn(Value) -> Result<Value, Error>,
+    seen: &mut Vec<Value>,
+) -> Result<Value, Error> {
+    let hash = RHash::from_value(value);
+    let ary = RArray::from_value(value);
+    if (hash.is_some() || ary.is_some()) && seen.iter().any(|v| v.equal_id(value)) {
+        return Err(Error::new(
+            ruby.exception_arg_error(),
+            "can't transform keys of recursive hash/array",
+        ));
+    }
+    if let Some(hash) = hash {
+        let new = ruby.hash_new_capa(hash.len());
+        hash.transform_keys_into(new, true, func, seen)?;
+        return Ok(new.as_value());
+    }
+    if let Some(ary) = ary {
+        let new = ruby.ary_new_capa(ary.len());
+        seen.push(value);
+        let mut i = 0;
+        while i < ary.len() {
+            let item = ary.entry(i as isize)?;
+            new.push(transform_keys_nested(ruby, item, func, seen)?)?;
+            i += 1;
+        }
+        seen.pop();
+        return Ok(new.as_value());
+    }
+    Ok(value)
+}
+
+/// Conversion from a Ruby Hash to a Rust 'record' type.
+///
+/// This is used by [`RArray::to_vec_of`] to convert an Array of Hashes, such
+/// as rows from a database query or objects from a JSON API, to a [`Vec`].
+///
+/// This can be derived for structs with named fields with
+/// [`#[derive(FromRHash)]`](derive@FromRHash).
+///
+/// As the result may be stored on the heap, implementing types should not
+/// contain Ruby objects, see [`TryConvertOwned`].
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{r_hash::FromRHash, Error, RHash, Ruby};
+///
+/// struct User {
+///     name: String,
+///     age: u8,
+/// }
+///
+/// impl FromRHash for User {
+///     fn from_r_hash(hash: RHash) -> Result<Self, Error> {
+///         Ok(Self {
+///             name: hash.fetch_field("name")?,
+///             age: hash.fetch_field("age")?,
+///         })
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let hash: RHash = ruby.eval(r#"{name: "Alice", age: 42}"#)?;
+///     let user = User::from_r_hash(hash)?;
+///     assert_eq!(user.name, "Alice");
+///     assert_eq!(user.age, 42);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait FromRHash: Sized {
+    /// Convert `hash` to `Self`.
+    fn from_r_hash(hash: RHash) -> Result<Self, Error>;
+}
+
 impl fmt::Display for RHash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", unsafe { self.to_s_infallible() })
@@ -940,6 +1761,27 @@ where
 {
 }
 
+impl<K, V> IntoValue for BTreeMap<K, V>
+where
+    K: IntoValueFromNative,
+    V: IntoValueFromNative,
+{
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        let hash = handle.hash_new();
+        for (k, v) in self {
+            let _ = hash.aset(k, v);
+        }
+        hash.into_value_with(handle)
+    }
+}
+
+unsafe impl<K, V> IntoValueFromNative for BTreeMap<K, V>
+where
+    K: IntoValueFromNative,
+    V: IntoValueFromNative,
+{
+}
+
 #[cfg(feature = "old-api")]
 impl<K, V> FromIterator<(K, V)> for RHash
 where
@@ -963,18 +1805,11 @@ impl ReprValue for RHash {}
 impl TryConvert for RHash {
     fn try_convert(val: Value) -> Result<Self, Error> {
         debug_assert_value!(val);
-        if let Some(v) = Self::from_value(val) {
-            return Ok(v);
-        }
-        unsafe {
-            protect(|| Value::new(rb_check_hash_type(val.as_rb_value()))).and_then(|res| {
-                Self::from_value(res).ok_or_else(|| {
-                    Error::new(
-                        Ruby::get_with(val).exception_type_error(),
-                        format!("no implicit conversion of {} into Hash", val.class()),
-                    )
-                })
-            })
-        }
+        to_hash(val)?.ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into Hash", val.class()),
+            )
+        })
     }
 }
@@ -0,0 +1,437 @@
+//! Types for working with Ruby's Method and UnboundMethod objects.
+
+use std::{fmt, os::raw::c_int};
+
+use rb_sys::{rb_method_call_kw, rb_obj_is_method, VALUE};
+
+use crate::{
+    block::Proc,
+    error::{protect, Error},
+    into_value::{kw_splat, ArgList, IntoValue},
+    object::Object,
+    r_typed_data::RTypedData,
+    symbol::Symbol,
+    try_convert::TryConvert,
+    value::{
+        private::{self, ReprValue as _},
+        ReprValue, Value,
+    },
+    Ruby,
+};
+
+/// Wrapper type for a Value known to be an instance of Ruby's `Method` class.
+///
+/// A `Method` is a method bound to a receiver, as returned by Ruby's
+/// `Object#method`. This can be used to accept callbacks from Ruby in the
+/// form `method(:foo)` as well as Procs.
+///
+/// As with other Ruby types, an `RMethod` must not be stored on the heap
+/// where it is hidden from Ruby's garbage collector. To keep hold of a
+/// callback, store it in an [`Opaque`](crate::value::Opaque) in a wrapped
+/// type and mark it in
+/// [`DataTypeFunctions::mark`](crate::typed_data::DataTypeFunctions::mark),
+/// or register it with [`gc::register_mark_object`](crate::gc::register_mark_object).
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct RMethod(RTypedData);
+
+impl RMethod {
+    /// Return `Some(RMethod)` if `val` is a `Method`, `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, RMethod};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert!(RMethod::from_value(eval("1.method(:+)").unwrap()).is_some());
+    /// assert!(RMethod::from_value(eval("proc {}").unwrap()).is_none());
+    /// assert!(RMethod::from_value(eval("Integer.instance_method(:+)").unwrap()).is_none());
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        unsafe {
+            // `rb_obj_is_method` is also true for `UnboundMethod`, as they
+            // share a data type
+            (Value::new(rb_obj_is_method(val.as_rb_value())).to_bool()
+                && val.is_kind_of(Ruby::get_with(val).class_method()))
+            .then(|| Self(RTypedData::from_rb_value_unchecked(val.as_rb_value())))
+        }
+    }
+
+    /// Call the method with `args`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     let res: i64 = method.call((2,))?;
+    ///     assert_eq!(res, 3);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn call<A, T>(self, args: A) -> Result<T, Error>
+    where
+        A: ArgList,
+        T: TryConvert,
+    {
+        let kw_splat = kw_splat(&args);
+        let args = args.into_arg_list_with(&Ruby::get_with(self));
+        let slice = args.as_ref();
+        unsafe {
+            protect(|| {
+                Value::new(rb_method_call_kw(
+                    slice.len() as c_int,
+                    slice.as_ptr() as *const VALUE,
+                    self.as_rb_value(),
+                    kw_splat as c_int,
+                ))
+            })
+            .and_then(TryConvert::try_convert)
+        }
+    }
+
+    /// Returns the receiver `self` is bound to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     assert_eq!(method.receiver::<i64>()?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn receiver<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.funcall("receiver", ())
+    }
+
+    /// Returns `self` detached from its receiver.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     let unbound = method.unbind()?;
+    ///     let res: i64 = unbound.bind(2)?.call((3,))?;
+    ///     assert_eq!(res, 5);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn unbind(self) -> Result<UnboundMethod, Error> {
+        self.funcall("unbind", ())
+    }
+
+    /// Returns the class or module that defines the method, converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RClass, RMethod, RModule, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     assert!(method.owner::<RClass>()?.equal(ruby.class_integer())?);
+    ///
+    ///     let method: RMethod = ruby.eval("1.method(:puts)")?;
+    ///     assert!(method.owner::<RModule>()?.equal(ruby.module_kernel())?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn owner<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.funcall("owner", ())
+    }
+
+    /// Returns the number of arguments the method takes.
+    ///
+    /// If the method takes a fixed number of arguments, returns that number.
+    /// If the method takes optional or splat arguments, returns `-n-1`, where
+    /// `n` is the number of required arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     assert_eq!(method.arity()?, 1);
+    ///
+    ///     let method: RMethod = ruby.eval("1.method(:puts)")?;
+    ///     assert_eq!(method.arity()?, -1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn arity(self) -> Result<i64, Error> {
+        self.funcall("arity", ())
+    }
+
+    /// Returns the name of the method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     assert_eq!(method.name()?.name()?, "+");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn name(self) -> Result<Symbol, Error> {
+        self.funcall("name", ())
+    }
+
+    /// Returns a lambda [`Proc`] calling the method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RMethod, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let method: RMethod = ruby.eval("1.method(:+)")?;
+    ///     let proc = method.to_proc()?;
+    ///     assert!(proc.is_lambda());
+    ///     assert_eq!(proc.call::<_, i64>((2,))?, 3);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_proc(self) -> Result<Proc, Error> {
+        self.funcall("to_proc", ())
+    }
+}
+
+impl fmt::Display for RMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for RMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for RMethod {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.as_value()
+    }
+}
+
+impl Object for RMethod {}
+
+unsafe impl private::ReprValue for RMethod {}
+
+impl ReprValue for RMethod {}
+
+impl TryConvert for RMethod {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into Method", unsafe {
+                    val.classname()
+                },),
+            )
+        })
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's `UnboundMethod`
+/// class.
+///
+/// An `UnboundMethod` is a method not bound to a receiver, as returned by
+/// Ruby's `Module#instance_method`. It must be bound to a receiver with
+/// [`bind`](UnboundMethod::bind) to be called.
+///
+/// See [`RMethod`] for guidance on storing this type.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct UnboundMethod(RTypedData);
+
+impl UnboundMethod {
+    /// Return `Some(UnboundMethod)` if `val` is an `UnboundMethod`, `None`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, UnboundMethod};
+    /// # let _cleanup = unsafe { magnus::embed::init() };
+    ///
+    /// assert!(UnboundMethod::from_value(eval("Integer.instance_method(:+)").unwrap()).is_some());
+    /// assert!(UnboundMethod::from_value(eval("1.method(:+)").unwrap()).is_none());
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        RTypedData::from_value(val)
+            .filter(|_| val.is_kind_of(Ruby::get_with(val).class_unbound_method()))
+            .map(Self)
+    }
+
+    /// Bind `self` to `receiver`, returning a [`RMethod`].
+    ///
+    /// Errors if `receiver` is not an instance of the class or module that
+    /// defines the method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, UnboundMethod};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let unbound: UnboundMethod = ruby.eval("Integer.instance_method(:+)")?;
+    ///     let res: i64 = unbound.bind(1)?.call((2,))?;
+    ///     assert_eq!(res, 3);
+    ///
+    ///     assert!(unbound.bind("example").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn bind<T>(self, receiver: T) -> Result<RMethod, Error>
+    where
+        T: IntoValue,
+    {
+        self.funcall("bind", (receiver,))
+    }
+
+    /// Returns the class or module that defines the method, converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RClass, Ruby, UnboundMethod};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let unbound: UnboundMethod = ruby.eval("Integer.instance_method(:+)")?;
+    ///     assert!(unbound.owner::<RClass>()?.equal(ruby.class_integer())?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn owner<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.funcall("owner", ())
+    }
+
+    /// Returns the number of arguments the method takes.
+    ///
+    /// See [`RMethod::arity`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, UnboundMethod};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let unbound: UnboundMethod = ruby.eval("Integer.instance_method(:+)")?;
+    ///     assert_eq!(unbound.arity()?, 1);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn arity(self) -> Result<i64, Error> {
+        self.funcall("arity", ())
+    }
+
+    /// Returns the name of the method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, UnboundMethod};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let unbound: UnboundMethod = ruby.eval("Integer.instance_method(:+)")?;
+    ///     assert_eq!(unbound.name()?.name()?, "+");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn name(self) -> Result<Symbol, Error> {
+        self.funcall("name", ())
+    }
+}
+
+impl fmt::Display for UnboundMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for UnboundMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for UnboundMethod {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.as_value()
+    }
+}
+
+impl Object for UnboundMethod {}
+
+unsafe impl private::ReprValue for UnboundMethod {}
+
+impl ReprValue for UnboundMethod {}
+
+impl TryConvert for UnboundMethod {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into UnboundMethod", unsafe {
+                    val.classname()
+                },),
+            )
+        })
+    }
+}
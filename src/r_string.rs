@@ -3,7 +3,7 @@
 use std::{
     borrow::Cow,
     cmp::Ordering,
-    ffi::CString,
+    ffi::{CString, OsStr, OsString},
     fmt, io,
     iter::Iterator,
     mem::transmute,
@@ -15,21 +15,22 @@ use std::{
 #[cfg(ruby_gte_3_0)]
 use rb_sys::rb_str_to_interned_str;
 use rb_sys::{
-    self, rb_enc_str_coderange, rb_enc_str_new, rb_str_buf_append, rb_str_buf_new, rb_str_capacity,
-    rb_str_cat, rb_str_cmp, rb_str_comparable, rb_str_conv_enc, rb_str_drop_bytes, rb_str_dump,
-    rb_str_ellipsize, rb_str_new, rb_str_new_frozen, rb_str_new_shared, rb_str_offset, rb_str_plus,
-    rb_str_replace, rb_str_scrub, rb_str_shared_replace, rb_str_split, rb_str_strlen, rb_str_times,
-    rb_str_to_str, rb_str_update, rb_utf8_str_new, rb_utf8_str_new_static, ruby_coderange_type,
-    ruby_rstring_flags, ruby_value_type, RSTRING_LEN, RSTRING_PTR, VALUE,
+    self, rb_enc_str_buf_cat, rb_enc_str_coderange, rb_enc_str_new, rb_str_append,
+    rb_str_buf_append, rb_str_buf_new, rb_str_capacity, rb_str_cat, rb_str_cmp, rb_str_comparable,
+    rb_str_conv_enc, rb_str_drop_bytes, rb_str_dump, rb_str_ellipsize, rb_str_locktmp, rb_str_new,
+    rb_str_new_frozen, rb_str_new_shared, rb_str_offset, rb_str_plus, rb_str_replace,
+    rb_str_resize, rb_str_scrub, rb_str_shared_replace, rb_str_split, rb_str_strlen, rb_str_subseq,
+    rb_str_times, rb_str_unlocktmp, rb_str_update, rb_utf8_str_new, rb_utf8_str_new_static,
+    ruby_coderange_type, ruby_rstring_flags, ruby_value_type, RSTRING_LEN, RSTRING_PTR, VALUE,
 };
 
 use crate::{
-    encoding::{Coderange, EncodingCapable, RbEncoding},
+    encoding::{Coderange, EncodingCapable, Index, RbEncoding},
     error::{protect, Error},
     into_value::{IntoValue, IntoValueFromNative},
     object::Object,
     r_array::RArray,
-    try_convert::TryConvert,
+    try_convert::{to_str, TryConvert},
     value::{
         private::{self, ReprValue as _},
         NonZeroValue, ReprValue, Value,
@@ -230,6 +231,59 @@ impl Ruby {
         self.str_new(c.encode_utf8(&mut buf[..]))
     }
 
+    /// Create a new Ruby string from the UTF-16 code units `s`.
+    ///
+    /// The encoding of the Ruby string will be UTF-8. Errors if `s` is not
+    /// valid UTF-16, e.g. contains unpaired surrogates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let wide = "café 🦀".encode_utf16().collect::<Vec<_>>();
+    ///     let s = ruby.str_from_utf16(&wide)?;
+    ///     rb_assert!(ruby, r#"s == "café 🦀""#, s);
+    ///
+    ///     assert!(ruby.str_from_utf16(&[0xd800]).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn str_from_utf16(&self, s: &[u16]) -> Result<RString, Error> {
+        let bytes = s.iter().flat_map(|c| c.to_le_bytes()).collect::<Vec<_>>();
+        self.enc_str_new(bytes, self.find_encindex("UTF-16LE")?)
+            .conv_enc_strict(self.utf8_encindex())
+    }
+
+    /// Create a new Ruby string from the UTF-32 code units `s`.
+    ///
+    /// The encoding of the Ruby string will be UTF-8. Errors if `s` is not
+    /// valid UTF-32, e.g. contains values that are not Unicode scalar values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_from_utf32(&[0x63, 0x61, 0x66, 0xe9, 0x1f980])?;
+    ///     rb_assert!(ruby, r#"s == "café🦀""#, s);
+    ///
+    ///     assert!(ruby.str_from_utf32(&[0x110000]).is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn str_from_utf32(&self, s: &[u32]) -> Result<RString, Error> {
+        let bytes = s.iter().flat_map(|c| c.to_le_bytes()).collect::<Vec<_>>();
+        self.enc_str_new(bytes, self.find_encindex("UTF-32LE")?)
+            .conv_enc_strict(self.utf8_encindex())
+    }
+
     /// Create a new Ruby string containing the codepoint `code` in the
     /// encoding `enc`.
     ///
@@ -643,6 +697,125 @@ impl RString {
         self.as_slice_unconstrained()
     }
 
+    /// Return `self` as bytes, borrowed if `self` is frozen, or copied
+    /// otherwise.
+    ///
+    /// A frozen string can't be modified, so for read-only processing of
+    /// frozen strings this avoids copying the bytes, while still returning a
+    /// copy for strings that could change.
+    ///
+    /// # Safety
+    ///
+    /// When `self` is frozen this is directly viewing memory owned and
+    /// managed by Ruby. Ruby code can't modify a frozen string, but C code
+    /// can ignore the frozen flag, and Ruby may free or move the memory
+    /// backing the returned slice if `self` is garbage collected or
+    /// compacted. The caller must ensure this does not happen.
+    ///
+    /// Ruby must not be allowed to garbage collect `self` while a refrence to
+    /// the slice is held.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("example");
+    ///     // safe as we don't give Ruby the chance to mess with the string while
+    ///     // we hold a refrence to the slice.
+    ///     unsafe {
+    ///         assert!(matches!(s.to_cow_bytes(), Cow::Owned(_)));
+    ///         s.freeze();
+    ///         assert!(matches!(s.to_cow_bytes(), Cow::Borrowed(b"example")));
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn to_cow_bytes(&self) -> Cow<'_, [u8]> {
+        if self.is_frozen() {
+            Cow::Borrowed(self.as_slice())
+        } else {
+            Cow::Owned(self.as_slice().to_vec())
+        }
+    }
+
+    /// Call `func` with `self`'s bytes, without copying.
+    ///
+    /// `self` is temporarily locked while `func` runs, so any attempt to
+    /// modify it, from Ruby or Rust, will raise a `RuntimeError`. This makes
+    /// it safe to view the memory backing `self`, even if `func` calls Ruby.
+    ///
+    /// Works with strings of any encoding, including binary strings
+    /// containing NUL bytes.
+    ///
+    /// Errors with a `RuntimeError` if `self` is already locked, e.g. by
+    /// an outer call to `with_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_from_slice(&[0, 255, 0, 1]);
+    ///     let sum = s.with_bytes(|bytes| bytes.iter().map(|b| *b as u64).sum::<u64>())?;
+    ///     assert_eq!(sum, 256);
+    ///
+    ///     // modification while locked is an error
+    ///     let res = s.with_bytes(|_| s.push_str("more"))?;
+    ///     assert!(res.is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn with_bytes<F, T>(self, func: F) -> Result<T, Error>
+    where
+        F: FnOnce(&[u8]) -> T,
+    {
+        struct Unlock(RString);
+
+        impl Drop for Unlock {
+            fn drop(&mut self) {
+                unsafe { rb_str_unlocktmp(self.0.as_rb_value()) };
+            }
+        }
+
+        protect(|| unsafe { Value::new(rb_str_locktmp(self.as_rb_value())) })?;
+        let _unlock = Unlock(self);
+        // Ruby won't modify the string while it's locked, and self is on the
+        // stack so won't be garbage collected.
+        Ok(func(unsafe { self.as_slice() }))
+    }
+
+    /// Return `self`'s bytes as an owned `Vec<u8>`.
+    ///
+    /// Unlike [`to_string`](RString::to_string) this works with strings of
+    /// any encoding, including binary strings containing NUL bytes, and
+    /// doesn't require the string to be valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_from_slice(&[0, 159, 146, 150]);
+    ///     assert_eq!(s.to_vec(), vec![0, 159, 146, 150]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_vec(self) -> Vec<u8> {
+        unsafe { self.as_slice().to_vec() }
+    }
+
     unsafe fn as_slice_unconstrained<'a>(self) -> &'a [u8] {
         debug_assert_value!(self);
         slice::from_raw_parts(
@@ -822,6 +995,27 @@ impl RString {
         })
     }
 
+    /// Like [`conv_enc`](RString::conv_enc), but errors rather than returning
+    /// `self` unchanged if the string can't be converted.
+    fn conv_enc_strict(self, enc: Index) -> Result<Self, Error> {
+        let handle = Ruby::get_with(self);
+        if self.is_empty() {
+            return Ok(handle.enc_str_new(b"", enc));
+        }
+        let converted = self.conv_enc(enc)?;
+        if converted.enc_get() == enc {
+            return Ok(converted);
+        }
+        Err(Error::new(
+            handle.exception_encoding_error(),
+            format!(
+                "can't convert string from {} to {}",
+                RbEncoding::from(self.enc_get()).name(),
+                RbEncoding::from(enc).name()
+            ),
+        ))
+    }
+
     /// Returns a string omitting 'broken' parts of the string according to its
     /// encoding.
     ///
@@ -1175,6 +1369,109 @@ impl RString {
             .map_err(|e| Error::new(handle.exception_encoding_error(), format!("{}", e)))
     }
 
+    /// Returns `self` as UTF-16 code units, e.g. for passing to wide-char
+    /// APIs. The Ruby string will be reencoded as required. Errors if the
+    /// string can not be encoded as UTF-16.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café 🦀");
+    ///     assert_eq!(s.to_utf16()?, "café 🦀".encode_utf16().collect::<Vec<_>>());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_utf16(self) -> Result<Vec<u16>, Error> {
+        let handle = Ruby::get_with(self);
+        let utf16 = self.conv_enc_strict(handle.find_encindex("UTF-16LE")?)?;
+        let bytes = unsafe { utf16.as_slice() };
+        Ok(bytes
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect())
+    }
+
+    /// Returns `self` as UTF-32 code units. The Ruby string will be reencoded
+    /// as required. Errors if the string can not be encoded as UTF-32.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café🦀");
+    ///     assert_eq!(s.to_utf32()?, [0x63, 0x61, 0x66, 0xe9, 0x1f980]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_utf32(self) -> Result<Vec<u32>, Error> {
+        let handle = Ruby::get_with(self);
+        let utf32 = self.conv_enc_strict(handle.find_encindex("UTF-32LE")?)?;
+        let bytes = unsafe { utf32.as_slice() };
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+
+    /// Returns `self` as an owned [`OsString`].
+    ///
+    /// On Unix platforms the string is first transcoded to the filesystem
+    /// encoding, as Ruby does when passing a path to the OS, and the bytes
+    /// of that used as-is. A string that can't be transcoded, or is binary
+    /// (ASCII-8BIT), is used unchanged. On Windows the string is reencoded as
+    /// UTF-16, and elsewhere as UTF-8, erroring if that is not possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ffi::OsString;
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("example");
+    ///     assert_eq!(s.to_os_string()?, OsString::from("example"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn to_os_string(self) -> Result<OsString, Error> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStringExt;
+
+            use rb_sys::rb_str_export_to_enc;
+
+            let enc = Ruby::get_with(self).filesystem_encoding();
+            let s = protect(|| unsafe {
+                Self::from_rb_value_unchecked(rb_str_export_to_enc(
+                    self.as_rb_value(),
+                    enc.as_ptr(),
+                ))
+            })?;
+            Ok(OsString::from_vec(unsafe { s.as_slice().to_vec() }))
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStringExt;
+            Ok(OsString::from_wide(&self.to_utf16()?))
+        }
+        #[cfg(not(any(unix, windows)))]
+        {
+            self.to_string().map(Into::into)
+        }
+    }
+
     /// Returns `self` as an owned Rust `Bytes`.
     ///
     /// # Examples
@@ -1602,6 +1899,145 @@ impl RString {
         Ok(())
     }
 
+    /// Mutate `self`, adding `s` to the end.
+    ///
+    /// Unlike [`cat`](RString::cat) this takes in to account the encodings of
+    /// `self` and `s`, erroring if they are not compatible. Errors if `self`
+    /// is frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("foo");
+    ///     s.push_str("bar")?;
+    ///     assert_eq!(s.to_string()?, "foobar");
+    ///
+    ///     s.freeze();
+    ///     assert!(s.push_str("baz").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn push_str(self, s: &str) -> Result<(), Error> {
+        let enc = Ruby::get_with(self).utf8_encoding();
+        protect(|| unsafe {
+            Value::new(rb_enc_str_buf_cat(
+                self.as_rb_value(),
+                s.as_ptr() as *const c_char,
+                s.len() as c_long,
+                enc.as_ptr(),
+            ))
+        })?;
+        Ok(())
+    }
+
+    /// Mutate `self`, adding `other` to the end, as with Ruby's `String#<<`.
+    ///
+    /// Errors if `self` is frozen, or `self` and `other`'s encodings are not
+    /// compatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("foo");
+    ///     s.push_value(ruby.str_new("bar"))?;
+    ///     assert_eq!(s.to_string()?, "foobar");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn push_value(self, other: Self) -> Result<(), Error> {
+        protect(|| unsafe { Value::new(rb_str_append(self.as_rb_value(), other.as_rb_value())) })?;
+        Ok(())
+    }
+
+    /// Truncate `self` to an empty string.
+    ///
+    /// Errors if `self` is frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("foo");
+    ///     s.clear()?;
+    ///     assert!(s.is_empty());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn clear(self) -> Result<(), Error> {
+        protect(|| unsafe { Value::new(rb_str_resize(self.as_rb_value(), 0)) })?;
+        Ok(())
+    }
+
+    /// Replace the contents of `self` with `s`.
+    ///
+    /// `self`'s encoding will be set to UTF-8. See also
+    /// [`replace`](RString::replace). Errors if `self` is frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("foo");
+    ///     s.replace_str("bar")?;
+    ///     assert_eq!(s.to_string()?, "bar");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn replace_str(self, s: &str) -> Result<(), Error> {
+        self.replace(Ruby::get_with(self).str_new(s))
+    }
+
+    /// Insert `s` before the character at `index`, as with Ruby's
+    /// `String#insert`.
+    ///
+    /// `index` is measured in characters, not bytes. Negative values count
+    /// back from the end of the string, with `-1` inserting after the last
+    /// character. Errors if `index` is out of bounds, or `self` is frozen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("cafe");
+    ///     s.insert(0, "🦀 ")?;
+    ///     s.insert(-1, "!")?;
+    ///     s.insert(-2, "s")?;
+    ///     assert_eq!(s.to_string()?, "🦀 cafes!");
+    ///
+    ///     assert!(s.insert(20, "x").is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn insert(self, index: isize, s: &str) -> Result<(), Error> {
+        if index == -1 {
+            return self.push_str(s);
+        }
+        let index = if index < 0 { index + 1 } else { index };
+        self.update(index, 0, Ruby::get_with(self).str_new(s))
+    }
+
     /// Returns the number of bytes in `self`.
     ///
     /// See also [`length`](RString::length).
@@ -1728,6 +2164,229 @@ impl RString {
         unsafe { rb_str_comparable(self.as_rb_value(), other.as_rb_value()) != 0 }
     }
 
+    /// Case-insensitive comparison of `self` and `other`, as with Ruby's
+    /// `String#casecmp`.
+    ///
+    /// Only ASCII characters are case folded. Returns `None` if the
+    /// encodings of `self` and `other` are not compatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.str_new("abc");
+    ///     assert_eq!(a.casecmp(ruby.str_new("ABC"))?, Some(Ordering::Equal));
+    ///     assert_eq!(a.casecmp(ruby.str_new("ABD"))?, Some(Ordering::Less));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn casecmp(self, other: Self) -> Result<Option<Ordering>, Error> {
+        self.funcall::<_, _, Option<i64>>("casecmp", (other,))
+            .map(|o| o.map(|i| i.cmp(&0)))
+    }
+
+    /// Returns whether `self` and `other` are equal after Unicode case
+    /// folding, as with Ruby's `String#casecmp?`.
+    ///
+    /// Returns `None` if the encodings of `self` and `other` are not
+    /// compatible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.str_new("äbc");
+    ///     assert_eq!(a.casecmp_eq(ruby.str_new("ÄBC"))?, Some(true));
+    ///     assert_eq!(a.casecmp_eq(ruby.str_new("abc"))?, Some(false));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn casecmp_eq(self, other: Self) -> Result<Option<bool>, Error> {
+        self.funcall("casecmp?", (other,))
+    }
+
+    /// Returns whether `self` has the same content as the Rust string `s`.
+    ///
+    /// This compares bytes directly, without allocating a Ruby string for
+    /// `s`. As with Ruby's string equality, a string in an encoding other
+    /// than UTF-8 will compare equal if both it and `s` are entirely ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Error, RString, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.str_new("foo").eql_str("foo"));
+    ///     assert!(!ruby.str_new("foo").eql_str("Foo"));
+    ///
+    ///     let s: RString = eval!(ruby, r#""foo".b"#)?;
+    ///     assert!(s.eql_str("foo"));
+    ///     let s: RString = eval!(ruby, r#""café".b"#)?;
+    ///     assert!(!s.eql_str("café"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn eql_str(self, s: &str) -> bool {
+        // safe as we don't give Ruby the chance to mess with the string while
+        // we hold a reference to the slice
+        if unsafe { self.as_slice() } != s.as_bytes() {
+            return false;
+        }
+        self.is_utf8_compatible_encoding()
+            || (s.is_ascii() && self.enc_coderange_scan() == Coderange::SevenBit)
+    }
+
+    /// Returns whether `self` starts with the Rust string `s`, as with Ruby's
+    /// `String#start_with?`.
+    ///
+    /// When `self` has a UTF-8 compatible encoding, or both `self` and `s` are
+    /// entirely ASCII, this compares bytes directly without calling Ruby or
+    /// allocating a Ruby string for `s`. Returns `false` if `s` can't be
+    /// compared with `self` due to incompatible encodings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{eval, Error, RString, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café au lait");
+    ///     assert!(s.starts_with("café"));
+    ///     assert!(!s.starts_with("lait"));
+    ///
+    ///     let s: RString = eval!(ruby, r#""café".encode("ISO-8859-1")"#)?;
+    ///     assert!(s.starts_with("caf"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn starts_with(self, s: &str) -> bool {
+        if self.can_compare_bytes(s) {
+            // safe as we don't give Ruby the chance to mess with the string
+            // while we hold a reference to the slice
+            return unsafe { self.as_slice() }.starts_with(s.as_bytes());
+        }
+        self.funcall("start_with?", (s,)).unwrap_or(false)
+    }
+
+    /// Returns whether `self` ends with the Rust string `s`, as with Ruby's
+    /// `String#end_with?`.
+    ///
+    /// See [`starts_with`](RString::starts_with) for details of when this
+    /// avoids calling Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café au lait");
+    ///     assert!(s.ends_with("lait"));
+    ///     assert!(!s.ends_with("café"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn ends_with(self, s: &str) -> bool {
+        if self.can_compare_bytes(s) {
+            // safe as we don't give Ruby the chance to mess with the string
+            // while we hold a reference to the slice
+            return unsafe { self.as_slice() }.ends_with(s.as_bytes());
+        }
+        self.funcall("end_with?", (s,)).unwrap_or(false)
+    }
+
+    /// Returns whether `self` contains the Rust string `s`, as with Ruby's
+    /// `String#include?`.
+    ///
+    /// See [`starts_with`](RString::starts_with) for details of when this
+    /// avoids calling Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café au lait");
+    ///     assert!(s.contains("au"));
+    ///     assert!(!s.contains("the"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn contains(self, s: &str) -> bool {
+        self.index_of(s).is_some()
+    }
+
+    /// Returns the character index of the first occurrence of the Rust string
+    /// `s` in `self`, as with Ruby's `String#index`.
+    ///
+    /// See [`starts_with`](RString::starts_with) for details of when this
+    /// avoids calling Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("café au lait");
+    ///     assert_eq!(s.index_of("au"), Some(5));
+    ///     assert_eq!(s.index_of("the"), None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn index_of(self, s: &str) -> Option<usize> {
+        if self.can_compare_bytes(s) {
+            // safe as we don't give Ruby the chance to mess with the string
+            // while we hold a reference to the slice
+            let haystack = unsafe { self.as_slice() };
+            let needle = s.as_bytes();
+            if needle.is_empty() {
+                return Some(0);
+            }
+            return haystack
+                .windows(needle.len())
+                .position(|w| w == needle)
+                // count the chars before the match, i.e. the bytes that
+                // aren't UTF-8 continuation bytes
+                .map(|pos| {
+                    haystack[..pos]
+                        .iter()
+                        .filter(|b| **b & 0xc0 != 0x80)
+                        .count()
+                });
+        }
+        self.funcall("index", (s,)).unwrap_or(None)
+    }
+
+    /// Whether `self` and `s` can be compared byte-for-byte, with the results
+    /// matching Ruby's.
+    fn can_compare_bytes(self, s: &str) -> bool {
+        self.is_utf8_compatible_encoding()
+            || (s.is_ascii() && self.enc_coderange_scan() == Coderange::SevenBit)
+    }
+
     /// Shorten `self` to `len`, adding "...".
     ///
     /// If `self` is shorter than `len` the returned value will be `self`.
@@ -1786,6 +2445,130 @@ impl RString {
         let delim = CString::new(delim).unwrap();
         unsafe { RArray::from_rb_value_unchecked(rb_str_split(self.as_rb_value(), delim.as_ptr())) }
     }
+
+    /// Split `self` around `pattern`, as with Ruby's `String#split`.
+    ///
+    /// `pattern` may be a String, a Regexp, or `nil` (to split around
+    /// whitespace, or `$;` if set). Unlike [`split`](RString::split), this
+    /// supports Regexps and respects `self`'s encoding when `pattern` is a
+    /// String.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("a1b22c333d");
+    ///     let parts = s.split_pattern(ruby.reg_new(r"\d+", Default::default())?)?;
+    ///     assert_eq!(Vec::<String>::try_convert(parts.as_value())?, vec!["a", "b", "c", "d"]);
+    ///
+    ///     let s = ruby.str_new("café,crème");
+    ///     let parts = s.split_pattern(",")?;
+    ///     assert_eq!(Vec::<String>::try_convert(parts.as_value())?, vec!["café", "crème"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn split_pattern<T>(self, pattern: T) -> Result<RArray, Error>
+    where
+        T: IntoValue,
+    {
+        self.funcall("split", (pattern,))
+    }
+
+    /// Return an iterator over `self`'s characters, each as a new `RString`.
+    ///
+    /// Characters are determined by `self`'s encoding, as with Ruby's
+    /// `String#each_char`, with each invalid byte yielded as a separate
+    /// character. The returned strings have the same encoding as `self`.
+    ///
+    /// Unlike [`char_bytes`](RString::char_bytes) this is safe, as `self` is
+    /// re-read for each character. If `self` is modified during iteration the
+    /// iterator continues from the same byte offset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("🦀 café");
+    ///     let chars = s
+    ///         .each_char()
+    ///         .map(|c| c.to_string())
+    ///         .collect::<Result<Vec<_>, Error>>()?;
+    ///     assert_eq!(chars, vec!["🦀", " ", "c", "a", "f", "é"]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn each_char(self) -> Chars {
+        Chars {
+            string: self,
+            pos: 0,
+        }
+    }
+
+    /// Return an Array of `self`'s extended grapheme clusters, as with Ruby's
+    /// `String#grapheme_clusters`.
+    ///
+    /// A grapheme cluster is a user-perceived character, which may be made
+    /// up of multiple codepoints, such as an emoji with a skin tone modifier,
+    /// or a letter followed by a combining accent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("e\u{301}👍🏽!");
+    ///     let graphemes = s.each_grapheme_cluster()?;
+    ///     assert_eq!(
+    ///         Vec::<String>::try_convert(graphemes.as_value())?,
+    ///         vec!["e\u{301}", "👍🏽", "!"]
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn each_grapheme_cluster(self) -> Result<RArray, Error> {
+        self.funcall("grapheme_clusters", ())
+    }
+
+    /// Return a new string with the characters of `self` in reverse order.
+    ///
+    /// Characters are determined by `self`'s encoding, as with Ruby's
+    /// `String#reverse`, so multibyte characters are kept intact. The new
+    /// string has the same encoding as `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("🦀 café");
+    ///     assert_eq!(s.reverse().to_string()?, "éfac 🦀");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn reverse(self) -> Self {
+        let mut reversed = Vec::with_capacity(self.len());
+        // no Ruby calls while the slice is live, so it can't be modified
+        unsafe {
+            for c in self.char_bytes().collect::<Vec<_>>().into_iter().rev() {
+                reversed.extend_from_slice(c);
+            }
+        }
+        Ruby::get_with(self).enc_str_new(reversed, self.enc_get())
+    }
 }
 
 impl fmt::Display for RString {
@@ -1901,6 +2684,18 @@ impl IntoRString for PathBuf {
     }
 }
 
+impl IntoRString for &OsStr {
+    fn into_r_string_with(self, handle: &Ruby) -> RString {
+        Path::new(self).into_r_string_with(handle)
+    }
+}
+
+impl IntoRString for OsString {
+    fn into_r_string_with(self, handle: &Ruby) -> RString {
+        self.as_os_str().into_r_string_with(handle)
+    }
+}
+
 impl IntoValue for RString {
     #[inline]
     fn into_value_with(self, _: &Ruby) -> Value {
@@ -1925,6 +2720,33 @@ impl IntoValue for bytes::Bytes {
     }
 }
 
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+impl IntoValue for uuid::Uuid {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        let mut buf = uuid::Uuid::encode_buffer();
+        handle
+            .str_new(self.hyphenated().encode_lower(&mut buf))
+            .into_value_with(handle)
+    }
+}
+
+#[cfg(feature = "uuid")]
+unsafe impl IntoValueFromNative for uuid::Uuid {}
+
+#[cfg(feature = "url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl IntoValue for url::Url {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        handle.str_new(self.as_str()).into_value_with(handle)
+    }
+}
+
+#[cfg(feature = "url")]
+unsafe impl IntoValueFromNative for url::Url {}
+
 impl IntoValue for String {
     #[inline]
     fn into_value_with(self, handle: &Ruby) -> Value {
@@ -1963,6 +2785,26 @@ impl IntoValue for PathBuf {
 
 unsafe impl IntoValueFromNative for PathBuf {}
 
+impl IntoValue for &OsStr {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        self.into_r_string_with(handle).into_value_with(handle)
+    }
+}
+
+unsafe impl IntoValueFromNative for &OsStr {}
+
+impl IntoValue for OsString {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        self.as_os_str()
+            .into_r_string_with(handle)
+            .into_value_with(handle)
+    }
+}
+
+unsafe impl IntoValueFromNative for OsString {}
+
 impl Object for RString {}
 
 unsafe impl private::ReprValue for RString {}
@@ -1971,13 +2813,15 @@ impl ReprValue for RString {}
 
 impl TryConvert for RString {
     fn try_convert(val: Value) -> Result<Self, Error> {
-        match Self::from_value(val) {
-            Some(i) => Ok(i),
-            None => protect(|| {
-                debug_assert_value!(val);
-                unsafe { Self::from_rb_value_unchecked(rb_str_to_str(val.as_rb_value())) }
-            }),
-        }
+        debug_assert_value!(val);
+        to_str(val)?.ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!("no implicit conversion of {} into String", unsafe {
+                    val.classname()
+                },),
+            )
+        })
     }
 }
 
@@ -2179,6 +3023,36 @@ impl<'a> Iterator for CharBytes<'a> {
     }
 }
 
+/// An iterator over a Ruby string's characters as new strings.
+///
+/// See [`RString::each_char`].
+pub struct Chars {
+    string: RString,
+    pos: usize,
+}
+
+impl Iterator for Chars {
+    type Item = RString;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slice = unsafe { self.string.as_slice() };
+        if self.pos >= slice.len() {
+            return None;
+        }
+        let encoding: RbEncoding = self.string.enc_get().into();
+        let len = encoding.mbclen(&slice[self.pos..]);
+        let c = unsafe {
+            RString::from_rb_value_unchecked(rb_str_subseq(
+                self.string.as_rb_value(),
+                self.pos as c_long,
+                len as c_long,
+            ))
+        };
+        self.pos += len;
+        Some(c)
+    }
+}
+
 /// Create a [`RString`] from a Rust str literal.
 ///
 /// # Panics
@@ -2,14 +2,9 @@
 //!
 //! See also [`Ruby`](Ruby#struct) for more Struct related methods.
 
-use std::{
-    borrow::Cow,
-    ffi::CString,
-    fmt,
-    os::raw::c_char,
-    ptr::{null, NonNull},
-    slice,
-};
+use std::{borrow::Cow, ffi::CString, fmt, os::raw::c_char, ptr::null};
+#[cfg(not(feature = "capi-strict"))]
+use std::{ptr::NonNull, slice};
 
 #[cfg(ruby_gte_3_3)]
 use rb_sys::rb_data_define;
@@ -35,6 +30,7 @@ use crate::{
 // contents, but we have to reimplement those for Rust. The for that we need
 // the definition of RStruct, but that isn't public, so we have to duplicate it
 // here.
+#[cfg(not(feature = "capi-strict"))]
 mod sys {
     #[cfg(ruby_lt_3_0)]
     use rb_sys::ruby_fl_type::RUBY_FL_USHIFT;
@@ -108,6 +104,7 @@ impl RStruct {
         Self(NonZeroValue::new_unchecked(Value::new(val)))
     }
 
+    #[cfg(not(feature = "capi-strict"))]
     fn as_internal(self) -> NonNull<sys::RStruct> {
         // safe as inner value is NonZero
         unsafe { NonNull::new_unchecked(self.0.get().as_rb_value() as *mut _) }
@@ -121,10 +118,12 @@ impl RStruct {
     ///
     /// Ruby may modify or free the memory backing the returned slice, the
     /// caller must ensure this does not happen.
+    #[cfg(not(feature = "capi-strict"))]
     unsafe fn as_slice(&self) -> &[Value] {
         self.as_slice_unconstrained()
     }
 
+    #[cfg(not(feature = "capi-strict"))]
     unsafe fn as_slice_unconstrained<'a>(self) -> &'a [Value] {
         debug_assert_value!(self);
         let r_basic = self.r_basic_unchecked();
@@ -163,6 +162,11 @@ impl RStruct {
     where
         T: TryConvert,
     {
+        // rb_struct_aref raises the same error as below when out of bounds
+        #[cfg(feature = "capi-strict")]
+        return self.aref(index);
+
+        #[cfg(not(feature = "capi-strict"))]
         unsafe {
             let slice = self.as_slice();
             slice
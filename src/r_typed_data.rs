@@ -1,6 +1,9 @@
-use std::{fmt, ptr::NonNull};
+use std::{ffi::c_void, fmt, ptr::NonNull};
 
-use rb_sys::{self, rb_check_typeddata, rb_data_typed_object_wrap, ruby_value_type, VALUE};
+use rb_sys::{
+    self, rb_check_typeddata, rb_data_typed_object_wrap, rb_typeddata_is_kind_of, ruby_value_type,
+    VALUE,
+};
 
 use crate::{
     class::RClass,
@@ -383,30 +386,152 @@ impl RTypedData {
     /// `'static`.
     #[inline]
     pub(crate) unsafe fn get_unconstrained<'a, T>(self) -> Result<&'a T, Error>
+    where
+        T: TypedData,
+    {
+        self.get_ptr().map(|ptr| &*ptr)
+    }
+
+    /// Get a mutable reference to the Rust type wrapped in the Ruby object
+    /// `self`.
+    ///
+    /// Errors if `self` does not wrap a `T`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure no other references to the wrapped data exist
+    /// for the lifetime of the returned reference. This includes references
+    /// obtained with [`get`](RTypedData::get), or from converting `self` to
+    /// `&T` as a method argument, in this or any other Ruby thread. The
+    /// lifetime of the returned reference is unconstrained, the caller must
+    /// also ensure it does not outlive the wrapped data, see
+    /// [`get_ptr`](RTypedData::get_ptr) for how long the data remains
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_class("Point", ruby.class_object())?;
+    ///     let value = ruby.wrap(Point { x: 4, y: 2 });
+    ///
+    ///     // safe as there are no other references to the wrapped Point
+    ///     unsafe { value.get_mut_unchecked::<Point>()?.x = 5 };
+    ///     assert_eq!(value.get::<Point>()?.x, 5);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[inline]
+    pub unsafe fn get_mut_unchecked<'a, T>(self) -> Result<&'a mut T, Error>
+    where
+        T: TypedData,
+    {
+        self.get_ptr().map(|ptr| &mut *ptr)
+    }
+
+    /// Get a raw pointer to the Rust type wrapped in the Ruby object `self`,
+    /// e.g. to pass to a C library as callback data.
+    ///
+    /// Errors if `self` does not wrap a `T`.
+    ///
+    /// Getting the pointer is safe, but dereferencing it is subject to the
+    /// following contract:
+    ///
+    /// * The wrapped data is allocated separately from the Ruby object and
+    ///   will not be moved by Ruby's garbage collector, so the pointer
+    ///   remains valid for as long as the Ruby object `self` is alive.
+    ///   The caller must keep `self` alive, e.g. by marking it or registering
+    ///   it with [`gc::register_mark_object`](crate::gc::register_mark_object),
+    ///   for as long as the pointer is in use. Once `self` is garbage
+    ///   collected the data is dropped and the pointer dangles.
+    /// * Ruby does not track references created from the pointer. The caller
+    ///   is responsible for upholding Rust's aliasing rules; a `&mut T` must
+    ///   not exist at the same time as any other reference to the data.
+    /// * The wrapped data may only be accessed from a Ruby thread, unless `T`
+    ///   is [`Sync`] (for shared access).
+    ///
+    /// See [`from_data_ptr`](RTypedData::from_data_ptr) to convert the
+    /// pointer back to a reference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, RTypedData, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_class("Point", ruby.class_object())?;
+    ///     let value = ruby.wrap(Point { x: 4, y: 2 });
+    ///
+    ///     let ptr = value.get_ptr::<Point>()?.cast();
+    ///     // ... pass `ptr` through a C callback ...
+    ///     // safe as `value` is still alive, and there are no `&mut Point`
+    ///     let point = unsafe { RTypedData::from_data_ptr::<Point>(ptr) };
+    ///     assert_eq!(point.x, 4);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get_ptr<T>(self) -> Result<*mut T, Error>
     where
         T: TypedData,
     {
         debug_assert_value!(self);
+        let data_type = T::data_type().as_rb_data_type() as *const _;
+        if unsafe { rb_typeddata_is_kind_of(self.as_rb_value(), data_type) } != 0 {
+            // can't raise, as we've checked the type
+            return Ok(unsafe { rb_check_typeddata(self.as_rb_value(), data_type) } as *mut T);
+        }
         let handle = Ruby::get_with(self);
-        let mut res = None;
-        let _ = protect(|| {
-            res = (rb_check_typeddata(
-                self.as_rb_value(),
-                T::data_type().as_rb_data_type() as *const _,
-            ) as *const T)
-                .as_ref();
+        // call again, protected, for Ruby's TypeError naming the actual and
+        // expected wrapped types
+        protect(|| unsafe {
+            rb_check_typeddata(self.as_rb_value(), data_type);
             handle.qnil()
-        });
-        res.ok_or_else(|| {
-            Error::new(
-                handle.exception_type_error(),
-                format!(
-                    "no implicit conversion of {} into {}",
-                    self.classname(),
-                    T::class(&handle)
-                ),
-            )
-        })
+        })?;
+        unreachable!("rb_check_typeddata returned for a value of the wrong type")
+    }
+
+    /// Convert a pointer obtained from [`get_ptr`](RTypedData::get_ptr)
+    /// back to a reference to the wrapped data.
+    ///
+    /// This accepts a `*mut c_void`, as is commonly used for callback data
+    /// in C APIs.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`get_ptr`](RTypedData::get_ptr)
+    /// with the same `T`, and the Ruby object it was obtained from must
+    /// still be alive. No `&mut T` to the data may exist for the lifetime of
+    /// the returned reference, which can be any lifetime needed, so the
+    /// caller must take care not to hold it past the life of the Ruby
+    /// object. See [`get_ptr`](RTypedData::get_ptr) for details.
+    ///
+    /// # Examples
+    ///
+    /// See [`get_ptr`](RTypedData::get_ptr).
+    #[inline]
+    pub unsafe fn from_data_ptr<'a, T>(ptr: *mut c_void) -> &'a T
+    where
+        T: TypedData,
+    {
+        &*(ptr as *const T)
     }
 }
 
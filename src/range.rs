@@ -2,13 +2,16 @@
 
 use std::{
     fmt,
-    ops::{Range as StdRange, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive},
+    ops::{
+        Bound, Range as StdRange, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+    },
     os::raw::{c_int, c_long},
 };
 
 use rb_sys::{rb_range_beg_len, rb_range_new};
 
 use crate::{
+    enumerator::Enumerator,
     error::{protect, Error},
     into_value::{IntoValue, IntoValueFromNative},
     object::Object,
@@ -293,6 +296,158 @@ impl Range {
         let (beg, len) = self.beg_len(length)?;
         Ok(beg..(beg + len))
     }
+
+    /// Returns the start and end bounds of the range, converting the values
+    /// to `T`.
+    ///
+    /// Unlike [`to_range_with_len`](Range::to_range_with_len) this is not
+    /// limited to ranges of Integers, so can be used with ranges of Floats,
+    /// or of Ruby objects such as `Date`s by converting to [`Value`].
+    /// Beginless and endless ranges have an [`Bound::Unbounded`] start or
+    /// end.
+    ///
+    /// Errors if the conversion fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::ops::Bound;
+    ///
+    /// use magnus::{Error, Range, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let range: Range = ruby.eval("1.5...2.5")?;
+    ///     assert_eq!(
+    ///         range.bounds::<f64>()?,
+    ///         (Bound::Included(1.5), Bound::Excluded(2.5))
+    ///     );
+    ///
+    ///     let range: Range = ruby.eval("..2.5")?;
+    ///     assert_eq!(
+    ///         range.bounds::<f64>()?,
+    ///         (Bound::Unbounded, Bound::Included(2.5))
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn bounds<T>(self) -> Result<(Bound<T>, Bound<T>), Error>
+    where
+        T: TryConvert,
+    {
+        let beg = match self.beg::<Option<Value>>()? {
+            Some(v) => Bound::Included(T::try_convert(v)?),
+            None => Bound::Unbounded,
+        };
+        let end = match self.end::<Option<Value>>()? {
+            Some(v) if self.excl() => Bound::Excluded(T::try_convert(v)?),
+            Some(v) => Bound::Included(T::try_convert(v)?),
+            None => Bound::Unbounded,
+        };
+        Ok((beg, end))
+    }
+
+    /// Returns whether `val` is between the beginning and end of the range.
+    ///
+    /// This uses Ruby's `Range#cover?`, comparing `val` with the ends of the
+    /// range, rather than iterating over the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Range, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let range: Range = ruby.eval("1.0...2.0")?;
+    ///     assert!(range.cover(1.5)?);
+    ///     assert!(range.cover(1)?);
+    ///     assert!(!range.cover(2.0)?);
+    ///
+    ///     let range: Range = ruby.eval(r#""a".."z""#)?;
+    ///     assert!(range.cover("bb")?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn cover<T>(self, val: T) -> Result<bool, Error>
+    where
+        T: IntoValue,
+    {
+        self.funcall("cover?", (val,))
+    }
+
+    /// Returns an [`Enumerator`] over every `n`th value of the range.
+    ///
+    /// For numeric ranges `n` is added to the beginning of the range until
+    /// the end is reached, so it may be a Float. Other ranges step by `n`
+    /// calls to `succ`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Range, Ruby, TryConvert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let range: Range = ruby.eval("1..10")?;
+    ///     let res = range
+    ///         .step(3)?
+    ///         .map(|v| v.and_then(i64::try_convert))
+    ///         .collect::<Result<Vec<_>, Error>>()?;
+    ///     assert_eq!(res, [1, 4, 7, 10]);
+    ///
+    ///     let range: Range = ruby.eval("0.0..1.0")?;
+    ///     let res = range
+    ///         .step(0.25)?
+    ///         .map(|v| v.and_then(f64::try_convert))
+    ///         .collect::<Result<Vec<_>, Error>>()?;
+    ///     assert_eq!(res, [0.0, 0.25, 0.5, 0.75, 1.0]);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn step<T>(self, n: T) -> Result<Enumerator, Error>
+    where
+        T: IntoValue,
+    {
+        self.funcall("step", (n,))
+    }
+
+    /// Returns the number of elements in the range, converted to `T`.
+    ///
+    /// This is Ruby's `Range#size`, which returns `nil` for ranges that can
+    /// not be iterated, such as those of Strings or beginning with a Float on
+    /// Ruby 3.3 and later, and `Float::INFINITY` for endless ranges.
+    /// Converting to `Option<usize>` handles the first case, but the
+    /// conversion will error for infinite ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Range, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let range: Range = ruby.eval("2..7")?;
+    ///     assert_eq!(range.size::<Option<usize>>()?, Some(6));
+    ///
+    ///     let range: Range = ruby.eval(r#""a".."z""#)?;
+    ///     assert_eq!(range.size::<Option<usize>>()?, None);
+    ///
+    ///     let range: Range = ruby.eval("1..")?;
+    ///     assert!(range.size::<f64>()?.is_infinite());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn size<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.funcall("size", ())
+    }
 }
 
 impl fmt::Display for Range {
@@ -182,6 +182,13 @@ impl FromRawId for Id {
 /// All functions exposed by Magnus that call Ruby in a way that may unwind
 /// already use this internally, this should only be required to wrap functions
 /// from [rb-sys](rb_sys).
+///
+/// The closure is passed to Ruby by pointer, so no allocation is required.
+/// When making many calls to functions from [rb-sys](rb_sys), the overhead
+/// of setting up the protected call can be reduced by making several calls
+/// within a single closure, see also [`protect_returning`] and
+/// [`protect_batch`]. For calling a single function that takes and returns a
+/// [`VALUE`] see [`protect_fn`].
 pub fn protect<F>(func: F) -> Result<VALUE, Error>
 where
     F: FnOnce() -> VALUE,
@@ -189,6 +196,132 @@ where
     error::protect(|| Value::new(func())).map(|v| v.as_rb_value())
 }
 
+/// Calls the given closure, catching all cases of unwinding from Ruby
+/// returning them as an [`Error`].
+///
+/// This is the same as [`protect`], but the closure can return any type,
+/// rather than only a [`VALUE`].
+///
+/// If any call within the closure unwinds, the remainder of the closure will
+/// not run, and Rust values owned by the closure will not be dropped.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     rb_sys::{protect_returning, AsRawValue},
+///     Error, Ruby,
+/// };
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let s = ruby.str_new("example");
+///     let len = protect_returning(|| unsafe { rb_sys::rb_str_strlen(s.as_raw()) })?;
+///     assert_eq!(len, 7);
+///
+///     let res = protect_returning(|| unsafe { rb_sys::rb_num2long(s.as_raw()) });
+///     assert!(res.is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn protect_returning<F, T>(func: F) -> Result<T, Error>
+where
+    F: FnOnce() -> T,
+{
+    error::protect_returning(func)
+}
+
+/// Calls the given function with `arg`, catching all cases of unwinding from
+/// Ruby returning them as an [`Error`].
+///
+/// This is a fast path for [`protect`], `func` is passed directly to Ruby
+/// rather than being called from an adapter function that unpacks a Rust
+/// closure.
+///
+/// # Safety
+///
+/// `func` must be safe to call with `arg`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     rb_sys::{protect_fn, AsRawValue},
+///     Error, Ruby,
+/// };
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val = ruby.integer_from_i64(42);
+///     let res = unsafe { protect_fn(rb_sys::rb_to_int, val.as_raw()) }?;
+///     assert_eq!(res, val.as_raw());
+///
+///     let s = ruby.str_new("example");
+///     let res = unsafe { protect_fn(rb_sys::rb_to_int, s.as_raw()) };
+///     assert!(res.is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub unsafe fn protect_fn(
+    func: unsafe extern "C" fn(VALUE) -> VALUE,
+    arg: VALUE,
+) -> Result<VALUE, Error> {
+    error::protect_fn(func, arg).map(|v| v.as_rb_value())
+}
+
+/// Calls the given closure with each item from `iter`, catching all cases of
+/// unwinding from Ruby returning them as an [`Error`].
+///
+/// All items are processed within a single protected call, which avoids the
+/// overhead of setting up a protected call per item when converting many
+/// values.
+///
+/// If any call unwinds, the remaining items will not be processed. `iter`,
+/// the items remaining in it, and `func` will be dropped as normal, but the
+/// item passed to the call that unwound will not be dropped.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     prelude::*,
+///     rb_sys::{protect_batch, AsRawValue},
+///     Error, Ruby,
+/// };
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let vals = [ruby.integer_from_i64(1), ruby.integer_from_i64(2)];
+///     let res = protect_batch(vals.iter(), |v| unsafe { rb_sys::rb_num2long(v.as_raw()) })?;
+///     assert_eq!(res, [1, 2]);
+///
+///     let vals = [ruby.integer_from_i64(1).as_value(), ruby.str_new("2").as_value()];
+///     let res = protect_batch(vals.iter(), |v| unsafe { rb_sys::rb_num2long(v.as_raw()) });
+///     assert!(res.is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn protect_batch<I, F, T>(iter: I, mut func: F) -> Result<Vec<T>, Error>
+where
+    I: IntoIterator,
+    F: FnMut(I::Item) -> T,
+{
+    let mut iter = iter.into_iter();
+    // the iterator, function, and results are kept outside of the protected
+    // call, and only borrowed by it, so they are still dropped if Ruby
+    // unwinds
+    let mut out = Vec::with_capacity(iter.size_hint().0);
+    error::protect_returning(|| {
+        for item in &mut iter {
+            out.push(func(item));
+        }
+    })?;
+    Ok(out)
+}
+
 /// Attempts to catch cases of Rust unwinding, converting to a fatal [`Error`].
 ///
 /// This should not be used to catch and discard panics.
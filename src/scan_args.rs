@@ -30,23 +30,25 @@
 //! See also [`Ruby`](Ruby#argument-parsing).
 
 use std::{
+    cell::UnsafeCell,
     ffi::CString,
     fmt,
     mem::transmute,
     ops::{Bound, RangeBounds},
     os::raw::c_int,
+    sync::Once,
 };
 
-use rb_sys::{rb_error_arity, rb_get_kwargs, rb_scan_args, ID, VALUE};
+use rb_sys::{rb_error_arity, rb_get_kwargs, rb_keyword_given_p, rb_scan_args, ID, VALUE};
 use seq_macro::seq;
 
 use crate::{
     block::Proc,
     error::{protect, Error},
     r_array::RArray,
-    r_hash::RHash,
+    r_hash::{FromRHash, RHash},
     try_convert::{TryConvert, TryConvertOwned},
-    value::{private::ReprValue as _, Id, IntoId, ReprValue, Value},
+    value::{private::ReprValue as _, Id, IntoId, OpaqueId, ReprValue, Value},
     Ruby,
 };
 
@@ -774,27 +776,38 @@ where
         .map(|id| id.into_id_with(&handle))
         .chain(optional.iter().copied().map(|id| id.into_id_with(&handle)))
         .collect::<Vec<Id>>();
+    unsafe { get_kwargs_ids(kw, ids.as_ptr() as *const ID) }
+}
+
+// `ids` must point to `Req::LEN + Opt::LEN` IDs, required then optional.
+unsafe fn get_kwargs_ids<Req, Opt, Splat>(
+    kw: RHash,
+    ids: *const ID,
+) -> Result<KwArgs<Req, Opt, Splat>, Error>
+where
+    Req: ScanArgsRequired,
+    Opt: ScanArgsOpt,
+    Splat: ScanArgsKw,
+{
+    let handle = Ruby::get_with(kw);
     let optional_len = if Splat::REQ {
-        -(optional.len() as i8 + 1)
+        -(Opt::LEN as i8 + 1)
     } else {
-        optional.len() as i8
+        Opt::LEN as i8
     };
-    let mut out = [unsafe { Ruby::get_unchecked().qnil().as_value() }; 19];
+    let mut out = [handle.qnil().as_value(); 19];
     let total = Req::LEN + Opt::LEN + Splat::REQ as usize;
 
-    let mut parsed = 0;
-    unsafe {
-        protect(|| {
-            parsed = rb_get_kwargs(
-                kw.as_rb_value(),
-                ids.as_ptr() as *const ID,
-                required.len() as c_int,
-                optional_len as c_int,
-                out[..total].as_mut_ptr() as *mut VALUE,
-            ) as usize;
-            Ruby::get_unchecked().qnil()
-        })?;
-    };
+    protect(|| {
+        rb_get_kwargs(
+            kw.as_rb_value(),
+            ids,
+            Req::LEN as c_int,
+            optional_len as c_int,
+            out[..total].as_mut_ptr() as *mut VALUE,
+        );
+        handle.qnil()
+    })?;
 
     let opt_end = Req::LEN + Opt::LEN;
     Ok(KwArgs {
@@ -804,6 +817,122 @@ where
     })
 }
 
+/// A table of keyword names for use with
+/// [`get_kwargs`](Keywords::get_kwargs).
+///
+/// [`get_kwargs`](fn@get_kwargs) converts each keyword name to a Ruby
+/// [`Id`] on every call. A `Keywords` can be assigned to a `static` and will
+/// intern its names only once, on first use, making it more efficient for
+/// frequently called methods.
+///
+/// The names are listed with the required keywords first, followed by the
+/// optional keywords.
+///
+/// As with [`LazyId`](crate::value::LazyId), initialisation must happen on a
+/// Ruby thread. If the first use is from a non-Ruby thread the `Keywords`
+/// will panic and then become *poisoned* and all future use of it will
+/// panic.
+pub struct Keywords<const N: usize> {
+    init: Once,
+    inner: UnsafeCell<KeywordsInner<N>>,
+}
+
+union KeywordsInner<const N: usize> {
+    names: [&'static str; N],
+    ids: [OpaqueId; N],
+}
+
+impl<const N: usize> Keywords<N> {
+    /// Create a new `Keywords`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::scan_args::Keywords;
+    ///
+    /// static KEYWORDS: Keywords<3> = Keywords::new(["a", "b", "c"]);
+    /// ```
+    pub const fn new(names: [&'static str; N]) -> Self {
+        Self {
+            init: Once::new(),
+            inner: UnsafeCell::new(KeywordsInner { names }),
+        }
+    }
+
+    fn ids(&self, handle: &Ruby) -> &[OpaqueId; N] {
+        unsafe {
+            self.init.call_once(|| {
+                let inner = self.inner.get();
+                (*inner).ids = (*inner).names.map(|name| handle.intern(name).into());
+            });
+            &(*self.inner.get()).ids
+        }
+    }
+
+    /// Deconstruct keyword arguments.
+    ///
+    /// Extracts required and optional arguments from the given `kw` hash,
+    /// as with [`get_kwargs`](fn@get_kwargs). The first `Req::LEN` names of
+    /// `self` are the required keywords, the remainder are optional.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the length of `self` does not match the
+    /// total length of the `Req` and `Opt` type parameters.
+    ///
+    /// # Examples
+    ///
+    /// The rough equivalent of `def test(a:, b:, c: nil, **rest)` would be:
+    /// ```
+    /// use magnus::{
+    ///     function, prelude::*, rb_assert, scan_args::Keywords, Error, RArray, RHash, Ruby,
+    /// };
+    ///
+    /// static KEYWORDS: Keywords<3> = Keywords::new(["a", "b", "c"]);
+    ///
+    /// fn test(ruby: &Ruby, kw: RHash) -> Result<RArray, Error> {
+    ///     let args = KEYWORDS.get_kwargs(kw)?;
+    ///     let (a, b): (String, usize) = args.required;
+    ///     let (c,): (Option<bool>,) = args.optional;
+    ///     let rest: RHash = args.splat;
+    ///
+    ///     let res = ruby.ary_new_capa(4);
+    ///     res.push(a)?;
+    ///     res.push(b)?;
+    ///     res.push(c)?;
+    ///     res.push(rest)?;
+    ///     Ok(res)
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("test", function!(test, 1));
+    ///
+    ///     rb_assert!(
+    ///         ruby,
+    ///         r#"test(a: "foo", b: 1, c: true, d: "bar") == ["foo", 1, true, {d: "bar"}]"#
+    ///     );
+    ///     rb_assert!(ruby, r#"test(a: "foo", b: 1) == ["foo", 1, nil, {}]"#);
+    ///     rb_assert!(ruby, r#"(test(a: "foo") rescue $!).is_a?(ArgumentError)"#);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get_kwargs<Req, Opt, Splat>(&self, kw: RHash) -> Result<KwArgs<Req, Opt, Splat>, Error>
+    where
+        Req: ScanArgsRequired,
+        Opt: ScanArgsOpt,
+        Splat: ScanArgsKw,
+    {
+        assert_eq!(Req::LEN + Opt::LEN, N);
+        let ids = self.ids(&Ruby::get_with(kw));
+        unsafe { get_kwargs_ids(kw, ids.as_ptr() as *const ID) }
+    }
+}
+
+unsafe impl<const N: usize> Send for Keywords<N> {}
+unsafe impl<const N: usize> Sync for Keywords<N> {}
+
 /// # Argument Parsing
 ///
 /// Functions for handling argument parsing.
@@ -917,3 +1046,246 @@ where
 {
     get_ruby!().check_arity(len, bounds)
 }
+
+/// A lightweight alternative to [`scan_args`] for methods defined with an
+/// arity of -1.
+///
+/// `MethodArgs` wraps the slice of arguments passed to the method, splitting
+/// off any keywords, and allows converting the positional arguments to a
+/// tuple with [`parse`](MethodArgs::parse).
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, rb_assert, scan_args::MethodArgs, Error, Ruby, Value};
+///
+/// fn greet(ruby: &Ruby, args: &[Value]) -> Result<String, Error> {
+///     let args = MethodArgs::new(ruby, args);
+///     let ((name,), (greeting,)): ((String,), (Option<String>,)) = args.parse()?;
+///     let punctuation = match args.keywords() {
+///         Some(kw) => kw.lookup::<_, Option<String>>(ruby.to_symbol("punctuation"))?,
+///         None => None,
+///     };
+///     Ok(format!(
+///         "{}, {}{}",
+///         greeting.as_deref().unwrap_or("Hello"),
+///         name,
+///         punctuation.as_deref().unwrap_or("."),
+///     ))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("greet", function!(greet, -1));
+///
+///     rb_assert!(ruby, r#"greet("Alice") == "Hello, Alice.""#);
+///     rb_assert!(ruby, r#"greet("Bob", "Hi", punctuation: "!") == "Hi, Bob!""#);
+///     rb_assert!(
+///         ruby,
+///         r#"(greet rescue $!).message == "wrong number of arguments (given 0, expected 1..2)""#,
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy)]
+pub struct MethodArgs<'a> {
+    ruby: &'a Ruby,
+    args: &'a [Value],
+    keywords: Option<RHash>,
+}
+
+impl fmt::Debug for MethodArgs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MethodArgs")
+            .field("args", &self.args)
+            .field("keywords", &self.keywords)
+            .finish()
+    }
+}
+
+impl<'a> MethodArgs<'a> {
+    /// Wrap the arguments `args` passed to the current method.
+    ///
+    /// If the method was called with keywords they will be removed from the
+    /// positional arguments, and are available from
+    /// [`keywords`](MethodArgs::keywords).
+    ///
+    /// This must be called from the method `args` were passed to, as it
+    /// checks how the current method was called to determine if keywords
+    /// were passed.
+    pub fn new(ruby: &'a Ruby, args: &'a [Value]) -> Self {
+        if unsafe { rb_keyword_given_p() } != 0 {
+            if let Some((last, rest)) = args.split_last() {
+                if let Some(keywords) = RHash::from_value(*last) {
+                    return Self {
+                        ruby,
+                        args: rest,
+                        keywords: Some(keywords),
+                    };
+                }
+            }
+        }
+        Self {
+            ruby,
+            args,
+            keywords: None,
+        }
+    }
+
+    /// Convert the positional arguments to a tuple of required arguments
+    /// `Req` and a tuple of optional arguments `Opt`.
+    ///
+    /// `Req` is a tuple of types implementing [`TryConvert`], and `Opt` a
+    /// tuple of `Option`s of types implementing `TryConvert`, as with
+    /// [`scan_args`]. Optional arguments that were not passed are `None`.
+    /// Returns an `ArgumentError` if too few or too many arguments were
+    /// passed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, scan_args::MethodArgs, Error, Ruby, Value};
+    ///
+    /// fn add(ruby: &Ruby, args: &[Value]) -> Result<i64, Error> {
+    ///     let ((a, b), (c,)) = MethodArgs::new(ruby, args).parse::<(i64, i64), (Option<i64>,)>()?;
+    ///     Ok(a + b + c.unwrap_or(0))
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("add", function!(add, -1));
+    ///
+    ///     rb_assert!(ruby, "add(1, 2) == 3");
+    ///     rb_assert!(ruby, "add(1, 2, 3) == 6");
+    ///     rb_assert!(ruby, "(add(1) rescue $!).is_a?(ArgumentError)");
+    ///     rb_assert!(ruby, "(add(1, 2, 3, 4) rescue $!).is_a?(ArgumentError)");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn parse<Req, Opt>(&self) -> Result<(Req, Opt), Error>
+    where
+        Req: ScanArgsRequired,
+        Opt: ScanArgsOpt,
+    {
+        let req_len = <Req as private::ScanArgsRequired>::LEN;
+        let opt_len = <Opt as private::ScanArgsOpt>::LEN;
+        self.ruby
+            .check_arity(self.args.len(), req_len..=req_len + opt_len)?;
+        let (req, opt) = self.args.split_at(req_len);
+        Ok((
+            private::ScanArgsRequired::from_slice(req)?,
+            private::ScanArgsOpt::from_slice(opt)?,
+        ))
+    }
+
+    /// Returns the keywords passed to the method, if any.
+    pub fn keywords(&self) -> Option<RHash> {
+        self.keywords
+    }
+
+    /// Returns `self` along with the block passed to the current method, if
+    /// any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{function, rb_assert, scan_args::MethodArgs, Error, Ruby, Value};
+    ///
+    /// fn apply(ruby: &Ruby, args: &[Value]) -> Result<Value, Error> {
+    ///     let (args, block) = MethodArgs::new(ruby, args).split_block();
+    ///     let ((val,), ()): ((Value,), ()) = args.parse()?;
+    ///     match block {
+    ///         Some(block) => block.call((val,)),
+    ///         None => Ok(val),
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_global_function("apply", function!(apply, -1));
+    ///
+    ///     rb_assert!(ruby, "apply(1) == 1");
+    ///     rb_assert!(ruby, "apply(1) { |i| i + 1 } == 2");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn split_block(self) -> (Self, Option<Proc>) {
+        let block = if self.ruby.block_given() {
+            self.ruby.block_proc().ok()
+        } else {
+            None
+        };
+        (self, block)
+    }
+
+    /// Returns the positional arguments as a slice.
+    pub fn as_slice(&self) -> &'a [Value] {
+        self.args
+    }
+
+    /// Returns the number of positional arguments.
+    pub fn len(&self) -> usize {
+        self.args.len()
+    }
+
+    /// Returns whether no positional arguments were passed.
+    pub fn is_empty(&self) -> bool {
+        self.args.is_empty()
+    }
+}
+
+/// Keyword arguments converted to `T` with [`FromRHash`].
+///
+/// This is intended for use as the final argument of a method or function
+/// defined with the `kw` form of [`method!`](crate::method!) or
+/// [`function!`](crate::function!). If no keywords were passed `T` will be
+/// converted from an empty Hash.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     function, r_hash::FromRHash, rb_assert, scan_args::KeywordArgs, Error, RHash, Ruby,
+/// };
+///
+/// struct Options {
+///     times: Option<usize>,
+/// }
+///
+/// impl FromRHash for Options {
+///     fn from_r_hash(hash: RHash) -> Result<Self, Error> {
+///         let ruby = Ruby::get_with(hash);
+///         Ok(Self {
+///             times: hash.lookup(ruby.to_symbol("times"))?,
+///         })
+///     }
+/// }
+///
+/// fn repeat(s: String, kw: KeywordArgs<Options>) -> String {
+///     s.repeat(kw.0.times.unwrap_or(2))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("repeat", function!(repeat, 1..=1, kw));
+///
+///     rb_assert!(ruby, r#"repeat("ab") == "abab""#);
+///     rb_assert!(ruby, r#"repeat("ab", times: 3) == "ababab""#);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeywordArgs<T>(pub T);
+
+impl<T> TryConvert for KeywordArgs<T>
+where
+    T: FromRHash,
+{
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        T::from_r_hash(RHash::try_convert(val)?).map(Self)
+    }
+}
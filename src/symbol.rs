@@ -1,8 +1,15 @@
 //! Types and traits for working with Ruby symbols.
 
-use std::{borrow::Cow, fmt};
+use std::{
+    borrow::Cow,
+    fmt,
+    os::raw::{c_char, c_long},
+};
 
-use rb_sys::{rb_check_id, rb_intern_str, rb_sym2str, rb_to_symbol, ruby_value_type, VALUE};
+use rb_sys::{
+    rb_check_id, rb_check_symbol_cstr, rb_intern_str, rb_sym2str, rb_to_symbol, ruby_value_type,
+    VALUE,
+};
 
 use crate::{
     encoding::EncodingCapable,
@@ -42,6 +49,39 @@ impl Ruby {
     pub fn to_symbol<T: AsRef<str>>(&self, name: T) -> Symbol {
         name.as_ref().into_symbol_with(self)
     }
+
+    /// Return the `Symbol` for `name`, if one exists.
+    ///
+    /// Unlike [`Ruby::to_symbol`] this will not create a new symbol, so can
+    /// be used to look up untrusted input without growing Ruby's symbol
+    /// table. Unlike [`Ruby::check_symbol`] this will also find dynamic
+    /// (garbage collectable) symbols.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Symbol};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.find_symbol("example").is_none());
+    ///     let sym: Symbol = ruby.eval(r#""example".to_sym"#)?;
+    ///     assert_eq!(ruby.find_symbol("example"), Some(sym));
+    ///     assert!(ruby.check_symbol("example").is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn find_symbol(&self, name: &str) -> Option<Symbol> {
+        unsafe {
+            let res = Value::new(rb_check_symbol_cstr(
+                name.as_ptr() as *mut c_char,
+                name.len() as c_long,
+                self.utf8_encoding().as_ptr(),
+            ));
+            (!res.is_nil()).then(|| Symbol::from_rb_value_unchecked(res.as_rb_value()))
+        }
+    }
 }
 
 /// A type wrapping either a [`StaticSymbol`] or a Value pointer to a RSymbol
@@ -191,6 +231,32 @@ impl Symbol {
             .then(|| unsafe { StaticSymbol::from_rb_value_unchecked(self.as_rb_value()) })
     }
 
+    /// Returns whether the name of `self` is `s`.
+    ///
+    /// This does not allocate a Ruby object, so is cheaper than converting
+    /// `s` to a symbol to compare.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, Symbol};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.eval::<Symbol>(":foo")?.eql_str("foo"));
+    ///     assert!(ruby.to_symbol("bar").eql_str("bar"));
+    ///     assert!(!ruby.to_symbol("bar").eql_str("baz"));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn eql_str(self, s: &str) -> bool {
+        if let Some(sym) = self.as_static() {
+            return sym.name().map(|name| name == s).unwrap_or(false);
+        }
+        unsafe { RString::from_rb_value_unchecked(rb_sym2str(self.as_rb_value())) }.eql_str(s)
+    }
+
     /// If `self` is already static simply returns `self` as a
     /// [`StaticSymbol`]. If `self` is not static it will be made so and
     /// returned as a [`StaticSymbol`].
@@ -0,0 +1,243 @@
+//! Helpers for testing Ruby extensions.
+//!
+//! See also the [`rb_assert!`](crate::rb_assert) and
+//! [`assert_ruby_eq!`](crate::assert_ruby_eq) macros.
+
+use rb_sys::ruby_value_type;
+
+use crate::{
+    class::RClass,
+    error::Error,
+    module::RModule,
+    symbol::IntoSymbol,
+    typed_data::{Obj, TypedData},
+    value::{private::ReprValue as _, ReprValue, Value},
+    Ruby,
+};
+
+/// Define a class named `name` in the root scope, call `func` with it, then
+/// remove the class.
+///
+/// The class is removed even if `func` returns an error or panics, so tests
+/// don't leak classes in to each other.
+///
+/// Errors if a constant named `name` is already defined.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{method, prelude::*, rb_assert, testing::with_temp_class, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     with_temp_class(ruby, "Example", ruby.class_object(), |class| {
+///         class.define_method("answer", method!(|_: Value| 42, 0))?;
+///         rb_assert!(ruby, "Example.new.answer == 42");
+///         Ok(())
+///     })?;
+///     rb_assert!(ruby, "!defined?(Example)");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn with_temp_class<F, T>(
+    ruby: &Ruby,
+    name: &str,
+    superclass: RClass,
+    func: F,
+) -> Result<T, Error>
+where
+    F: FnOnce(RClass) -> Result<T, Error>,
+{
+    check_undefined(ruby, name)?;
+    let class = ruby.define_class(name, superclass)?;
+    let _guard = RemoveConst { ruby, name };
+    func(class)
+}
+
+/// Define a module named `name` in the root scope, call `func` with it, then
+/// remove the module.
+///
+/// The module is removed even if `func` returns an error or panics, so tests
+/// don't leak modules in to each other.
+///
+/// Errors if a constant named `name` is already defined.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, prelude::*, rb_assert, testing::with_temp_module, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     with_temp_module(ruby, "Example", |module| {
+///         module.define_module_function("answer", function!(|| 42, 0))?;
+///         rb_assert!(ruby, "Example.answer == 42");
+///         Ok(())
+///     })?;
+///     rb_assert!(ruby, "!defined?(Example)");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn with_temp_module<F, T>(ruby: &Ruby, name: &str, func: F) -> Result<T, Error>
+where
+    F: FnOnce(RModule) -> Result<T, Error>,
+{
+    check_undefined(ruby, name)?;
+    let module = ruby.define_module(name)?;
+    let _guard = RemoveConst { ruby, name };
+    func(module)
+}
+
+/// Run GC compaction, then check the Ruby objects referenced by `obj` are
+/// still valid.
+///
+/// `fields` should return a name and the current value of each Ruby object
+/// stored in `obj`. A stale reference, left pointing at the old location of
+/// an object after it has been moved, usually means the type marks objects
+/// with [`mark_movable`](crate::gc::Marker::mark_movable) but doesn't
+/// update them in
+/// [`DataTypeFunctions::compact`](crate::typed_data::DataTypeFunctions::compact).
+///
+/// Compaction is run with `GC.compact`. Ruby only moves objects that it can
+/// move in to free slots, and never moves objects referenced from the stack,
+/// so this can't detect stale references to objects that were not moved.
+/// If the current platform does not support compaction this does nothing and
+/// returns `Ok(())`.
+///
+/// Returns `Err` naming the fields with stale references. The stale
+/// references must be replaced before Ruby next marks `obj`.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use magnus::{
+///     gc, testing::verify_compaction, value::Opaque, DataTypeFunctions, Error, RString, Ruby,
+///     TypedData,
+/// };
+///
+/// #[derive(TypedData)]
+/// #[magnus(class = "Label", mark, compact)]
+/// struct Label {
+///     text: RefCell<Opaque<RString>>,
+/// }
+///
+/// impl DataTypeFunctions for Label {
+///     fn mark(&self, marker: &gc::Marker) {
+///         marker.mark_movable(*self.text.borrow());
+///     }
+///
+///     fn compact(&self, compactor: &gc::Compactor) {
+///         let text = compactor.location(*self.text.borrow());
+///         *self.text.borrow_mut() = text;
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_class("Label", ruby.class_object())?;
+///     let label = ruby.obj_wrap(Label {
+///         text: RefCell::new(Opaque::from(ruby.str_new("example"))),
+///     });
+///
+///     verify_compaction(ruby, label, |label| {
+///         vec![("text", ruby.get_inner(*label.text.borrow()).as_value())]
+///     })?;
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn verify_compaction<T, F>(ruby: &Ruby, obj: Obj<T>, fields: F) -> Result<(), Error>
+where
+    T: TypedData,
+    F: Fn(&T) -> Vec<(&'static str, Value)>,
+{
+    let gc = ruby.module_gc();
+    // `respond_to?` is false where `GC.compact` is not implemented
+    let supported: bool = gc.funcall("respond_to?", ("compact".into_symbol_with(ruby),))?;
+    if !supported {
+        return Ok(());
+    }
+
+    let ids = object_ids(&*obj, &fields)?;
+
+    // `GC.verify_compaction_references` would move more objects, but it
+    // aborts the process when it finds a reference to a moved object, which
+    // is exactly what this is looking for
+    let _: Value = gc.funcall("compact", ())?;
+
+    let mut stale = Vec::new();
+    for ((name, val), id) in fields(&obj).into_iter().zip(ids) {
+        // check the type first, calling a method on a moved object will
+        // likely crash
+        let moved = matches!(
+            val.rb_type(),
+            ruby_value_type::RUBY_T_MOVED
+                | ruby_value_type::RUBY_T_NONE
+                | ruby_value_type::RUBY_T_ZOMBIE
+        );
+        if moved || val.object_id()?.to_u64()? != id {
+            stale.push(name);
+        }
+    }
+    if !stale.is_empty() {
+        return Err(Error::new(
+            ruby.exception_runtime_error(),
+            format!(
+                "stale references after compaction in {}: {}",
+                T::class(ruby),
+                stale.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+// Not inlined so the Ruby objects returned by `fields` aren't left on the
+// stack of `verify_compaction`, which would pin them during compaction.
+#[inline(never)]
+fn object_ids<T, F>(obj: &T, fields: &F) -> Result<Vec<u64>, Error>
+where
+    F: Fn(&T) -> Vec<(&'static str, Value)>,
+{
+    fields(obj)
+        .into_iter()
+        .map(|(_, val)| val.object_id()?.to_u64())
+        .collect()
+}
+
+fn check_undefined(ruby: &Ruby, name: &str) -> Result<(), Error> {
+    let defined: bool = ruby
+        .class_object()
+        .funcall("const_defined?", (name.into_symbol_with(ruby), false))?;
+    if defined {
+        return Err(Error::new(
+            ruby.exception_name_error(),
+            format!("constant {} is already defined", name),
+        ));
+    }
+    Ok(())
+}
+
+/// Removes the constant `name` from `Object` when dropped.
+struct RemoveConst<'a> {
+    ruby: &'a Ruby,
+    name: &'a str,
+}
+
+impl Drop for RemoveConst<'_> {
+    fn drop(&mut self) {
+        // `remove_const` is private, hence `__send__`. If this fails the
+        // constant has already been removed, so there's nothing to do.
+        let _: Result<Value, Error> = self.ruby.class_object().funcall(
+            "__send__",
+            (
+                "remove_const".into_symbol_with(self.ruby),
+                self.name.into_symbol_with(self.ruby),
+            ),
+        );
+    }
+}
@@ -1,11 +1,19 @@
-use std::{fmt, mem::size_of, os::raw::c_void, slice, time::Duration};
+use std::{
+    fmt,
+    mem::size_of,
+    os::raw::c_void,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    ptr, slice,
+    time::Duration,
+};
 
 use rb_sys::{
-    rb_data_typed_object_wrap, rb_thread_alone, rb_thread_check_ints, rb_thread_create,
-    rb_thread_current, rb_thread_fd_close, rb_thread_fd_writable, rb_thread_interrupted,
-    rb_thread_kill, rb_thread_local_aref, rb_thread_local_aset, rb_thread_main, rb_thread_run,
-    rb_thread_schedule, rb_thread_sleep_deadly, rb_thread_sleep_forever, rb_thread_wait_fd,
-    rb_thread_wait_for, rb_thread_wakeup, rb_thread_wakeup_alive, timeval, VALUE,
+    rb_data_typed_object_wrap, rb_sys_fail, rb_thread_alone, rb_thread_call_without_gvl,
+    rb_thread_check_ints, rb_thread_create, rb_thread_current, rb_thread_fd_close,
+    rb_thread_fd_writable, rb_thread_interrupted, rb_thread_kill, rb_thread_local_aref,
+    rb_thread_local_aset, rb_thread_main, rb_thread_run, rb_thread_schedule,
+    rb_thread_sleep_deadly, rb_thread_sleep_forever, rb_thread_wait_fd, rb_thread_wait_for,
+    rb_thread_wakeup, rb_thread_wakeup_alive, rb_wait_for_single_fd, timeval, RB_WAITFD_IN, VALUE,
 };
 
 use crate::{
@@ -18,6 +26,7 @@ use crate::{
     object::Object,
     r_file::fd::AsRawFd,
     r_typed_data::RTypedData,
+    symbol::IntoSymbol,
     try_convert::TryConvert,
     typed_data::{DataType, DataTypeFunctions},
     value::{
@@ -217,6 +226,64 @@ impl Ruby {
         Ok(())
     }
 
+    /// Blocks until the given file descriptor is readable, or `timeout` has
+    /// elapsed.
+    ///
+    /// Returns `Ok(true)` if `fd` is readable, or `Ok(false)` if the timeout
+    /// elapsed first. If `timeout` is `None` this will wait indefinitely.
+    ///
+    /// As with [`thread_wait_fd`](Ruby::thread_wait_fd), other Ruby threads
+    /// can run while waiting, and the wait can be interrupted, e.g. by a
+    /// signal or `Thread#raise`, in which case an error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::{io::Write, os::unix::net::UnixStream, time::Duration};
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let (mut a, b) = UnixStream::pair().unwrap();
+    ///
+    ///     let timeout = Some(Duration::from_millis(10));
+    ///     assert!(!ruby.thread_wait_fd_readable(&b, timeout)?);
+    ///
+    ///     a.write_all(b"hello, world!").unwrap();
+    ///     assert!(ruby.thread_wait_fd_readable(&b, timeout)?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// # }
+    /// ```
+    pub fn thread_wait_fd_readable<T>(
+        &self,
+        fd: &T,
+        timeout: Option<Duration>,
+    ) -> Result<bool, Error>
+    where
+        T: AsRawFd,
+    {
+        let fd = fd.as_raw_fd();
+        let mut t = timeout.map(|duration| timeval {
+            tv_sec: duration.as_secs() as _,
+            tv_usec: duration.subsec_micros() as _,
+        });
+        let t = t.as_mut().map_or(ptr::null_mut(), |t| t as *mut timeval);
+        let mut res = 0;
+        protect(|| unsafe {
+            res = rb_wait_for_single_fd(fd, RB_WAITFD_IN as _, t);
+            if res < 0 {
+                rb_sys_fail(ptr::null());
+            }
+            self.qnil()
+        })?;
+        Ok(res != 0)
+    }
+
     /// Blocks until the given file descriptor is writable.
     ///
     /// # Examples
@@ -303,6 +370,9 @@ impl Ruby {
 
     /// Blocks for the given period of time.
     ///
+    /// Unlike [`std::thread::sleep`], other Ruby threads can run while this
+    /// thread sleeps, and the sleep can be interrupted.
+    ///
     /// Returns an error if sleep is intrrupted by a signal.
     ///
     /// # Examples
@@ -393,6 +463,222 @@ impl Ruby {
         })?;
         Ok(())
     }
+
+    /// Run `func` with the Global VM Lock (GVL) released, allowing other Ruby
+    /// threads to run in parallel.
+    ///
+    /// If Ruby needs to interrupt the current thread while `func` is running,
+    /// e.g. for `Thread#kill` or ctrl-c, `unblock` is called from another
+    /// thread. It should cause `func` to return early, for example by setting
+    /// a flag `func` checks. If `unblock` is `None` then `func` can not be
+    /// interrupted and will always run to completion.
+    ///
+    /// Any interrupts are run once `func` returns. If an interrupt raises an
+    /// error it is returned as `Err` and the result of `func` is discarded.
+    /// If `func` panics, the panic is resumed once the GVL has been
+    /// reacquired.
+    ///
+    /// # Safety
+    ///
+    /// `func` and `unblock` must not interact with Ruby in any way. This
+    /// includes getting a [`Ruby`] handle with [`Ruby::get`], calling
+    /// functions that get one internally, such as the `old-api` functions, and
+    /// accessing [`Opaque`](crate::value::Opaque) or [`Lazy`](crate::value::Lazy)
+    /// values. Requiring `func` and its return value to be `Send` prevents
+    /// capturing Ruby values, but the current thread is still a Ruby thread,
+    /// so does not prevent these.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let cancelled = AtomicBool::new(false);
+    ///     let sum = unsafe {
+    ///         ruby.thread_call_without_gvl(
+    ///             || {
+    ///                 let mut sum = 0_u64;
+    ///                 for i in 0..1_000_000 {
+    ///                     if cancelled.load(Ordering::Relaxed) {
+    ///                         break;
+    ///                     }
+    ///                     sum += i;
+    ///                 }
+    ///                 sum
+    ///             },
+    ///             Some(|| cancelled.store(true, Ordering::Relaxed)),
+    ///         )?
+    ///     };
+    ///     assert_eq!(sum, 499999500000);
+    ///
+    ///     let answer = unsafe { ruby.thread_call_without_gvl(|| 6 * 7, None::<fn()>)? };
+    ///     assert_eq!(answer, 42);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub unsafe fn thread_call_without_gvl<F, R, U>(
+        &self,
+        func: F,
+        unblock: Option<U>,
+    ) -> Result<R, Error>
+    where
+        F: Send + FnOnce() -> R,
+        R: Send,
+        U: Sync + Fn(),
+    {
+        type Data<F, R> = (Option<F>, Option<std::thread::Result<R>>);
+
+        unsafe extern "C" fn call<F, R>(arg: *mut c_void) -> *mut c_void
+        where
+            F: FnOnce() -> R,
+        {
+            let data = &mut *(arg as *mut Data<F, R>);
+            let func = data.0.take().unwrap();
+            data.1 = Some(catch_unwind(AssertUnwindSafe(func)));
+            ptr::null_mut()
+        }
+
+        unsafe extern "C" fn call_unblock<U>(arg: *mut c_void)
+        where
+            U: Fn(),
+        {
+            // we can't unwind in to Ruby, and there's nowhere to report a
+            // panic, so it's ignored
+            let _ = catch_unwind(AssertUnwindSafe(&*(arg as *const U)));
+        }
+
+        let mut data: Data<F, R> = (Some(func), None);
+        let (ubf, ubf_arg) = match &unblock {
+            Some(u) => (
+                Some(call_unblock::<U> as unsafe extern "C" fn(*mut c_void)),
+                u as *const U as *mut c_void,
+            ),
+            None => (None, ptr::null_mut()),
+        };
+        let res = protect(|| {
+            rb_thread_call_without_gvl(
+                Some(call::<F, R>),
+                &mut data as *mut Data<F, R> as *mut c_void,
+                ubf,
+                ubf_arg,
+            );
+            self.qnil()
+        });
+        let out = match data.1 {
+            Some(Ok(out)) => Some(out),
+            Some(Err(e)) => resume_unwind(e),
+            None => None,
+        };
+        res?;
+        // Ruby only skips calling func if an interrupt raises before the GVL
+        // is released, in which case protect has returned Err above. func
+        // can't unwind, as panics are caught and resumed above.
+        Ok(out.expect("func is always called when the GVL is released"))
+    }
+
+    /// Get the value for `key` from the storage local to the currently
+    /// executing Fiber, like Ruby's `Thread.current[key]`.
+    ///
+    /// Unlike Rust's thread local storage this follows Ruby's Fibers, so is
+    /// suitable for per-request state in Ruby frameworks that run each
+    /// request in its own Fiber.
+    ///
+    /// See also [`Thread::local_aref`], and
+    /// [`thread_local_get`](Ruby::thread_local_get) for storage shared by all
+    /// Fibers on a thread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.fiber_local_get::<_, Option<i64>>("request_id")?, None);
+    ///
+    ///     ruby.fiber_local_set("request_id", 42)?;
+    ///     assert_eq!(ruby.fiber_local_get::<_, i64>("request_id")?, 42);
+    ///     rb_assert!(ruby, "Thread.current[:request_id] == 42");
+    ///
+    ///     // not visible from another Fiber
+    ///     rb_assert!(ruby, "Fiber.new { Thread.current[:request_id] }.resume.nil?");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn fiber_local_get<I, T>(&self, key: I) -> Result<T, Error>
+    where
+        I: IntoId,
+        T: TryConvert,
+    {
+        self.thread_current().local_aref(key)
+    }
+
+    /// Set the value for `key` in the storage local to the currently
+    /// executing Fiber, like Ruby's `Thread.current[key] = val`.
+    ///
+    /// See [`fiber_local_get`](Ruby::fiber_local_get) for details.
+    pub fn fiber_local_set<I, T>(&self, key: I, val: T) -> Result<(), Error>
+    where
+        I: IntoId,
+        T: IntoValue,
+    {
+        self.thread_current().local_aset(key, val)
+    }
+
+    /// Get the value for `key` from the storage local to the current thread,
+    /// shared by all Fibers on the thread, like Ruby's
+    /// `Thread.current.thread_variable_get(key)`.
+    ///
+    /// See also [`Thread::variable_get`], and
+    /// [`fiber_local_get`](Ruby::fiber_local_get) for storage local to the
+    /// current Fiber.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert_eq!(ruby.thread_local_get::<_, Option<i64>>("pool_size")?, None);
+    ///
+    ///     ruby.thread_local_set("pool_size", 5)?;
+    ///     assert_eq!(ruby.thread_local_get::<_, i64>("pool_size")?, 5);
+    ///
+    ///     // visible from another Fiber on the same thread
+    ///     rb_assert!(
+    ///         ruby,
+    ///         "Fiber.new { Thread.current.thread_variable_get(:pool_size) }.resume == 5"
+    ///     );
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn thread_local_get<I, T>(&self, key: I) -> Result<T, Error>
+    where
+        I: IntoId,
+        T: TryConvert,
+    {
+        self.thread_current().variable_get(key)
+    }
+
+    /// Set the value for `key` in the storage local to the current thread,
+    /// like Ruby's `Thread.current.thread_variable_set(key, val)`.
+    ///
+    /// See [`thread_local_get`](Ruby::thread_local_get) for details.
+    pub fn thread_local_set<I, T>(&self, key: I, val: T) -> Result<(), Error>
+    where
+        I: IntoId,
+        T: IntoValue,
+    {
+        self.thread_current().variable_set(key, val)
+    }
 }
 
 /// Wrapper type for a Value known to be an instance of Ruby's Thread class.
@@ -489,8 +775,8 @@ impl Thread {
     ///
     /// When Fibers were added to Ruby this method became Fiber-local. If only
     /// a single Fiber is run on a thread then this acts exactly like
-    /// thread-local storage. Ruby's C API does not expose true thread local
-    /// storage.
+    /// thread-local storage. See [`variable_get`](Thread::variable_get) for
+    /// true thread-local storage.
     ///
     /// # Examples
     ///
@@ -543,8 +829,8 @@ impl Thread {
     ///
     /// When Fibers were added to Ruby this method became Fiber-local. If only
     /// a single Fiber is run on a thread then this acts exactly like
-    /// thread-local storage. Ruby's C API does not expose true thread local
-    /// storage.
+    /// thread-local storage. See [`variable_set`](Thread::variable_set) for
+    /// true thread-local storage.
     ///
     /// # Examples
     ///
@@ -592,6 +878,56 @@ impl Thread {
         Ok(())
     }
 
+    /// Get the value for `key` from the thread-local storage of `self`,
+    /// shared by all Fibers running on the thread.
+    ///
+    /// This is equivalent to Ruby's `Thread#thread_variable_get`. See
+    /// [`local_aref`](Thread::local_aref) for Fiber-local storage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let current = ruby.thread_current();
+    ///     assert_eq!(current.variable_get::<_, Option<String>>("example")?, None);
+    ///
+    ///     current.variable_set("example", "foo")?;
+    ///     assert_eq!(current.variable_get::<_, String>("example")?, "foo");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn variable_get<I, T>(self, key: I) -> Result<T, Error>
+    where
+        I: IntoId,
+        T: TryConvert,
+    {
+        let ruby = Ruby::get_with(self);
+        let key = key.into_id_with(&ruby).into_symbol_with(&ruby);
+        self.funcall("thread_variable_get", (key,))
+    }
+
+    /// Set the value for `key` in the thread-local storage of `self`, shared
+    /// by all Fibers running on the thread.
+    ///
+    /// Returns `Err` if `self` is frozen.
+    ///
+    /// This is equivalent to Ruby's `Thread#thread_variable_set`. See
+    /// [`local_aset`](Thread::local_aset) for Fiber-local storage.
+    pub fn variable_set<I, T>(self, key: I, val: T) -> Result<(), Error>
+    where
+        I: IntoId,
+        T: IntoValue,
+    {
+        let ruby = Ruby::get_with(self);
+        let key = key.into_id_with(&ruby).into_symbol_with(&ruby);
+        let _: Value = self.funcall("thread_variable_set", (key, val))?;
+        Ok(())
+    }
+
     /// Check if `self` has been interrupted.
     ///
     /// Returns true if the thread was interrupted, false otherwise. This can
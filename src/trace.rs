@@ -0,0 +1,84 @@
+// Optional instrumentation of calls between Ruby and Rust.
+//
+// With the `tracing` feature enabled these emit `tracing` spans at the trace
+// level, otherwise they compile down to nothing.
+
+#[cfg(feature = "tracing")]
+mod imp {
+    use std::{any::type_name, fmt};
+
+    use rb_sys::rb_frame_this_func;
+    use tracing::{span::EnteredSpan, trace_span};
+
+    use crate::value::Id;
+
+    pub(crate) type Guard = EnteredSpan;
+
+    struct Name(Id);
+
+    impl fmt::Display for Name {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self.0.name() {
+                Ok(name) => f.write_str(name),
+                Err(_) => f.write_str("<unknown>"),
+            }
+        }
+    }
+
+    /// Span for calling the Ruby method `id`.
+    #[inline]
+    pub(crate) fn funcall(id: Id) -> Guard {
+        trace_span!("funcall", method = %Name(id)).entered()
+    }
+
+    /// Span for running a closure under `rb_protect`.
+    #[inline]
+    pub(crate) fn protect() -> Guard {
+        trace_span!("protect").entered()
+    }
+
+    /// Span for a call from Ruby in to a Rust method or function.
+    #[inline]
+    pub(crate) fn method() -> Guard {
+        let id = unsafe { rb_frame_this_func() };
+        if id == 0 {
+            return trace_span!("method").entered();
+        }
+        trace_span!("method", method = %Name(Id::from_rb_id(id))).entered()
+    }
+
+    /// Span for converting a method argument to `T`.
+    #[inline]
+    pub(crate) fn convert<T>() -> Guard {
+        trace_span!("convert", to = type_name::<T>()).entered()
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod imp {
+    use crate::value::Id;
+
+    pub(crate) struct Guard;
+
+    #[inline]
+    pub(crate) fn funcall(_id: Id) -> Guard {
+        Guard
+    }
+
+    #[inline]
+    pub(crate) fn protect() -> Guard {
+        Guard
+    }
+
+    #[inline]
+    pub(crate) fn method() -> Guard {
+        Guard
+    }
+
+    #[inline]
+    pub(crate) fn convert<T>() -> Guard {
+        Guard
+    }
+}
+
+pub(crate) use imp::*;
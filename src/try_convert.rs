@@ -1,19 +1,33 @@
 //! Traits for converting from Ruby [`Value`]s to Rust types.
 
-use std::path::PathBuf;
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::UnsafeCell,
+    ffi::OsString,
+    os::raw::{c_char, c_int},
+    path::PathBuf,
+};
 
-use rb_sys::{rb_get_path, rb_num2dbl};
+use rb_sys::{
+    rb_check_array_type, rb_check_convert_type, rb_check_hash_type, rb_check_string_type,
+    rb_get_path, rb_num2dbl, ruby_value_type, VALUE,
+};
 use seq_macro::seq;
 
 #[cfg(ruby_use_flonum)]
 use crate::value::Flonum;
 use crate::{
+    block::Proc,
+    class::{Class, RClass},
     error::{protect, Error},
+    float::Float,
+    gc,
     integer::Integer,
+    into_value::{IntoValue, IntoValueFromNative},
     r_array::RArray,
     r_hash::RHash,
     r_string::RString,
-    value::{Fixnum, ReprValue, Value},
+    value::{private::ReprValue as _, Fixnum, Qfalse, Qtrue, ReprValue, Value},
     Ruby,
 };
 
@@ -34,6 +48,269 @@ pub trait TryConvert: Sized {
 /// This trait must not be implemented for types that contain `Value`.
 pub unsafe trait TryConvertOwned: TryConvert {}
 
+/// Convert `val` to an [`RArray`] with Ruby's implicit conversion protocol.
+///
+/// Returns `Ok(Some(RArray))` if `val` is an Array or responds to `to_ary`,
+/// and `Ok(None)` if it does not respond to `to_ary`.
+///
+/// Errors if `to_ary` raises or does not return an Array.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{try_convert::to_ary, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val: Value = ruby.eval(
+///         r#"
+///           o = Object.new
+///           def o.to_ary; [1, 2]; end
+///           o
+///         "#,
+///     )?;
+///     assert_eq!(to_ary(val)?.unwrap().to_vec::<i64>()?, vec![1, 2]);
+///
+///     assert!(to_ary(ruby.eval("1..2")?)?.is_none());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_ary(val: Value) -> Result<Option<RArray>, Error> {
+    if let Some(v) = RArray::from_value(val) {
+        return Ok(Some(v));
+    }
+    protect(|| unsafe { Value::new(rb_check_array_type(val.as_rb_value())) })
+        .map(RArray::from_value)
+}
+
+/// Convert `val` to an [`RHash`] with Ruby's implicit conversion protocol.
+///
+/// Returns `Ok(Some(RHash))` if `val` is a Hash or responds to `to_hash`, and
+/// `Ok(None)` if it does not respond to `to_hash`.
+///
+/// Errors if `to_hash` raises or does not return a Hash.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{try_convert::to_hash, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val: Value = ruby.eval(
+///         r#"
+///           o = Object.new
+///           def o.to_hash; {a: 1}; end
+///           o
+///         "#,
+///     )?;
+///     assert_eq!(to_hash(val)?.unwrap().len(), 1);
+///
+///     assert!(to_hash(ruby.eval("[[:a, 1]]")?)?.is_none());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_hash(val: Value) -> Result<Option<RHash>, Error> {
+    if let Some(v) = RHash::from_value(val) {
+        return Ok(Some(v));
+    }
+    protect(|| unsafe { Value::new(rb_check_hash_type(val.as_rb_value())) }).map(RHash::from_value)
+}
+
+/// Convert `val` to an [`RString`] with Ruby's implicit conversion protocol.
+///
+/// Returns `Ok(Some(RString))` if `val` is a String or responds to `to_str`,
+/// and `Ok(None)` if it does not respond to `to_str`.
+///
+/// Errors if `to_str` raises or does not return a String.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{try_convert::to_str, Error, Ruby, Value};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let val: Value = ruby.eval(
+///         r#"
+///           o = Object.new
+///           def o.to_str; "example"; end
+///           o
+///         "#,
+///     )?;
+///     assert_eq!(to_str(val)?.unwrap().to_string()?, "example");
+///
+///     assert!(to_str(ruby.eval(":example")?)?.is_none());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_str(val: Value) -> Result<Option<RString>, Error> {
+    if let Some(v) = RString::from_value(val) {
+        return Ok(Some(v));
+    }
+    protect(|| unsafe { Value::new(rb_check_string_type(val.as_rb_value())) })
+        .map(RString::from_value)
+}
+
+/// Convert `val` to a [`Proc`] with Ruby's implicit conversion protocol.
+///
+/// Returns `Ok(Some(Proc))` if `val` is a Proc or responds to `to_proc`, and
+/// `Ok(None)` if it does not respond to `to_proc`.
+///
+/// Errors if `to_proc` raises or does not return a Proc.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{prelude::*, try_convert::to_proc, Error, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let proc = to_proc(ruby.to_symbol("upcase").as_value())?.unwrap();
+///     let res: String = proc.call(("example",))?;
+///     assert_eq!(res, "EXAMPLE");
+///
+///     assert!(to_proc(ruby.eval("1")?)?.is_none());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn to_proc(val: Value) -> Result<Option<Proc>, Error> {
+    if let Some(v) = Proc::from_value(val) {
+        return Ok(Some(v));
+    }
+    let res = protect(|| unsafe {
+        Value::new(rb_check_convert_type(
+            val.as_rb_value(),
+            ruby_value_type::RUBY_T_DATA as c_int,
+            "Proc\0".as_ptr() as *const c_char,
+            "to_proc\0".as_ptr() as *const c_char,
+        ))
+    })?;
+    if res.is_nil() {
+        return Ok(None);
+    }
+    Proc::from_value(res).map(Some).ok_or_else(|| {
+        Error::new(
+            Ruby::get_with(val).exception_type_error(),
+            format!(
+                "can't convert {0} to Proc ({0}#to_proc gives {1})",
+                unsafe { val.classname() },
+                unsafe { res.classname() },
+            ),
+        )
+    })
+}
+
+type Converter<T> = fn(Value) -> Result<T, Error>;
+
+struct ConverterRegistry(UnsafeCell<Vec<(TypeId, VALUE, Box<dyn Any>)>>);
+
+// The registry is only accessed from Ruby threads, while holding the GVL, and
+// never while calling Ruby or a registered converter.
+unsafe impl Sync for ConverterRegistry {}
+
+static CONVERTERS: ConverterRegistry = ConverterRegistry(UnsafeCell::new(Vec::new()));
+
+/// Register `func` to convert instances of `class` (or its subclasses) to
+/// `T` with [`dynamic`].
+///
+/// This allows conversions to Rust types where implementing [`TryConvert`]
+/// is not possible, such as a type from another crate, and conversions
+/// provided by plugins at runtime.
+///
+/// Registering a converter for a `class` and `T` that already has a
+/// converter replaces the existing converter.
+///
+/// `class` will never be garbage collected.
+///
+/// # Examples
+///
+/// ```
+/// use std::net::Ipv4Addr;
+///
+/// use magnus::{prelude::*, try_convert, Error, RClass, Ruby, Value};
+///
+/// fn ipaddr_to_ipv4(val: Value) -> Result<Ipv4Addr, Error> {
+///     let s: String = val.funcall("to_s", ())?;
+///     s.parse().map_err(|e| {
+///         Error::new(
+///             Ruby::get_with(val).exception_arg_error(),
+///             format!("{}", e),
+///         )
+///     })
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.require("ipaddr")?;
+///     let class: RClass = ruby.class_object().const_get("IPAddr")?;
+///     try_convert::register_converter(class, ipaddr_to_ipv4);
+///
+///     let val: Value = ruby.eval(r#"IPAddr.new("127.0.0.1")"#)?;
+///     let addr: Ipv4Addr = try_convert::dynamic(val)?;
+///     assert_eq!(addr, Ipv4Addr::LOCALHOST);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub fn register_converter<T>(class: RClass, func: Converter<T>)
+where
+    T: 'static,
+{
+    let type_id = TypeId::of::<T>();
+    let raw = class.as_rb_value();
+    let converters = unsafe { &mut *CONVERTERS.0.get() };
+    match converters
+        .iter_mut()
+        .find(|(t, c, _)| *t == type_id && *c == raw)
+    {
+        Some(entry) => entry.2 = Box::new(func),
+        None => {
+            gc::register_mark_object(class);
+            converters.push((type_id, raw, Box::new(func)));
+        }
+    }
+}
+
+/// Convert `val` to `T` with a converter registered with
+/// [`register_converter`].
+///
+/// The converter registered for the nearest class in `val`'s class hierarchy
+/// is used. Errors if no converter to `T` has been registered for `val`'s
+/// class or any of its superclasses.
+///
+/// See [`register_converter`] for examples.
+pub fn dynamic<T>(val: Value) -> Result<T, Error>
+where
+    T: 'static,
+{
+    let type_id = TypeId::of::<T>();
+    let mut next = Some(val.class());
+    while let Some(class) = next {
+        let raw = class.as_rb_value();
+        let func = unsafe { &*CONVERTERS.0.get() }
+            .iter()
+            .find(|(t, c, _)| *t == type_id && *c == raw)
+            .and_then(|(_, _, f)| f.downcast_ref::<Converter<T>>().copied());
+        if let Some(func) = func {
+            return func(val);
+        }
+        next = class.superclass()?;
+    }
+    Err(Error::new(
+        Ruby::get_with(val).exception_type_error(),
+        format!(
+            "no converter registered for {} into {}",
+            unsafe { val.classname() },
+            type_name::<T>(),
+        ),
+    ))
+}
+
 impl<T> TryConvert for Option<T>
 where
     T: TryConvert,
@@ -171,6 +448,15 @@ impl TryConvert for String {
 }
 unsafe impl TryConvertOwned for String {}
 
+impl TryConvert for OsString {
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        debug_assert_value!(val);
+        RString::try_convert(val)?.to_os_string()
+    }
+}
+unsafe impl TryConvertOwned for OsString {}
+
 #[cfg(feature = "bytes")]
 impl TryConvert for bytes::Bytes {
     #[inline]
@@ -183,6 +469,61 @@ impl TryConvert for bytes::Bytes {
 #[cfg(feature = "bytes")]
 unsafe impl TryConvertOwned for bytes::Bytes {}
 
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+impl TryConvert for uuid::Uuid {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        debug_assert_value!(val);
+        let r_string = RString::try_convert(val)?;
+        // safe as we don't hold on to the slice past the parse
+        uuid::Uuid::try_parse_ascii(unsafe { r_string.as_slice() }).map_err(|e| {
+            Error::new(
+                Ruby::get_with(val).exception_arg_error(),
+                format!("invalid UUID: {}", e),
+            )
+        })
+    }
+}
+
+#[cfg(feature = "uuid")]
+unsafe impl TryConvertOwned for uuid::Uuid {}
+
+#[cfg(feature = "url")]
+#[cfg_attr(docsrs, doc(cfg(feature = "url")))]
+impl TryConvert for url::Url {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        debug_assert_value!(val);
+        let ruby = Ruby::get_with(val);
+        let r_string = match RString::from_value(val) {
+            Some(r_string) => r_string,
+            None if is_uri(&ruby, val)? => val.funcall("to_s", ())?,
+            None => RString::try_convert(val)?,
+        };
+        // safe as we don't hold on to the str past the parse
+        let s = unsafe { r_string.as_str()? };
+        url::Url::parse(s)
+            .map_err(|e| Error::new(ruby.exception_arg_error(), format!("invalid URL: {}", e)))
+    }
+}
+
+#[cfg(feature = "url")]
+unsafe impl TryConvertOwned for url::Url {}
+
+/// Check if `val` is an instance of Ruby's `URI::Generic`, without loading
+/// the `uri` library if it isn't already loaded.
+#[cfg(feature = "url")]
+fn is_uri(ruby: &Ruby, val: Value) -> Result<bool, Error> {
+    use crate::module::{Module, RModule};
+
+    let defined: bool = ruby.class_object().funcall("const_defined?", ("URI",))?;
+    if !defined {
+        return Ok(false);
+    }
+    let uri: RModule = ruby.class_object().const_get("URI")?;
+    let generic: RClass = uri.const_get("Generic")?;
+    Ok(val.is_kind_of(generic))
+}
+
 impl TryConvert for char {
     #[inline]
     fn try_convert(val: Value) -> Result<Self, Error> {
@@ -269,6 +610,24 @@ where
 {
 }
 
+impl<K, V> TryConvert for std::collections::BTreeMap<K, V>
+where
+    K: TryConvertOwned + Ord,
+    V: TryConvertOwned,
+{
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        debug_assert_value!(val);
+        RHash::try_convert(val)?.to_btree_map()
+    }
+}
+unsafe impl<K, V> TryConvertOwned for std::collections::BTreeMap<K, V>
+where
+    K: TryConvertOwned + Ord,
+    V: TryConvertOwned,
+{
+}
+
 #[cfg(unix)]
 impl TryConvert for PathBuf {
     fn try_convert(val: Value) -> Result<Self, Error> {
@@ -293,3 +652,290 @@ impl TryConvert for PathBuf {
 }
 
 unsafe impl TryConvertOwned for PathBuf {}
+
+/// Conversions from [`Value`] to Rust types that do not use Ruby's implicit
+/// conversion protocols.
+///
+/// This is used by [`Strict`], see its documentation for details.
+pub trait TryConvertStrict: Sized {
+    /// Convert `val` into `Self`, without any implicit conversions.
+    fn try_convert_strict(val: Value) -> Result<Self, Error>;
+}
+
+/// Wrapper type to opt in to strict conversions.
+///
+/// The default [`TryConvert`] implementations follow Ruby's conversion
+/// protocols, for example a Ruby `Float` will convert to an [`i64`] with the
+/// fractional part truncated, as Ruby's `Float` implements `to_int`.
+///
+/// `Strict<T>` will only convert values that are already of the expected
+/// Ruby type:
+///
+/// * Integer types only accept Ruby `Integer`s.
+/// * [`f32`] and [`f64`] accept Ruby `Float`s and `Integer`s.
+/// * [`String`] only accepts Ruby `String`s, not objects implementing
+///   `to_str`.
+/// * [`bool`] only accepts `true` and `false`.
+/// * [`Option<T>`] accepts `nil`, or values accepted by `Strict<T>`.
+///
+/// Anything else results in a `TypeError`. As the method macros convert
+/// arguments with [`TryConvert`], strict conversion can be selected per
+/// argument by using `Strict<T>` as the argument type.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, prelude::*, try_convert::Strict, Error, Ruby};
+///
+/// fn double(value: Strict<i64>) -> i64 {
+///     value.into_inner() * 2
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("double", function!(double, 1));
+///
+///     assert_eq!(ruby.eval::<i64>("double(21)")?, 42);
+///     assert!(ruby.eval::<i64>("double(21.5)").is_err());
+///     assert!(ruby.eval::<i64>("double(nil)").is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    /// Consume `self`, returning the wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> std::ops::Deref for Strict<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> TryConvert for Strict<T>
+where
+    T: TryConvertStrict,
+{
+    #[inline]
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        T::try_convert_strict(val).map(Strict)
+    }
+}
+
+unsafe impl<T> TryConvertOwned for Strict<T> where T: TryConvertStrict + TryConvertOwned {}
+
+fn strict_type_error(val: Value, type_name: &str) -> Error {
+    Error::new(
+        Ruby::get_with(val).exception_type_error(),
+        format!(
+            "no implicit conversion of {} into {}",
+            unsafe { val.classname() },
+            type_name
+        ),
+    )
+}
+
+impl<T> TryConvertStrict for Option<T>
+where
+    T: TryConvertStrict,
+{
+    #[inline]
+    fn try_convert_strict(val: Value) -> Result<Self, Error> {
+        (!val.is_nil())
+            .then(|| T::try_convert_strict(val))
+            .transpose()
+    }
+}
+
+impl TryConvertStrict for bool {
+    fn try_convert_strict(val: Value) -> Result<Self, Error> {
+        if Qtrue::from_value(val).is_some() {
+            Ok(true)
+        } else if Qfalse::from_value(val).is_some() {
+            Ok(false)
+        } else {
+            Err(strict_type_error(val, "true or false"))
+        }
+    }
+}
+
+macro_rules! impl_try_convert_strict_int {
+    ($($t:ty => $to:ident),* $(,)?) => {
+        $(
+            impl TryConvertStrict for $t {
+                fn try_convert_strict(val: Value) -> Result<Self, Error> {
+                    Integer::from_value(val)
+                        .ok_or_else(|| strict_type_error(val, "Integer"))?
+                        .$to()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_convert_strict_int!(
+    i8 => to_i8,
+    i16 => to_i16,
+    i32 => to_i32,
+    i64 => to_i64,
+    isize => to_isize,
+    u8 => to_u8,
+    u16 => to_u16,
+    u32 => to_u32,
+    u64 => to_u64,
+    usize => to_usize,
+);
+
+impl TryConvertStrict for f32 {
+    fn try_convert_strict(val: Value) -> Result<Self, Error> {
+        f64::try_convert_strict(val).map(|f| f as f32)
+    }
+}
+
+impl TryConvertStrict for f64 {
+    fn try_convert_strict(val: Value) -> Result<Self, Error> {
+        if Float::from_value(val).is_none() && Integer::from_value(val).is_none() {
+            return Err(strict_type_error(val, "Float"));
+        }
+        f64::try_convert(val)
+    }
+}
+
+impl TryConvertStrict for String {
+    fn try_convert_strict(val: Value) -> Result<Self, Error> {
+        RString::from_value(val)
+            .ok_or_else(|| strict_type_error(val, "String"))?
+            .to_string()
+    }
+}
+
+/// Wrapper type to convert a [`char`] to and from a Ruby `Integer`
+/// codepoint.
+///
+/// By default a `char` converts to and from a single character Ruby
+/// `String`. `Codepoint` instead converts from an `Integer` (as returned by
+/// Ruby's `String#ord`), and to an `Integer`.
+///
+/// Converting from anything other than an `Integer` results in a
+/// `TypeError`, and from an `Integer` that isn't a valid Unicode scalar value
+/// in a `RangeError`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, prelude::*, try_convert::Codepoint, Error, Ruby};
+///
+/// fn next_char(c: Codepoint) -> Codepoint {
+///     Codepoint(char::from_u32(c.0 as u32 + 1).unwrap_or(c.0))
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("next_char", function!(next_char, 1));
+///
+///     assert_eq!(ruby.eval::<i64>("next_char('a'.ord)")?, 98);
+///     assert!(ruby.eval::<i64>("next_char('a')").is_err());
+///     assert!(ruby.eval::<i64>("next_char(0xD800)").is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Codepoint(pub char);
+
+impl TryConvert for Codepoint {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        let codepoint = Integer::from_value(val)
+            .ok_or_else(|| strict_type_error(val, "Integer"))?
+            .to_u32()?;
+        char::from_u32(codepoint).map(Codepoint).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_range_error(),
+                format!("invalid codepoint 0x{:X}", codepoint),
+            )
+        })
+    }
+}
+unsafe impl TryConvertOwned for Codepoint {}
+
+impl IntoValue for Codepoint {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        (self.0 as u32).into_value_with(handle)
+    }
+}
+
+unsafe impl IntoValueFromNative for Codepoint {}
+
+/// Wrapper type to convert a fixed size byte array to and from a Ruby
+/// `String`.
+///
+/// By default a `[u8; N]` converts from a Ruby `Array` of `Integer`s.
+/// `ByteArray<N>` instead converts from a `String` of exactly `N` bytes, in
+/// any encoding, and to a binary (ASCII-8BIT) `String`. This is useful for
+/// protocols with fixed size fields, such as UUIDs or hashes.
+///
+/// Converting from anything other than a `String` results in a `TypeError`,
+/// and from a `String` of the wrong length in an `ArgumentError`.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{function, prelude::*, try_convert::ByteArray, Error, Ruby};
+///
+/// fn reverse_id(id: ByteArray<4>) -> ByteArray<4> {
+///     let mut bytes = id.0;
+///     bytes.reverse();
+///     ByteArray(bytes)
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_global_function("reverse_id", function!(reverse_id, 1));
+///
+///     assert_eq!(
+///         ruby.eval::<String>(r#"reverse_id("\x00\x01\x02\x03".b).unpack1("H*")"#)?,
+///         "03020100"
+///     );
+///     assert!(ruby.eval::<String>(r#"reverse_id("abc")"#).is_err());
+///     assert!(ruby.eval::<String>("reverse_id([0, 1, 2, 3])").is_err());
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteArray<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> TryConvert for ByteArray<N> {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        let s = RString::from_value(val).ok_or_else(|| strict_type_error(val, "String"))?;
+        // no Ruby calls while the slice is live, so it can't be modified
+        let slice = unsafe { s.as_slice() };
+        slice.try_into().map(ByteArray).map_err(|_| {
+            Error::new(
+                Ruby::get_with(val).exception_arg_error(),
+                format!("expected {} bytes, got {}", N, slice.len()),
+            )
+        })
+    }
+}
+unsafe impl<const N: usize> TryConvertOwned for ByteArray<N> {}
+
+impl<const N: usize> IntoValue for ByteArray<N> {
+    #[inline]
+    fn into_value_with(self, handle: &Ruby) -> Value {
+        handle.str_from_slice(&self.0).into_value_with(handle)
+    }
+}
+
+unsafe impl<const N: usize> IntoValueFromNative for ByteArray<N> {}
@@ -11,16 +11,19 @@ use std::{
     marker::PhantomData,
     mem::size_of_val,
     ops::Deref,
+    os::raw::c_int,
     panic::catch_unwind,
     ptr,
+    sync::Arc,
 };
 
 #[cfg(ruby_gte_3_0)]
 use rb_sys::rbimpl_typeddata_flags::{self, RUBY_TYPED_FREE_IMMEDIATELY, RUBY_TYPED_WB_PROTECTED};
 use rb_sys::{
-    self, rb_data_type_struct__bindgen_ty_1, rb_data_type_t, rb_gc_writebarrier,
-    rb_gc_writebarrier_unprotect, rb_obj_reveal, rb_singleton_class_attached,
-    rb_singleton_class_clone, size_t, VALUE,
+    self, rb_call_super_kw, rb_data_type_struct__bindgen_ty_1, rb_data_type_t,
+    rb_funcall_passing_block_kw, rb_gc_writebarrier, rb_gc_writebarrier_unprotect,
+    rb_keyword_given_p, rb_obj_reveal, rb_singleton_class_attached, rb_singleton_class_clone,
+    size_t, VALUE,
 };
 
 #[cfg(ruby_lt_3_0)]
@@ -30,17 +33,23 @@ const RUBY_TYPED_FREE_IMMEDIATELY: u32 = 1;
 const RUBY_TYPED_WB_PROTECTED: u32 = rb_sys::ruby_fl_type::RUBY_FL_WB_PROTECTED as u32;
 
 use crate::{
-    class::RClass,
-    error::{bug_from_panic, Error},
+    block::block_call_closure,
+    class::{Class, RClass},
+    error::{bug_from_panic, protect, Error},
     gc::{self, Mark},
     into_value::IntoValue,
+    method::{Method0, Method1, Method2, MethodCAry},
+    module::{Module, RModule},
     object::Object,
+    r_hash::RHash,
+    r_string::RString,
     r_typed_data::RTypedData,
     scan_args::{get_kwargs, scan_args},
+    symbol::Symbol,
     try_convert::TryConvert,
     value::{
         private::{self, ReprValue as _},
-        ReprValue, Value,
+        IntoId, Lazy, LazyId, ReprValue, Value,
     },
     Ruby,
 };
@@ -104,6 +113,12 @@ where
     /// If your type contains any Ruby values you must mark each of those
     /// values in this function to avoid them being garbage collected.
     ///
+    /// Ruby's API must not be called during marking, other than through the
+    /// functions provided by `marker`. `marker` can only be obtained in this
+    /// function and can not be sent to another thread, but it does not
+    /// prevent other Ruby APIs from being called, so care must be taken not
+    /// to call functions that may allocate or raise.
+    ///
     /// This function is only called when the `mark` flag is set with the
     /// [`wrap`](macro@crate::wrap)/[`TypedData`](macro@crate::TypedData)
     /// macro or [`DataTypeBuilder::mark`].
@@ -498,6 +513,14 @@ where
     }
 }
 
+/// Converts a Ruby object wrapping `T` to a reference to the wrapped data.
+///
+/// There is no conversion to `&mut T`, so wrapped methods can't take
+/// `&mut self`. Ruby code can hold any number of references to the same
+/// object, and any of them may call back in to Rust while a reference to the
+/// wrapped data is live, so a `&mut T` could not be guaranteed to be unique.
+/// Use interior mutability, such as a [`RefCell`](std::cell::RefCell), for
+/// wrapped data that needs to be updated.
 impl<T> TryConvert for &T
 where
     T: TypedData,
@@ -531,6 +554,127 @@ where
     }
 }
 
+// The DataType for `Arc<T>`. This is an associated const so it can be generic
+// over `T`, which a `static` can't be. `T`'s DataType isn't available at
+// compile time, so this can't copy its flags, and doesn't set any of the flags
+// that are optional for soundness.
+struct SharedDataType<T>(PhantomData<T>);
+
+impl<T> SharedDataType<T>
+where
+    T: DataTypeFunctions + Sync,
+{
+    const DATA_TYPE: DataType =
+        DataTypeBuilder::<Arc<T>>::new(unsafe { CStr::from_bytes_with_nul_unchecked(b"Arc\0") })
+            .mark()
+            .size()
+            .compact()
+            .build();
+}
+
+// Maps the address of the `T` in an `Arc<T>` to the Ruby object wrapping it.
+static SHARED_OBJECTS: Lazy<Value> = Lazy::new(new_weak_map);
+
+impl<T> DataTypeFunctions for Arc<T>
+where
+    T: DataTypeFunctions + Sync,
+{
+    fn free(self: Box<Self>) {
+        if let Ok(inner) = Arc::try_unwrap(*self) {
+            T::free(Box::new(inner));
+        }
+    }
+
+    fn mark(&self, marker: &gc::Marker) {
+        T::mark(&**self, marker);
+    }
+
+    fn size(&self) -> usize {
+        T::size(&**self)
+    }
+
+    fn compact(&self, compactor: &gc::Compactor) {
+        T::compact(&**self, compactor);
+    }
+}
+
+/// Wrapping an `Arc<T>` allows a `T` to be shared between Ruby and Rust.
+///
+/// The wrapping Ruby object will be an instance of the same class as for `T`,
+/// but an object wrapping an `Arc<T>` can only be converted to an `Arc<T>`
+/// (or `&Arc<T>`, or `Obj<Arc<T>>`), and not `&T`.
+///
+/// Use [`Ruby::obj_wrap_shared`] to wrap an `Arc<T>`, which returns the
+/// existing Ruby object if the `T` has already been wrapped, so the `T` is
+/// marked once per garbage collection. Wrapping an `Arc<T>` any other way,
+/// e.g. with [`Ruby::obj_wrap`] or [`IntoValue`], always creates a new Ruby
+/// object.
+///
+/// # GC Safety
+///
+/// Ruby values held by `T` are marked only while a Ruby object wrapping the
+/// `Arc<T>` is alive. Once those Ruby objects have been garbage collected,
+/// Ruby values held by a `T` kept alive by `Arc`s on the Rust side are no
+/// longer marked, and may be freed. Ruby values that need to outlive the Ruby
+/// wrapper should be held in a [`BoxValue`](crate::value::BoxValue), or
+/// registered with [`gc::register_mark_object`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use magnus::{prelude::*, Error, Ruby, TryConvert};
+///
+/// #[magnus::wrap(class = "Counter", free_immediately)]
+/// struct Counter {
+///     count: std::sync::atomic::AtomicUsize,
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_class("Counter", ruby.class_object())?;
+///
+///     let counter = Arc::new(Counter {
+///         count: Default::default(),
+///     });
+///     let a = ruby.obj_wrap_shared(counter.clone())?;
+///     let b = ruby.obj_wrap_shared(counter.clone())?;
+///     assert!(a.equal(b)?);
+///
+///     let shared = Arc::<Counter>::try_convert(a.as_value())?;
+///     assert!(Arc::ptr_eq(&shared, &counter));
+///     assert!(Arc::ptr_eq(&*b, &counter));
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+unsafe impl<T> TypedData for Arc<T>
+where
+    T: TypedData + DataTypeFunctions + Sync,
+{
+    fn class(ruby: &Ruby) -> RClass {
+        T::class(ruby)
+    }
+
+    fn data_type() -> &'static DataType {
+        &SharedDataType::<T>::DATA_TYPE
+    }
+
+    fn class_for(ruby: &Ruby, value: &Self) -> RClass {
+        T::class_for(ruby, value)
+    }
+}
+
+impl<T> TryConvert for Arc<T>
+where
+    T: TypedData + DataTypeFunctions + Sync,
+{
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        <&Self>::try_convert(val).map(Arc::clone)
+    }
+}
+
 /// A Ruby Object wrapping a Rust type `T`.
 ///
 /// This is a Value pointer to a RTypedData struct, Ruby’s internal
@@ -685,6 +829,184 @@ impl Ruby {
             phantom: PhantomData,
         }
     }
+
+    /// Wrap a shared `Arc<T>` in a Ruby object.
+    ///
+    /// If the `T` is already wrapped by a live Ruby object that object is
+    /// returned, otherwise a new Ruby object is created. The `T` will be
+    /// dropped once the Ruby object and all other `Arc`s referencing it are
+    /// dropped.
+    ///
+    /// See the [`TypedData`] implementation for `Arc<T>` for restrictions on
+    /// Ruby values held by `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let point_class = ruby.define_class("Point", ruby.class_object())?;
+    ///
+    ///     let point = Arc::new(Point { x: 4, y: 2 });
+    ///     let value = ruby.obj_wrap_shared(point.clone())?;
+    ///     assert!(value.is_kind_of(point_class));
+    ///     assert_eq!((value.x, value.y), (4, 2));
+    ///     assert_eq!(Arc::strong_count(&point), 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap();
+    /// ```
+    pub fn obj_wrap_shared<T>(&self, data: Arc<T>) -> Result<Obj<Arc<T>>, Error>
+    where
+        T: TypedData + DataTypeFunctions + Sync,
+    {
+        // the Ruby object holds an `Arc`, so while it's alive the address
+        // can't be reused by another `T`
+        let key = identity_key(self, Arc::as_ptr(&data));
+        let map = self.get_inner(&SHARED_OBJECTS);
+        if let Some(obj) = map.funcall::<_, _, Option<Obj<Arc<T>>>>("[]", (key,))? {
+            return Ok(obj);
+        }
+        let obj = self.obj_wrap(data);
+        map.funcall::<_, _, Value>("[]=", (key, obj))?;
+        Ok(obj)
+    }
+
+    /// Register the [`DataType`] of `T` in a registry shared by all
+    /// libraries loaded in to this Ruby process.
+    ///
+    /// Errors if a different `DataType` has already been registered with the
+    /// same name. This happens when two libraries wrap types under the same
+    /// name, or the same library has been loaded twice from different paths.
+    /// Wrapping two different types as the same Ruby class would otherwise
+    /// result in confusing type errors or crashes.
+    ///
+    /// Registering the same `DataType` again is not an error.
+    ///
+    /// Only libraries that also register their types can be detected. See
+    /// [`Ruby::define_wrapped_class`] to register a type while defining its
+    /// class.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// mod other {
+    ///     #[magnus::wrap(class = "Point")]
+    ///     pub struct Point {
+    ///         pub x: f64,
+    ///         pub y: f64,
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_class("Point", ruby.class_object())?;
+    ///     ruby.register_data_type::<Point>()?;
+    ///     ruby.register_data_type::<Point>()?;
+    ///
+    ///     let err = ruby.register_data_type::<other::Point>().unwrap_err();
+    ///     assert!(err.is_kind_of(ruby.exception_runtime_error()));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap();
+    /// ```
+    pub fn register_data_type<T>(&self) -> Result<(), Error>
+    where
+        T: TypedData,
+    {
+        // an instance variable without a leading `@` is hidden from Ruby
+        static REGISTRY: LazyId = LazyId::new("__magnus_data_types__");
+
+        let data_type = T::data_type();
+        let name = unsafe { CStr::from_ptr(data_type.as_rb_data_type().wrap_struct_name) }
+            .to_string_lossy();
+        // each library, and each copy of a library, has its own `DataType`
+        // static, so its address identifies the library that registered it
+        let address = data_type as *const DataType as usize;
+
+        let object = self.class_object();
+        let registry = match object.ivar_get::<_, Option<RHash>>(*REGISTRY)? {
+            Some(registry) => registry,
+            None => {
+                let registry = self.hash_new();
+                object.ivar_set(*REGISTRY, registry)?;
+                registry
+            }
+        };
+        match registry.get_str(&name) {
+            None => registry.aset_str(&name, address),
+            Some(val) if usize::try_convert(val)? == address => Ok(()),
+            Some(_) => Err(Error::new(
+                self.exception_runtime_error(),
+                format!(
+                    "data type `{}` is already registered by another library, \
+                     or another copy of this library",
+                    name
+                ),
+            )),
+        }
+    }
+
+    /// Define a class in the root scope for the wrapped type `T`, registering
+    /// its [`DataType`] with [`Ruby::register_data_type`].
+    ///
+    /// Errors if a different `DataType` has already been registered with the
+    /// same name, or for the same reasons as [`Ruby::define_class`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// mod other {
+    ///     #[magnus::wrap(class = "Point")]
+    ///     pub struct Point {
+    ///         pub x: f64,
+    ///         pub y: f64,
+    ///     }
+    /// }
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_wrapped_class::<Point>("Point", ruby.class_object())?;
+    ///
+    ///     let res = ruby.define_wrapped_class::<other::Point>("Point", ruby.class_object());
+    ///     assert!(res.is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap();
+    /// ```
+    pub fn define_wrapped_class<T>(&self, name: &str, superclass: RClass) -> Result<RClass, Error>
+    where
+        T: TypedData,
+    {
+        self.class_object()
+            .define_wrapped_class::<T>(name, superclass)
+    }
 }
 
 impl<T> Obj<T>
@@ -1453,6 +1775,112 @@ where
     }
 }
 
+/// Trait for a Ruby-compatible `#pretty_print` method, as used by Ruby's
+/// `pp`.
+///
+/// Automatically implemented for any type implementing [`Debug`].
+///
+/// If the [`Debug`] output fits within the line width of the `PP` object
+/// it is written on one line, otherwise the alternate (`{:#?}`) output is
+/// written line by line, indented relative to the surrounding output, so
+/// nested Rust structures are readable.
+///
+/// See also [`Inspect`].
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{method, prelude::*, rb_assert, typed_data, Error, Ruby};
+///
+/// #[derive(Debug)]
+/// #[magnus::wrap(class = "Config")]
+/// struct Config {
+///     name: String,
+///     servers: Vec<String>,
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Config", ruby.class_object())?;
+///     class.define_method(
+///         "pretty_print",
+///         method!(<Config as typed_data::PrettyPrint>::pretty_print, 1),
+///     )?;
+///
+///     let config = Config {
+///         name: String::from("example"),
+///         servers: vec![String::from("a"), String::from("b")],
+///     };
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         require "pp"
+///         PP.pp(config, +"") == %(Config { name: "example", servers: ["a", "b"] }\n)
+///         "#,
+///         config
+///     );
+///
+///     let config = Config {
+///         name: String::from("example"),
+///         servers: vec![String::from("a"), String::from("b")],
+///     };
+///     rb_assert!(
+///         ruby,
+///         r#"
+///         PP.pp([config], +"", 20) == <<~OUT
+///           [Config {
+///                name: "example",
+///                servers: [
+///                    "a",
+///                    "b",
+///                ],
+///            }]
+///         OUT
+///         "#,
+///         config
+///     );
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait PrettyPrint {
+    // Docs at trait level.
+    #![allow(missing_docs)]
+    fn pretty_print(&self, pp: Value) -> Result<(), Error>;
+}
+
+impl<T> PrettyPrint for T
+where
+    T: fmt::Debug,
+{
+    fn pretty_print(&self, pp: Value) -> Result<(), Error> {
+        let compact = format!("{:?}", self);
+        let maxwidth: usize = pp.funcall("maxwidth", ())?;
+        let indent: usize = pp.funcall("indent", ())?;
+        if indent + compact.len() <= maxwidth {
+            let _: Value = pp.funcall("text", (compact,))?;
+            return Ok(());
+        }
+        let pretty = format!("{:#?}", self);
+        let _: Value = block_call_closure(pp, "group", (), |_, _, _| -> Result<(), Error> {
+            for (i, line) in pretty.lines().enumerate() {
+                let text = line.trim_start();
+                if i > 0 {
+                    // newline, indented by the line's leading whitespace
+                    // relative to the current indentation level
+                    let nest = line.len() - text.len();
+                    let _: Value = block_call_closure(pp, "nest", (nest,), |_, _, _| {
+                        pp.funcall::<_, _, Value>("breakable", ("",))
+                    })?;
+                }
+                let _: Value = pp.funcall("text", (text,))?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
+
 /// Trait for a Ruby-compatible `#dup` and `#clone` methods.
 ///
 /// Automatically implemented for any type implementing [`Clone`].
@@ -1541,3 +1969,454 @@ where
         Ok(clone)
     }
 }
+
+/// Trait for forwarding unknown methods to a wrapped Ruby object, similar to
+/// Ruby's `SimpleDelegator`.
+///
+/// Implement [`__getobj__`](Delegate::__getobj__) to return the object to
+/// delegate to, then call
+/// [`define_delegate_methods`](Delegate::define_delegate_methods) to define
+/// `__getobj__`, `method_missing`, `respond_to_missing?`, `==`, `to_s`, and
+/// `inspect` on the class. The individual methods can also be bound with
+/// [`method`](crate::method!) if only some are required.
+///
+/// See also [`Dup`], [`Inspect`], [`IsEql`], [`typed_data::Cmp`](Cmp), and
+/// [`typed_data::Hash`](Hash).
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{
+///     function, gc, prelude::*, rb_assert, typed_data, value::Opaque, DataTypeFunctions, Error,
+///     Ruby, TypedData, Value,
+/// };
+///
+/// #[derive(TypedData)]
+/// #[magnus(class = "Decorator", free_immediately, mark)]
+/// struct Decorator {
+///     inner: Opaque<Value>,
+/// }
+///
+/// impl Decorator {
+///     fn new(inner: Value) -> Self {
+///         Self {
+///             inner: inner.into(),
+///         }
+///     }
+/// }
+///
+/// impl DataTypeFunctions for Decorator {
+///     fn mark(&self, marker: &gc::Marker) {
+///         marker.mark(self.inner);
+///     }
+/// }
+///
+/// impl typed_data::Delegate for Decorator {
+///     fn __getobj__(&self) -> Value {
+///         Ruby::get().unwrap().get_inner(self.inner)
+///     }
+/// }
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let class = ruby.define_class("Decorator", ruby.class_object())?;
+///     class.define_singleton_method("new", function!(Decorator::new, 1))?;
+///     <Decorator as typed_data::Delegate>::define_delegate_methods(class)?;
+///
+///     let decorator = Decorator::new(ruby.str_new("foo").as_value());
+///     rb_assert!(ruby, r#"decorator.upcase == "FOO""#, decorator);
+///     rb_assert!(ruby, "decorator.respond_to?(:upcase)", decorator);
+///     rb_assert!(ruby, "!decorator.respond_to?(:keys)", decorator);
+///     rb_assert!(ruby, r#"decorator == "foo""#, decorator);
+///     rb_assert!(ruby, r#"decorator.inspect == "\"foo\"""#, decorator);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub trait Delegate: TypedData + Sized {
+    /// Returns the object that unknown methods are forwarded to.
+    fn __getobj__(&self) -> Value;
+
+    /// Ruby's `method_missing`.
+    ///
+    /// Forwards the call, including any block and keywords, to the object
+    /// returned by [`__getobj__`](Delegate::__getobj__) if it responds to
+    /// the method, otherwise calls `super`.
+    fn method_missing(rbself: Obj<Self>, args: &[Value]) -> Result<Value, Error> {
+        let ruby = Ruby::get_with(rbself);
+        let (name, rest) = args
+            .split_first()
+            .ok_or_else(|| Error::new(ruby.exception_arg_error(), "no method name given"))?;
+        let name = Symbol::try_convert(*name)?;
+        let kw_splat = unsafe { rb_keyword_given_p() };
+        let target = rbself.__getobj__();
+        if target.respond_to(name, false)? {
+            let id = name.into_id_with(&ruby);
+            protect(|| unsafe {
+                Value::new(rb_funcall_passing_block_kw(
+                    target.as_rb_value(),
+                    id.as_rb_id(),
+                    rest.len() as c_int,
+                    rest.as_ptr() as *const VALUE,
+                    kw_splat,
+                ))
+            })
+        } else {
+            protect(|| unsafe {
+                Value::new(rb_call_super_kw(
+                    args.len() as c_int,
+                    args.as_ptr() as *const VALUE,
+                    kw_splat,
+                ))
+            })
+        }
+    }
+
+    /// Ruby's `respond_to_missing?`.
+    fn respond_to_missing(&self, name: Symbol, include_private: bool) -> Result<bool, Error> {
+        self.__getobj__().respond_to(name, include_private)
+    }
+
+    /// Ruby's `==`.
+    ///
+    /// Returns `true` if `other` is the same object as `rbself`, otherwise
+    /// compares `other` with the object returned by
+    /// [`__getobj__`](Delegate::__getobj__).
+    fn eq(rbself: Obj<Self>, other: Value) -> Result<bool, Error> {
+        if rbself.as_rb_value() == other.as_rb_value() {
+            return Ok(true);
+        }
+        rbself.__getobj__().equal(other)
+    }
+
+    /// Ruby's `to_s`, forwarded to [`__getobj__`](Delegate::__getobj__).
+    fn to_s(&self) -> Result<RString, Error> {
+        self.__getobj__().to_r_string()
+    }
+
+    /// Ruby's `inspect`, forwarded to [`__getobj__`](Delegate::__getobj__).
+    fn inspect(&self) -> Result<Value, Error> {
+        self.__getobj__().funcall("inspect", ())
+    }
+
+    /// Define the delegation methods on `module`.
+    ///
+    /// This defines `__getobj__`, `==`, `to_s`, and `inspect` as public
+    /// methods, and `method_missing` and `respond_to_missing?` as private
+    /// methods.
+    fn define_delegate_methods<T>(module: T) -> Result<(), Error>
+    where
+        T: Module,
+    {
+        module.define_method(
+            "__getobj__",
+            delegate_getobj::<Self> as unsafe extern "C" fn(Value) -> Value,
+        )?;
+        module.define_private_method(
+            "method_missing",
+            delegate_method_missing::<Self>
+                as unsafe extern "C" fn(c_int, *const Value, Value) -> Value,
+        )?;
+        module.define_private_method(
+            "respond_to_missing?",
+            delegate_respond_to_missing::<Self>
+                as unsafe extern "C" fn(Value, Value, Value) -> Value,
+        )?;
+        module.define_method(
+            "==",
+            delegate_eq::<Self> as unsafe extern "C" fn(Value, Value) -> Value,
+        )?;
+        module.define_method(
+            "to_s",
+            delegate_to_s::<Self> as unsafe extern "C" fn(Value) -> Value,
+        )?;
+        module.define_method(
+            "inspect",
+            delegate_inspect::<Self> as unsafe extern "C" fn(Value) -> Value,
+        )?;
+        Ok(())
+    }
+}
+
+unsafe extern "C" fn delegate_getobj<T>(rb_self: Value) -> Value
+where
+    T: Delegate,
+{
+    Method0::call_handle_error(|rbself: &T| rbself.__getobj__(), rb_self)
+}
+
+unsafe extern "C" fn delegate_method_missing<T>(
+    argc: c_int,
+    argv: *const Value,
+    rb_self: Value,
+) -> Value
+where
+    T: Delegate,
+{
+    MethodCAry::call_handle_error(<T as Delegate>::method_missing, argc, argv, rb_self)
+}
+
+unsafe extern "C" fn delegate_respond_to_missing<T>(
+    rb_self: Value,
+    name: Value,
+    include_private: Value,
+) -> Value
+where
+    T: Delegate,
+{
+    Method2::call_handle_error(
+        |rbself: &T, name: Symbol, include_private: bool| {
+            rbself.respond_to_missing(name, include_private)
+        },
+        rb_self,
+        name,
+        include_private,
+    )
+}
+
+unsafe extern "C" fn delegate_eq<T>(rb_self: Value, other: Value) -> Value
+where
+    T: Delegate,
+{
+    Method1::call_handle_error(<T as Delegate>::eq, rb_self, other)
+}
+
+unsafe extern "C" fn delegate_to_s<T>(rb_self: Value) -> Value
+where
+    T: Delegate,
+{
+    Method0::call_handle_error(|rbself: &T| Delegate::to_s(rbself), rb_self)
+}
+
+unsafe extern "C" fn delegate_inspect<T>(rb_self: Value) -> Value
+where
+    T: Delegate,
+{
+    Method0::call_handle_error(|rbself: &T| Delegate::inspect(rbself), rb_self)
+}
+
+/// A map from Rust pointers to the Ruby objects wrapping them.
+///
+/// This can be used to ensure that wrapping the same shared Rust value (e.g.
+/// the target of an [`Rc`](std::rc::Rc) or [`Arc`](std::sync::Arc)) multiple
+/// times results in the same Ruby object, rather than a new Ruby object each
+/// time. This is often needed when wrapping trees or graphs of Rust values.
+///
+/// Entries are weak references, and are removed once the Ruby object has been
+/// garbage collected. The wrapped `T` should keep the value pointed to alive
+/// (e.g. by holding a clone of the `Arc`), so the pointer can't be reused for
+/// another value while the Ruby object is still alive.
+///
+/// An `IdentityMap` is intended to be assigned to a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use magnus::{prelude::*, typed_data::IdentityMap, Error, Ruby};
+///
+/// struct Node {
+///     name: String,
+/// }
+///
+/// #[magnus::wrap(class = "Node")]
+/// struct RbNode(Arc<Node>);
+///
+/// static NODES: IdentityMap<RbNode> = IdentityMap::new();
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_class("Node", ruby.class_object())?;
+///
+///     let node = Arc::new(Node {
+///         name: String::from("root"),
+///     });
+///     let a = NODES.get_or_wrap(ruby, Arc::as_ptr(&node), || RbNode(node.clone()))?;
+///     let b = NODES.get_or_wrap(ruby, Arc::as_ptr(&node), || RbNode(node.clone()))?;
+///     assert!(a.equal(b)?);
+///     assert_eq!(b.0.name, "root");
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct IdentityMap<T> {
+    map: Lazy<Value>,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> IdentityMap<T>
+where
+    T: TypedData,
+{
+    /// Create a new `IdentityMap`.
+    ///
+    /// This function can be called in a `const` context. The underlying Ruby
+    /// object is created on first use, and is never garbage collected.
+    pub const fn new() -> Self {
+        Self {
+            map: Lazy::new(new_weak_map),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Get the Ruby object associated with `key`, if any.
+    pub fn get<K>(&self, ruby: &Ruby, key: *const K) -> Result<Option<Obj<T>>, Error>
+    where
+        K: ?Sized,
+    {
+        ruby.get_inner(&self.map)
+            .funcall("[]", (identity_key(ruby, key),))
+    }
+
+    /// Associate `obj` with `key`, replacing any existing entry.
+    pub fn insert<K>(&self, ruby: &Ruby, key: *const K, obj: Obj<T>) -> Result<(), Error>
+    where
+        K: ?Sized,
+    {
+        ruby.get_inner(&self.map)
+            .funcall::<_, _, Value>("[]=", (identity_key(ruby, key), obj))?;
+        Ok(())
+    }
+
+    /// Get the Ruby object associated with `key`, or wrap the result of
+    /// `func` and associate it with `key` if there is no existing entry.
+    pub fn get_or_wrap<K, F>(&self, ruby: &Ruby, key: *const K, func: F) -> Result<Obj<T>, Error>
+    where
+        K: ?Sized,
+        F: FnOnce() -> T,
+    {
+        if let Some(obj) = self.get(ruby, key)? {
+            return Ok(obj);
+        }
+        let obj = ruby.obj_wrap(func());
+        self.insert(ruby, key, obj)?;
+        Ok(obj)
+    }
+}
+
+impl<T> Default for IdentityMap<T>
+where
+    T: TypedData,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn new_weak_map(ruby: &Ruby) -> Value {
+    ruby.class_object()
+        .const_get::<_, RModule>("ObjectSpace")
+        .and_then(|object_space| object_space.const_get::<_, RClass>("WeakMap"))
+        .and_then(|class| class.new_instance(()))
+        .unwrap()
+}
+
+fn identity_key<K>(ruby: &Ruby, key: *const K) -> Value
+where
+    K: ?Sized,
+{
+    ruby.integer_from_u64(key as *const u8 as usize as u64)
+        .as_value()
+}
+
+/// Rust data attached to a class or module, rather than its instances.
+///
+/// The data is wrapped as a Ruby object (see [`Obj`]) and stored in an
+/// instance variable of the class, so is marked by the garbage collector
+/// for as long as the class is alive. This is useful for per-class
+/// configuration, such as registered serialisers or schema metadata.
+///
+/// `name` is the name of the instance variable used to store the data. If it
+/// does not begin with `@` it will be invisible to Ruby code. Class data is
+/// not inherited, each subclass has its own value, if any.
+///
+/// A `ClassData` is intended to be assigned to a `static`.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use magnus::{prelude::*, typed_data::ClassData, Error, Ruby};
+///
+/// #[magnus::wrap(class = "Schema")]
+/// struct Schema {
+///     fields: RefCell<Vec<String>>,
+/// }
+///
+/// static SCHEMA: ClassData<Schema> = ClassData::new("__schema__");
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     ruby.define_class("Schema", ruby.class_object())?;
+///     let class = ruby.define_class("Record", ruby.class_object())?;
+///
+///     assert!(SCHEMA.get(class)?.is_none());
+///
+///     let schema = SCHEMA.get_or_init(class, || Schema {
+///         fields: RefCell::new(Vec::new()),
+///     })?;
+///     schema.fields.borrow_mut().push(String::from("name"));
+///
+///     let schema = SCHEMA.get(class)?.unwrap();
+///     assert_eq!(*schema.fields.borrow(), ["name"]);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+pub struct ClassData<T> {
+    name: LazyId,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> ClassData<T>
+where
+    T: TypedData,
+{
+    /// Create a new `ClassData`, stored in the instance variable `name`.
+    ///
+    /// This function can be called in a `const` context.
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name: LazyId::new(name),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Get the data attached to `module`, if any.
+    ///
+    /// Errors if the instance variable is set to something other than a
+    /// wrapped `T`.
+    pub fn get<M>(&self, module: M) -> Result<Option<Obj<T>>, Error>
+    where
+        M: Module,
+    {
+        module.ivar_get(*self.name)
+    }
+
+    /// Attach `data` to `module`, replacing any existing data.
+    ///
+    /// Errors if `module` is frozen.
+    pub fn set<M>(&self, module: M, data: T) -> Result<Obj<T>, Error>
+    where
+        M: Module,
+    {
+        let obj = Ruby::get_with(module).obj_wrap(data);
+        module.ivar_set(*self.name, obj)?;
+        Ok(obj)
+    }
+
+    /// Get the data attached to `module`, or attach the result of `func` if
+    /// there is no existing data.
+    pub fn get_or_init<M, F>(&self, module: M, func: F) -> Result<Obj<T>, Error>
+    where
+        M: Module,
+        F: FnOnce() -> T,
+    {
+        if let Some(obj) = self.get(module)? {
+            return Ok(obj);
+        }
+        self.set(module, func())
+    }
+}
@@ -7,6 +7,7 @@ mod flonum;
 use std::{
     borrow::{Borrow, Cow},
     cell::UnsafeCell,
+    collections::HashSet,
     ffi::CStr,
     fmt,
     hash::{Hash, Hasher},
@@ -17,6 +18,7 @@ use std::{
     os::raw::{c_char, c_int, c_long, c_ulong},
     ptr,
     sync::Once,
+    time::Instant,
 };
 
 #[cfg(ruby_use_flonum)]
@@ -26,9 +28,12 @@ use rb_sys::{
     rb_check_symbol_cstr, rb_enumeratorize_with_size_kw, rb_eql, rb_equal,
     rb_funcall_with_block_kw, rb_funcallv_kw, rb_funcallv_public_kw, rb_gc_register_address,
     rb_gc_unregister_address, rb_hash, rb_id2name, rb_id2sym, rb_inspect, rb_intern3, rb_ll2inum,
-    rb_obj_as_string, rb_obj_classname, rb_obj_freeze, rb_obj_is_kind_of, rb_obj_respond_to,
-    rb_sym2id, rb_ull2inum, ruby_fl_type, ruby_special_consts, ruby_value_type, RBasic, ID, VALUE,
+    rb_obj_as_string, rb_obj_classname, rb_obj_freeze, rb_obj_id, rb_obj_is_kind_of,
+    rb_obj_respond_to, rb_sym2id, rb_typeddata_is_kind_of, rb_ull2inum, ruby_fl_type,
+    ruby_special_consts, ruby_value_type, RBasic, ID, VALUE,
 };
+#[cfg(ruby_gte_3_0)]
+use rb_sys::{rb_ractor_make_shareable, rb_ractor_shareable_p_continue};
 
 // These don't seem to appear consistently in bindgen output, not sure if they
 // aren't consistently defined in the headers or what. Lets just do it
@@ -37,28 +42,65 @@ const RUBY_FIXNUM_MAX: c_ulong = (c_long::MAX / 2) as c_ulong;
 const RUBY_FIXNUM_MIN: c_long = c_long::MIN / 2;
 
 use crate::{
-    block::Proc,
+    block::{block_call_closure, Proc},
     class::RClass,
     encoding::EncodingCapable,
     enumerator::Enumerator,
     error::{protect, Error},
+    exception::ExceptionClass,
     gc,
     integer::{Integer, IntegerType},
     into_value::{kw_splat, ArgList, IntoValue, IntoValueFromNative},
     method::{Block, BlockReturn},
-    module::Module,
+    module::{Module, RModule},
     numeric::Numeric,
+    r_array::RArray,
     r_bignum::RBignum,
+    r_hash::{ForEach, RHash},
     r_string::RString,
+    r_struct::RStruct,
     symbol::{IntoSymbol, Symbol},
+    trace,
     try_convert::{TryConvert, TryConvertOwned},
+    typed_data::TypedData,
     Ruby,
 };
 
+fn deep_freeze(val: Value, seen: &mut HashSet<VALUE>) -> Result<(), Error> {
+    if val.is_immediate() || !seen.insert(val.as_rb_value()) {
+        return Ok(());
+    }
+    val.freeze();
+    if let Some(ary) = RArray::from_value(val) {
+        for i in 0..ary.len() {
+            deep_freeze(ary.entry(i as isize)?, seen)?;
+        }
+    } else if let Some(hash) = RHash::from_value(val) {
+        hash.foreach(|k: Value, v: Value| {
+            deep_freeze(k, seen)?;
+            deep_freeze(v, seen)?;
+            Ok(ForEach::Continue)
+        })?;
+    } else if let Some(st) = RStruct::from_value(val) {
+        for i in 0..st.size() {
+            deep_freeze(st.get(i)?, seen)?;
+        }
+    }
+    Ok(())
+}
+
 /// Ruby's `VALUE` type, which can represent any Ruby object.
 ///
 /// Methods for `Value` are implemented on the [`ReprValue`] trait, which is
 /// also implemented for all Ruby types.
+///
+/// `Value` does not implement [`Eq`] or [`Hash`]. Most Ruby objects live on
+/// the heap, where Ruby's garbage collector may move them (changing the
+/// `VALUE`), and objects stored in Rust collections aren't seen by the
+/// garbage collector so may be freed. Ruby's own equality is also defined by
+/// methods that may be overridden, rather than identity. The immediate types
+/// [`Fixnum`], [`StaticSymbol`], [`Qnil`], [`Qtrue`], and [`Qfalse`] have a
+/// stable representation, so do implement these traits.
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Value(VALUE, PhantomData<*mut RBasic>);
@@ -69,6 +111,30 @@ impl Value {
         Self(val, PhantomData)
     }
 
+    /// Returns Ruby's `nil` value as a `Value`.
+    ///
+    /// `nil` is a constant, so unlike most Ruby values this doesn't require a
+    /// [`Ruby`] handle. It is equivalent to `ruby.qnil().as_value()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby, Value};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let val = Value::nil();
+    ///     assert!(val.is_nil());
+    ///     assert!(val.equal(ruby.qnil())?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[inline]
+    pub const fn nil() -> Self {
+        QNIL.0.get()
+    }
+
     #[inline]
     pub(crate) const fn as_rb_value(self) -> VALUE {
         self.0
@@ -187,7 +253,9 @@ unsafe impl IntoValueFromNative for usize {}
 impl IntoValue for f32 {
     #[inline]
     fn into_value_with(self, handle: &Ruby) -> Value {
-        handle.float_from_f64(self as f64).into_value_with(handle)
+        handle
+            .float_from_f64(f64::from(self))
+            .into_value_with(handle)
     }
 }
 
@@ -603,6 +671,85 @@ where
     }
 }
 
+/// Lazily create a Ruby value once per callsite, returning the same value on
+/// every subsequent call.
+///
+/// Each use of `cached!` expands to a [`Lazy`] `static`, so the value is
+/// created on first use and is never garbage collected. This avoids the cost
+/// of repeatedly creating strings or symbols, or looking up classes, in
+/// frequently called methods.
+///
+/// The following forms are supported:
+///
+/// * `cached!(ruby, str "literal")` - a frozen [`RString`](crate::RString).
+/// * `cached!(ruby, sym "literal")` - a [`StaticSymbol`].
+/// * `cached!(ruby, class "Path::To::Class")` - an
+///   [`RClass`](crate::RClass).
+/// * `cached!(ruby, module "Path::To::Module")` - an
+///   [`RModule`](crate::RModule).
+/// * `cached!(ruby, Type, |ruby| expr)` - any type implementing
+///   [`ReprValue`], initialised with the given function.
+///
+/// # Panics
+///
+/// The `class` and `module` forms panic if the constant can not be found, or
+/// is not of the expected type.
+///
+/// # Examples
+///
+/// ```
+/// use magnus::{cached, prelude::*, rb_assert, Error, RArray, Ruby};
+///
+/// fn example(ruby: &Ruby) -> Result<(), Error> {
+///     let a = cached!(ruby, str "example");
+///     let b = cached!(ruby, sym "example");
+///     let c = cached!(ruby, class "Encoding::Converter");
+///     let d = cached!(ruby, module "Comparable");
+///     rb_assert!(ruby, r#"a == "example" && a.frozen?"#, a);
+///     rb_assert!(ruby, "b == :example", b);
+///     rb_assert!(ruby, "c == Encoding::Converter", c);
+///     rb_assert!(ruby, "d == Comparable", d);
+///
+///     let strs: Vec<_> = (0..2).map(|_| cached!(ruby, str "same")).collect();
+///     assert!(strs[0].equal(strs[1])?);
+///
+///     let ary = cached!(ruby, RArray, |ruby| ruby.ary_from_vec(vec![1, 2, 3]));
+///     rb_assert!(ruby, "ary == [1, 2, 3]", ary);
+///
+///     Ok(())
+/// }
+/// # Ruby::init(example).unwrap()
+/// ```
+#[macro_export]
+macro_rules! cached {
+    ($ruby:expr, str $lit:expr) => {
+        $crate::cached!($ruby, $crate::RString, |ruby| {
+            $crate::RString::new_frozen(ruby.str_new($lit))
+        })
+    };
+    ($ruby:expr, sym $lit:expr) => {
+        $crate::cached!($ruby, $crate::value::StaticSymbol, |ruby| {
+            ruby.sym_new($lit)
+        })
+    };
+    ($ruby:expr, class $path:expr) => {
+        $crate::cached!($ruby, $crate::RClass, |ruby| {
+            $crate::value::ReprValue::funcall(ruby.class_object(), "const_get", ($path,))
+                .unwrap_or_else(|e| panic!("failed to get class {}: {}", $path, e))
+        })
+    };
+    ($ruby:expr, module $path:expr) => {
+        $crate::cached!($ruby, $crate::RModule, |ruby| {
+            $crate::value::ReprValue::funcall(ruby.class_object(), "const_get", ($path,))
+                .unwrap_or_else(|e| panic!("failed to get module {}: {}", $path, e))
+        })
+    };
+    ($ruby:expr, $ty:ty, $init:expr) => {{
+        static CACHED: $crate::value::Lazy<$ty> = $crate::value::Lazy::new($init);
+        $ruby.get_inner(&CACHED)
+    }};
+}
+
 pub(crate) mod private {
     use super::*;
     use crate::value::ReprValue as _;
@@ -957,6 +1104,88 @@ pub trait ReprValue: private::ReprValue {
         unsafe { protect(|| Integer::from_rb_value_unchecked(rb_hash(self.as_rb_value()))) }
     }
 
+    /// Returns whether `self` and `other` are the same object, as with
+    /// Ruby's `#equal?` method.
+    ///
+    /// Unlike [`Value::equal`] and [`Value::eql`] this never calls Ruby.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.str_new("test");
+    ///     let b = ruby.str_new("test");
+    ///     assert!(a.equal_id(a));
+    ///     assert!(!a.equal_id(b));
+    ///     assert!(ruby.to_symbol("test").equal_id(ruby.to_symbol("test")));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn equal_id<T>(self, other: T) -> bool
+    where
+        T: ReprValue,
+    {
+        self.as_rb_value() == other.as_rb_value()
+    }
+
+    /// Returns the object id of `self`, as with Ruby's `#object_id` method.
+    ///
+    /// The object id is unique for the lifetime of `self` and, unlike the
+    /// address of the object, is stable even if Ruby's garbage collector
+    /// moves `self`. Object ids may be reused once `self` has been garbage
+    /// collected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.str_new("test");
+    ///     let b = ruby.str_new("test");
+    ///     assert_eq!(a.object_id()?, a.object_id()?);
+    ///     assert_ne!(a.object_id()?, b.object_id()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn object_id(self) -> Result<Integer, Error> {
+        unsafe { protect(|| Integer::from_rb_value_unchecked(rb_obj_id(self.as_rb_value()))) }
+    }
+
+    /// Returns a hash of the identity of `self`.
+    ///
+    /// This is based on the object id of `self`, rather than its contents, so
+    /// can be used to key a Rust-side map by Ruby object identity. Objects
+    /// that are [`equal_id`](ReprValue::equal_id) will have the same identity
+    /// hash. The return value is stable even if Ruby's garbage collector
+    /// moves `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let a = ruby.str_new("test");
+    ///     let b = ruby.str_new("test");
+    ///     assert_eq!(a.hash()?, b.hash()?);
+    ///     assert_eq!(a.identity_hash()?, a.identity_hash()?);
+    ///     assert_ne!(a.identity_hash()?, b.identity_hash()?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn identity_hash(self) -> Result<Integer, Error> {
+        self.object_id()?.hash()
+    }
+
     /// Returns the class that `self` is an instance of.
     ///
     /// # Panics
@@ -1087,6 +1316,86 @@ pub trait ReprValue: private::ReprValue {
         unsafe { rb_obj_freeze(self.as_rb_value()) };
     }
 
+    /// Freeze `self` and, for Arrays, Hashes, and Structs, their contents,
+    /// recursively.
+    ///
+    /// Unlike [`make_shareable`](ReprValue::make_shareable) this is available
+    /// on all Ruby versions, and does not traverse the instance variables of
+    /// arbitrary objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, Ruby, Value};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let val: Value = ruby.eval(r#"{"a" => ["b", {c: "d"}]}"#)?;
+    ///     val.deep_freeze()?;
+    ///     rb_assert!(ruby, "val.frozen?", val);
+    ///     rb_assert!(ruby, r#"val["a"].frozen?"#, val);
+    ///     rb_assert!(ruby, r#"val["a"][0].frozen?"#, val);
+    ///     rb_assert!(ruby, r#"val["a"][1][:c].frozen?"#, val);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn deep_freeze(self) -> Result<(), Error> {
+        deep_freeze(self.as_value(), &mut HashSet::new())
+    }
+
+    /// Deeply freeze `self` and mark it as shareable between Ractors, as with
+    /// Ruby's `Ractor.make_shareable`.
+    ///
+    /// Errors if `self` contains an object that can not be made shareable,
+    /// such as a Proc that is not isolated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, rb_assert, Error, RArray, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let ary: RArray = ruby.eval(r#"["a", ["b"]]"#)?;
+    ///     assert!(!ary.is_shareable());
+    ///     let ary = ary.make_shareable()?;
+    ///     assert!(ary.is_shareable());
+    ///     rb_assert!(ruby, "ary[1][0].frozen?", ary);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_0, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+    fn make_shareable(self) -> Result<Self, Error> {
+        protect(|| unsafe { Value::new(rb_ractor_make_shareable(self.as_rb_value())) })?;
+        Ok(self)
+    }
+
+    /// Check if `self` is shareable between Ractors, as with Ruby's
+    /// `Ractor.shareable?`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(ruby.integer_from_i64(1).is_shareable());
+    ///     assert!(!ruby.str_new("foo").is_shareable());
+    ///     assert!(ruby.str_new("foo").make_shareable()?.is_shareable());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_0, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_0)))]
+    fn is_shareable(self) -> bool {
+        unsafe { rb_ractor_shareable_p_continue(self.as_rb_value()) }
+    }
+
     /// Convert `self` to a `bool`, following Ruby's rules of `false` and `nil`
     /// as boolean `false` and everything else boolean `true`.
     ///
@@ -1167,6 +1476,7 @@ pub trait ReprValue: private::ReprValue {
     {
         let handle = Ruby::get_with(self);
         let id = method.into_id_with(&handle);
+        let _span = trace::funcall(id);
         let kw_splat = kw_splat(&args);
         let args = args.into_arg_list_with(&handle);
         let slice = args.as_ref();
@@ -1233,6 +1543,7 @@ pub trait ReprValue: private::ReprValue {
     {
         let handle = Ruby::get_with(self);
         let id = method.into_id_with(&handle);
+        let _span = trace::funcall(id);
         let kw_splat = kw_splat(&args);
         let args = args.into_arg_list_with(&handle);
         let slice = args.as_ref();
@@ -1281,6 +1592,7 @@ pub trait ReprValue: private::ReprValue {
     {
         let handle = Ruby::get_with(self);
         let id = method.into_id_with(&handle);
+        let _span = trace::funcall(id);
         let kw_splat = kw_splat(&args);
         let args = args.into_arg_list_with(&handle);
         let slice = args.as_ref();
@@ -1302,6 +1614,40 @@ pub trait ReprValue: private::ReprValue {
         }
     }
 
+    /// Start a [`Chain`] of method calls on `self`.
+    ///
+    /// This allows navigating an object graph, e.g. the Ruby
+    /// `obj.foo.bar[0].baz`, with a single `Result` to handle at the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby, Value};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let config: Value =
+    ///         ruby.eval(r#"Struct.new(:db).new({hosts: ["a.example", "b.example"]})"#)?;
+    ///
+    ///     let host: String = config
+    ///         .chain()
+    ///         .call("db")
+    ///         .index(ruby.to_symbol("hosts"))
+    ///         .index(1)
+    ///         .call("upcase")
+    ///         .get()?;
+    ///     assert_eq!(host, "B.EXAMPLE");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn chain(self) -> Chain {
+        Chain {
+            result: Ok(self.as_value()),
+            nil_safe: false,
+        }
+    }
+
     /// Call the method named `method` on `self` with `args` and `block`.
     ///
     /// Similar to [`funcall`](Value::funcall), but passes `block` as a Ruby
@@ -1332,6 +1678,7 @@ pub trait ReprValue: private::ReprValue {
     {
         let handle = Ruby::get_with(self);
         let id = method.into_id_with(&handle);
+        let _span = trace::funcall(id);
         let kw_splat = kw_splat(&args);
         let args = args.into_arg_list_with(&handle);
         let slice = args.as_ref();
@@ -1414,6 +1761,7 @@ pub trait ReprValue: private::ReprValue {
 
         let handle = Ruby::get_with(self);
         let id = method.into_id_with(&handle);
+        let _span = trace::funcall(id);
         let kw_splat = kw_splat(&args);
         let args = args.into_arg_list_with(&handle);
         let slice = args.as_ref();
@@ -1435,6 +1783,117 @@ pub trait ReprValue: private::ReprValue {
         .and_then(TryConvert::try_convert)
     }
 
+    /// Run `func` as the block to Ruby's `instance_exec` on `self`, with
+    /// `args`.
+    ///
+    /// `func` is called with `self` and `args`, and runs with `self` as
+    /// Ruby's `self`. This allows DSL-style APIs to be implemented in Rust
+    /// without generating strings of Ruby code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby, TryConvert};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s = ruby.str_new("example");
+    ///     let res: String = s.instance_exec_closure((3,), |s, args| {
+    ///         let n = usize::try_convert(args[0])?;
+    ///         s.funcall::<_, _, String>("upcase", ()).map(|s| s[..n].to_owned())
+    ///     })?;
+    ///     assert_eq!(res, "EXA");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn instance_exec_closure<A, F, R, T>(self, args: A, mut func: F) -> Result<T, Error>
+    where
+        A: ArgList,
+        F: FnMut(Self, &[Value]) -> R,
+        R: BlockReturn,
+        T: TryConvert,
+    {
+        block_call_closure(self.as_value(), "instance_exec", args, |_, args, _| {
+            func(self, args)
+        })
+    }
+
+    /// Call the method named `method` on `self` with `args`, aborting the
+    /// call if it is still running at `deadline`.
+    ///
+    /// This is useful when calling back in to Ruby code you don't control
+    /// from a Rust system with a strict latency budget.
+    ///
+    /// The call is run with Ruby's `Timeout.timeout`, which interrupts the
+    /// calling thread with `Thread#raise` from a timer thread. If the deadline
+    /// passes the call returns an [`Error`] wrapping a `Timeout::Error`
+    /// exception, which can be checked for with
+    /// [`Error::is_kind_of`](Error::is_kind_of). If `deadline` has already
+    /// passed `method` is not called.
+    ///
+    /// Ruby can only process the interrupt while it is running Ruby code, so
+    /// a method that is blocked in a C extension, or in Rust, without
+    /// releasing the GVL will not be aborted until it returns to Ruby. Ruby
+    /// code can also rescue the interrupt, so this is not a guarantee that the
+    /// call will complete by `deadline`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// use magnus::{prelude::*, Error, ExceptionClass, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let kernel = ruby.module_kernel();
+    ///
+    ///     let deadline = Instant::now() + Duration::from_secs(5);
+    ///     let res: u64 = kernel.funcall_with_deadline("Integer", ("42",), deadline)?;
+    ///     assert_eq!(res, 42);
+    ///
+    ///     let deadline = Instant::now() + Duration::from_millis(50);
+    ///     let res: Result<u64, Error> = kernel.funcall_with_deadline("sleep", (10,), deadline);
+    ///     let err = res.unwrap_err();
+    ///     let timeout_error = ruby.eval::<ExceptionClass>("Timeout::Error")?;
+    ///     assert!(err.is_kind_of(timeout_error));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    fn funcall_with_deadline<M, A, T>(
+        self,
+        method: M,
+        args: A,
+        deadline: Instant,
+    ) -> Result<T, Error>
+    where
+        M: IntoId,
+        A: ArgList,
+        T: TryConvert,
+    {
+        let handle = Ruby::get_with(self);
+        handle.require("timeout")?;
+        let timeout = handle.class_object().const_get::<_, RModule>("Timeout")?;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.as_nanos() == 0 {
+            let class = timeout.const_get::<_, ExceptionClass>("Error")?;
+            return Err(Error::new(class, "execution expired"));
+        }
+        let id = method.into_id_with(&handle);
+        let mut args = Some(args);
+        block_call_closure(
+            timeout.as_value(),
+            "timeout",
+            (remaining.as_secs_f64(),),
+            |_, _, _| match args.take() {
+                Some(args) => self.funcall::<_, _, Value>(id, args),
+                None => Ok(handle.qnil().as_value()),
+            },
+        )
+    }
+
     /// Check if `self` responds to the given Ruby method.
     ///
     /// The `include_private` agument controls whether `self`'s private methods
@@ -1624,6 +2083,51 @@ pub trait ReprValue: private::ReprValue {
         unsafe { Value::new(rb_obj_is_kind_of(self.as_rb_value(), class.as_rb_value())).to_bool() }
     }
 
+    /// Returns whether or not `self` is a Ruby object wrapping the Rust type
+    /// `T`.
+    ///
+    /// This is a cheap check that does not call Ruby or allocate, so can be
+    /// used to test a value before attempting conversion to `&T` or
+    /// [`Obj<T>`](crate::typed_data::Obj).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// #[magnus::wrap(class = "Point")]
+    /// struct Point {
+    ///     x: isize,
+    ///     y: isize,
+    /// }
+    ///
+    /// #[magnus::wrap(class = "Line")]
+    /// struct Line;
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     ruby.define_class("Point", ruby.class_object())?;
+    ///     let value = ruby.obj_wrap(Point { x: 4, y: 2 });
+    ///     assert!(value.is_wrapped::<Point>());
+    ///     assert!(!value.is_wrapped::<Line>());
+    ///     assert!(!ruby.str_new("example").is_wrapped::<Point>());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap();
+    /// # let _ = Point { x: 1, y: 2 }.x + Point { x: 3, y: 4 }.y;
+    /// ```
+    fn is_wrapped<T>(self) -> bool
+    where
+        T: TypedData,
+    {
+        unsafe {
+            rb_typeddata_is_kind_of(
+                self.as_rb_value(),
+                T::data_type().as_rb_data_type() as *const _,
+            ) != 0
+        }
+    }
+
     /// Generate an [`Enumerator`] from `method` on `self`, passing `args` to
     /// `method`.
     ///
@@ -1809,6 +2313,197 @@ where
 
 unsafe impl<T> IntoValueFromNative for BoxValue<T> where T: ReprValue {}
 
+/// A handle to a Ruby global variable defined with
+/// [`Ruby::define_variable`].
+///
+/// The storage for the variable is owned by Ruby's global variable table.
+/// Ruby provides no way to remove a global variable, so the storage lives
+/// for as long as the Ruby VM, and this handle can be freely copied.
+#[derive(Clone, Copy)]
+pub struct GlobalVariable(ptr::NonNull<Value>);
+
+impl GlobalVariable {
+    #[inline]
+    pub(crate) unsafe fn from_ptr_unchecked(ptr: *mut Value) -> Self {
+        Self(ptr::NonNull::new_unchecked(ptr))
+    }
+
+    /// Get the current value of the global variable, converted to `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let var = ruby.define_variable("example_get", 1)?;
+    ///     let _: i64 = ruby.eval("$example_get += 1")?;
+    ///     assert_eq!(var.get::<i64>()?, 2);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn get<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        // safe as this type isn't Send/Sync, so can only be accessed from
+        // the Ruby thread it was created on, while holding the GVL
+        T::try_convert(unsafe { *self.0.as_ptr() })
+    }
+
+    /// Set the value of the global variable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{rb_assert, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let var = ruby.define_variable("example_set", 1)?;
+    ///     var.set("answer");
+    ///     rb_assert!(ruby, r#"$example_set == "answer""#);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn set<T>(self, val: T)
+    where
+        T: IntoValue,
+    {
+        // safe as this type isn't Send/Sync, so can only be accessed from
+        // the Ruby thread it was created on, while holding the GVL
+        unsafe {
+            let val = val.into_value_with(&Ruby::get_unchecked());
+            *self.0.as_ptr() = val;
+        }
+    }
+
+    /// Returns a raw pointer to the global variable's storage.
+    pub fn as_ptr(self) -> *mut Value {
+        self.0.as_ptr()
+    }
+}
+
+/// A chain of method calls, created with [`ReprValue::chain`].
+///
+/// Each step is only run if the previous one succeeded, the first error is
+/// returned from [`get`](Chain::get).
+pub struct Chain {
+    result: Result<Value, Error>,
+    nil_safe: bool,
+}
+
+impl Chain {
+    /// Make the remainder of the chain stop at `nil`, as with Ruby's safe
+    /// navigation operator `&.`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby, Value};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let config: Value = ruby.eval(r#"Struct.new(:db).new({})"#)?;
+    ///
+    ///     let host: Option<String> = config
+    ///         .chain()
+    ///         .nil_safe()
+    ///         .call("db")
+    ///         .index(ruby.to_symbol("hosts"))
+    ///         .index(1)
+    ///         .get()?;
+    ///     assert_eq!(host, None);
+    ///
+    ///     // without nil_safe this calls `[]` on nil
+    ///     let res: Result<Option<String>, Error> = config
+    ///         .chain()
+    ///         .call("db")
+    ///         .index(ruby.to_symbol("hosts"))
+    ///         .index(1)
+    ///         .get();
+    ///     assert!(res.is_err());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn nil_safe(mut self) -> Self {
+        self.nil_safe = true;
+        self
+    }
+
+    /// Call the method `method` with no arguments on the current value.
+    pub fn call<M>(self, method: M) -> Self
+    where
+        M: IntoId,
+    {
+        self.step(|val| val.funcall(method, ()))
+    }
+
+    /// Call the method `method` with `args` on the current value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{prelude::*, Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let s: String = ruby
+    ///         .str_new("hello world")
+    ///         .chain()
+    ///         .call_with("split", (" ",))
+    ///         .call("last")
+    ///         .call_with("center", (9, "*"))
+    ///         .get()?;
+    ///     assert_eq!(s, "**world**");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn call_with<M, A>(self, method: M, args: A) -> Self
+    where
+        M: IntoId,
+        A: ArgList,
+    {
+        self.step(|val| val.funcall(method, args))
+    }
+
+    /// Call `[]` with `key` on the current value.
+    pub fn index<K>(self, key: K) -> Self
+    where
+        K: IntoValue,
+    {
+        self.step(|val| val.funcall("[]", (key,)))
+    }
+
+    /// Finish the chain, converting the final value to `T`.
+    ///
+    /// Errors if any step of the chain raised an exception, or the
+    /// conversion fails.
+    pub fn get<T>(self) -> Result<T, Error>
+    where
+        T: TryConvert,
+    {
+        self.result.and_then(T::try_convert)
+    }
+
+    fn step<F>(mut self, func: F) -> Self
+    where
+        F: FnOnce(Value) -> Result<Value, Error>,
+    {
+        self.result = match self.result {
+            Ok(val) if self.nil_safe && val.is_nil() => Ok(val),
+            Ok(val) => func(val),
+            Err(e) => Err(e),
+        };
+        self
+    }
+}
+
 /// # `false`
 ///
 /// Get Ruby's `false` value.
@@ -1840,6 +2535,9 @@ impl Ruby {
 /// See [`Ruby::qfalse`]/[`qfalse`] to obtain a value of this type.
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
+///
+/// `Qfalse` implements [`Eq`]/[`PartialEq`] and [`Hash`], so can be used as a
+/// key in Rust collections such as [`HashMap`](std::collections::HashMap).
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Qfalse(Value);
@@ -1911,6 +2609,22 @@ impl fmt::Debug for Qfalse {
     }
 }
 
+impl PartialEq for Qfalse {
+    #[inline]
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for Qfalse {}
+
+impl Hash for Qfalse {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_rb_value().hash(state);
+    }
+}
+
 impl IntoValue for Qfalse {
     #[inline]
     fn into_value_with(self, _: &Ruby) -> Value {
@@ -1969,7 +2683,10 @@ impl Ruby {
 /// See [`Ruby::qnil`]/[`qnil`] to obtain a value of this type.
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
-#[derive(Clone, Copy)]
+///
+/// `Qnil` implements [`Eq`]/[`PartialEq`] and [`Hash`], so can be used as a
+/// key in Rust collections such as [`HashMap`](std::collections::HashMap).
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 #[repr(transparent)]
 pub struct Qnil(NonZeroValue);
 
@@ -2126,7 +2843,10 @@ impl Ruby {
 /// See [`Ruby::qtrue`]/[`qtrue`] to obtain a value of this type.
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
-#[derive(Clone, Copy)]
+///
+/// `Qtrue` implements [`Eq`]/[`PartialEq`] and [`Hash`], so can be used as a
+/// key in Rust collections such as [`HashMap`](std::collections::HashMap).
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 #[repr(transparent)]
 pub struct Qtrue(NonZeroValue);
 
@@ -2358,7 +3078,12 @@ impl Ruby {
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
 /// See [`Ruby`](Ruby#fixnum) for methods to create a `Fixnum`.
-#[derive(Clone, Copy)]
+///
+/// As a fixnum is stored directly in the `VALUE`, rather than on the heap,
+/// `Fixnum` implements [`Eq`]/[`PartialEq`] and [`Hash`], comparing by value,
+/// so can be used as a key in Rust collections such as
+/// [`HashMap`](std::collections::HashMap).
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 #[repr(transparent)]
 pub struct Fixnum(NonZeroValue);
 
@@ -2865,6 +3590,9 @@ impl Ruby {
 
     /// Return the `StaticSymbol` for `name`, if one exists.
     ///
+    /// This will not find dynamic (garbage collectable) symbols, see
+    /// [`Ruby::find_symbol`] to look up any existing symbol.
+    ///
     /// # Examples
     ///
     /// ```
@@ -2886,7 +3614,7 @@ impl Ruby {
                 name.len() as c_long,
                 self.utf8_encoding().as_ptr(),
             ));
-            (!res.is_nil()).then(|| StaticSymbol::from_rb_value_unchecked(res.as_rb_value()))
+            StaticSymbol::from_value(res)
         }
     }
 }
@@ -2898,6 +3626,10 @@ impl Ruby {
 ///
 /// See the [`ReprValue`] trait for additional methods available on this type.
 /// See [`Ruby`](Ruby#staticsymbol) for methods to create a `StaticSymbol`.
+///
+/// `StaticSymbol` implements [`Eq`]/[`PartialEq`] and [`Hash`], so can be used
+/// as a key in Rust collections such as
+/// [`HashMap`](std::collections::HashMap).
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 #[repr(transparent)]
 pub struct StaticSymbol(NonZeroValue);
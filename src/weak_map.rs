@@ -0,0 +1,409 @@
+use std::fmt;
+
+use crate::{
+    class::{Class, RClass},
+    error::Error,
+    into_value::IntoValue,
+    module::{Module, RModule},
+    object::Object,
+    try_convert::TryConvert,
+    value::{
+        private::{self, ReprValue as _},
+        Lazy, NonZeroValue, ReprValue, Value,
+    },
+    Ruby,
+};
+
+fn object_space_class(ruby: &Ruby, name: &str) -> RClass {
+    ruby.class_object()
+        .const_get::<_, RModule>("ObjectSpace")
+        .and_then(|object_space| object_space.const_get(name))
+        .unwrap()
+}
+
+static WEAK_MAP: Lazy<RClass> = Lazy::new(|ruby| object_space_class(ruby, "WeakMap"));
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+static WEAK_KEY_MAP: Lazy<RClass> = Lazy::new(|ruby| object_space_class(ruby, "WeakKeyMap"));
+
+/// # `WeakMap`
+///
+/// Functions that can be used to create Ruby `ObjectSpace::WeakMap`s and
+/// `ObjectSpace::WeakKeyMap`s.
+///
+/// See also the [`WeakMap`] and [`WeakKeyMap`] types.
+impl Ruby {
+    /// Create a new `ObjectSpace::WeakMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let map = ruby.weak_map_new()?;
+    ///     assert_eq!(map.len()?, 0);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn weak_map_new(&self) -> Result<WeakMap, Error> {
+        self.get_inner(&WEAK_MAP)
+            .new_instance(())
+            .and_then(TryConvert::try_convert)
+    }
+
+    /// Create a new `ObjectSpace::WeakKeyMap`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let map = ruby.weak_key_map_new()?;
+    ///     assert!(!map.contains_key("example")?);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[cfg(any(ruby_gte_3_3, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+    pub fn weak_key_map_new(&self) -> Result<WeakKeyMap, Error> {
+        self.get_inner(&WEAK_KEY_MAP)
+            .new_instance(())
+            .and_then(TryConvert::try_convert)
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's
+/// `ObjectSpace::WeakMap` class.
+///
+/// A `WeakMap` holds weak references to both its keys and values, entries are
+/// removed once either the key or value is garbage collected. Keys are
+/// compared by identity (`equal?`), rather than `eql?`.
+///
+/// This can be used to build caches keyed by Ruby objects, without preventing
+/// those objects being garbage collected.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#weakmap) for methods to create a
+/// `WeakMap`.
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct WeakMap(NonZeroValue);
+
+impl WeakMap {
+    /// Return `Some(WeakMap)` if `val` is an `ObjectSpace::WeakMap`, `None`
+    /// otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby, WeakMap};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     assert!(WeakMap::from_value(ruby.eval("ObjectSpace::WeakMap.new")?).is_some());
+    ///     assert!(WeakMap::from_value(ruby.eval("{}")?).is_none());
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        let class = Ruby::get_with(val).get_inner(&WEAK_MAP);
+        val.is_kind_of(class)
+            .then(|| Self(unsafe { NonZeroValue::new_unchecked(val) }))
+    }
+
+    /// Associate `val` with `key`, replacing any existing entry.
+    ///
+    /// Neither `key` nor `val` will be prevented from being garbage
+    /// collected by this map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let map = ruby.weak_map_new()?;
+    ///     let key = ruby.str_new("key");
+    ///     map.aset(key, "value")?;
+    ///     assert_eq!(map.aref::<_, Option<String>>(key)?.unwrap(), "value");
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn aset<K, V>(self, key: K, val: V) -> Result<(), Error>
+    where
+        K: IntoValue,
+        V: IntoValue,
+    {
+        let _: Value = self.funcall("[]=", (key, val))?;
+        Ok(())
+    }
+
+    /// Return the value for `key`, converting it to `U`.
+    ///
+    /// Missing entries will be returned as `nil`, so use an [`Option`] for
+    /// `U` to distinguish a missing entry.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let map = ruby.weak_map_new()?;
+    ///     let key = ruby.str_new("key");
+    ///     assert_eq!(map.aref::<_, Option<i64>>(key)?, None);
+    ///
+    ///     map.aset(key, 42)?;
+    ///     assert_eq!(map.aref::<_, Option<i64>>(key)?, Some(42));
+    ///
+    ///     // keys are compared by identity
+    ///     assert_eq!(map.aref::<_, Option<i64>>(ruby.str_new("key"))?, None);
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn aref<K, U>(self, key: K) -> Result<U, Error>
+    where
+        K: IntoValue,
+        U: TryConvert,
+    {
+        self.funcall("[]", (key,))
+    }
+
+    /// Returns whether `key` is in the map.
+    pub fn contains_key<K>(self, key: K) -> Result<bool, Error>
+    where
+        K: IntoValue,
+    {
+        self.funcall("key?", (key,))
+    }
+
+    /// Removes the entry for `key`, returning the value, converted to `U`.
+    ///
+    /// Returns `nil` converted to `U` if there was no entry for `key`.
+    #[cfg(any(ruby_gte_3_3, docsrs))]
+    #[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+    pub fn delete<K, U>(self, key: K) -> Result<U, Error>
+    where
+        K: IntoValue,
+        U: TryConvert,
+    {
+        self.funcall("delete", (key,))
+    }
+
+    /// Returns the number of entries in the map.
+    ///
+    /// This may include entries which have been garbage collected but not
+    /// yet removed.
+    pub fn len(self) -> Result<usize, Error> {
+        self.funcall("size", ())
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(self) -> Result<bool, Error> {
+        self.len().map(|len| len == 0)
+    }
+}
+
+impl fmt::Display for WeakMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+impl fmt::Debug for WeakMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+impl IntoValue for WeakMap {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.get()
+    }
+}
+
+impl Object for WeakMap {}
+
+unsafe impl private::ReprValue for WeakMap {}
+
+impl ReprValue for WeakMap {}
+
+impl TryConvert for WeakMap {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!(
+                    "no implicit conversion of {} into ObjectSpace::WeakMap",
+                    unsafe { val.classname() },
+                ),
+            )
+        })
+    }
+}
+
+/// Wrapper type for a Value known to be an instance of Ruby's
+/// `ObjectSpace::WeakKeyMap` class.
+///
+/// A `WeakKeyMap` holds weak references to its keys, and strong references to
+/// its values. Entries are removed once the key is garbage collected. Keys
+/// are compared with `eql?` and `hash`, like a `Hash`.
+///
+/// This can be used to associate data with Ruby objects, without preventing
+/// those objects being garbage collected.
+///
+/// See the [`ReprValue`] and [`Object`] traits for additional methods
+/// available on this type. See [`Ruby`](Ruby#weakmap) for methods to create a
+/// `WeakKeyMap`.
+#[cfg(any(ruby_gte_3_3, docsrs))]
+#[cfg_attr(docsrs, doc(cfg(ruby_gte_3_3)))]
+#[derive(Clone, Copy)]
+#[repr(transparent)]
+pub struct WeakKeyMap(NonZeroValue);
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl WeakKeyMap {
+    /// Return `Some(WeakKeyMap)` if `val` is an `ObjectSpace::WeakKeyMap`,
+    /// `None` otherwise.
+    #[inline]
+    pub fn from_value(val: Value) -> Option<Self> {
+        let class = Ruby::get_with(val).get_inner(&WEAK_KEY_MAP);
+        val.is_kind_of(class)
+            .then(|| Self(unsafe { NonZeroValue::new_unchecked(val) }))
+    }
+
+    /// Associate `val` with `key`, replacing any existing entry.
+    ///
+    /// `val` will be kept alive for as long as `key` is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use magnus::{Error, Ruby};
+    ///
+    /// fn example(ruby: &Ruby) -> Result<(), Error> {
+    ///     let map = ruby.weak_key_map_new()?;
+    ///     map.aset(ruby.str_new("key"), 42)?;
+    ///
+    ///     // keys are compared with `eql?`
+    ///     assert_eq!(map.aref::<_, Option<i64>>("key")?, Some(42));
+    ///
+    ///     Ok(())
+    /// }
+    /// # Ruby::init(example).unwrap()
+    /// ```
+    pub fn aset<K, V>(self, key: K, val: V) -> Result<(), Error>
+    where
+        K: IntoValue,
+        V: IntoValue,
+    {
+        let _: Value = self.funcall("[]=", (key, val))?;
+        Ok(())
+    }
+
+    /// Return the value for `key`, converting it to `U`.
+    ///
+    /// Missing entries will be returned as `nil`, so use an [`Option`] for
+    /// `U` to distinguish a missing entry.
+    pub fn aref<K, U>(self, key: K) -> Result<U, Error>
+    where
+        K: IntoValue,
+        U: TryConvert,
+    {
+        self.funcall("[]", (key,))
+    }
+
+    /// Returns the existing key equal to `key`, converted to `U`, or `nil`
+    /// converted to `U` if there is no such key.
+    pub fn get_key<K, U>(self, key: K) -> Result<U, Error>
+    where
+        K: IntoValue,
+        U: TryConvert,
+    {
+        self.funcall("getkey", (key,))
+    }
+
+    /// Returns whether `key` is in the map.
+    pub fn contains_key<K>(self, key: K) -> Result<bool, Error>
+    where
+        K: IntoValue,
+    {
+        self.funcall("key?", (key,))
+    }
+
+    /// Removes the entry for `key`, returning the value, converted to `U`.
+    ///
+    /// Returns `nil` converted to `U` if there was no entry for `key`.
+    pub fn delete<K, U>(self, key: K) -> Result<U, Error>
+    where
+        K: IntoValue,
+        U: TryConvert,
+    {
+        self.funcall("delete", (key,))
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(self) -> Result<(), Error> {
+        let _: Value = self.funcall("clear", ())?;
+        Ok(())
+    }
+}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl fmt::Display for WeakKeyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", unsafe { self.to_s_infallible() })
+    }
+}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl fmt::Debug for WeakKeyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inspect())
+    }
+}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl IntoValue for WeakKeyMap {
+    #[inline]
+    fn into_value_with(self, _: &Ruby) -> Value {
+        self.0.get()
+    }
+}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl Object for WeakKeyMap {}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+unsafe impl private::ReprValue for WeakKeyMap {}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl ReprValue for WeakKeyMap {}
+
+#[cfg(any(ruby_gte_3_3, docsrs))]
+impl TryConvert for WeakKeyMap {
+    fn try_convert(val: Value) -> Result<Self, Error> {
+        Self::from_value(val).ok_or_else(|| {
+            Error::new(
+                Ruby::get_with(val).exception_type_error(),
+                format!(
+                    "no implicit conversion of {} into ObjectSpace::WeakKeyMap",
+                    unsafe { val.classname() },
+                ),
+            )
+        })
+    }
+}
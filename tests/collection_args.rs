@@ -0,0 +1,39 @@
+use std::collections::{BTreeMap, HashMap};
+
+use magnus::{function, rb_assert};
+
+fn sum(values: Vec<i64>) -> i64 {
+    values.iter().sum()
+}
+
+fn total(scores: HashMap<String, i64>) -> i64 {
+    scores.values().sum()
+}
+
+fn keys(scores: BTreeMap<String, i64>) -> Vec<String> {
+    scores.into_keys().collect()
+}
+
+fn swap(pair: (String, i64)) -> (i64, String) {
+    (pair.1, pair.0)
+}
+
+#[test]
+fn it_converts_collection_arguments() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("sum", function!(sum, 1));
+    ruby.define_global_function("total", function!(total, 1));
+    ruby.define_global_function("keys", function!(keys, 1));
+    ruby.define_global_function("swap", function!(swap, 1));
+
+    rb_assert!(ruby, "sum([1, 2, 3]) == 6");
+    rb_assert!(ruby, "(sum([1, :a]) rescue $!).is_a?(TypeError)");
+
+    rb_assert!(ruby, r#"total({"a" => 1, "b" => 2}) == 3"#);
+    rb_assert!(ruby, r#"keys({"b" => 1, "a" => 2}) == ["a", "b"]"#);
+    rb_assert!(ruby, "(keys({1 => 2}) rescue $!).is_a?(TypeError)");
+
+    rb_assert!(ruby, r#"swap(["a", 1]) == [1, "a"]"#);
+    rb_assert!(ruby, r#"(swap(["a"]) rescue $!).is_a?(TypeError)"#);
+}
@@ -0,0 +1,51 @@
+use magnus::{
+    block::Proc,
+    method,
+    prelude::*,
+    rb_assert,
+    scan_args::{get_kwargs, scan_args},
+    Error, RArray, Ruby, Value,
+};
+
+fn collect(ruby: &Ruby, rb_self: Value, args: &[Value]) -> Result<RArray, Error> {
+    let args = scan_args::<(i64,), (), RArray, (), _, Option<Proc>>(args)?;
+    let (scale,) = args.required;
+    let kw = get_kwargs::<_, (), (Option<bool>,), ()>(args.keywords, &[], &["negate"])?;
+    let (negate,) = kw.optional;
+    let result = ruby.ary_new();
+    for i in args.splat.to_vec::<i64>()? {
+        let mut i = i * scale;
+        if negate.unwrap_or(false) {
+            i = -i;
+        }
+        match args.block {
+            Some(block) => result.push(block.call::<_, Value>((rb_self, i))?)?,
+            None => result.push(i)?,
+        }
+    }
+    Ok(result)
+}
+
+#[test]
+fn it_defines_methods_with_splat_kwargs_and_block() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class = ruby.define_class("Collector", ruby.class_object()).unwrap();
+    class
+        .define_method("collect", method!(collect, -1))
+        .unwrap();
+
+    rb_assert!(ruby, "Collector.new.collect(2, 1, 2, 3) == [2, 4, 6]");
+    rb_assert!(
+        ruby,
+        "Collector.new.collect(2, 1, 2, negate: true) == [-2, -4]"
+    );
+    rb_assert!(
+        ruby,
+        "c = Collector.new; c.collect(1, 1, 2) { |s, i| [s.equal?(c), i] } == [[true, 1], [true, 2]]"
+    );
+    rb_assert!(
+        ruby,
+        "begin; Collector.new.collect(1, foo: 1); false; rescue ArgumentError; true; end"
+    );
+}
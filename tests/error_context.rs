@@ -0,0 +1,40 @@
+use magnus::{error::ErrorType, prelude::*, Exception, RHash, Symbol};
+
+#[test]
+fn it_keeps_the_original_exception_when_adding_context() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let hash: RHash = ruby
+        .eval(
+            r#"
+            class BadStr
+              def to_str
+                begin
+                  raise "inner"
+                rescue
+                  raise KeyError.new("bad", receiver: self, key: :name)
+                end
+              end
+            end
+            {name: BadStr.new}
+            "#,
+        )
+        .unwrap();
+
+    let err = hash.fetch_field::<String>("name").unwrap_err();
+    let e = match err.error_type() {
+        ErrorType::Exception(e) => *e,
+        _ => panic!("expected an exception"),
+    };
+    assert!(e.is_kind_of(ruby.exception_key_error()));
+    let message: String = e.funcall("message", ()).unwrap();
+    assert_eq!(message, "field `name`: bad");
+
+    let key: Symbol = e.funcall("key", ()).unwrap();
+    assert!(key.eql_str("name"));
+    let cause: Exception = e.funcall("cause", ()).unwrap();
+    let message: String = cause.funcall("message", ()).unwrap();
+    assert_eq!(message, "inner");
+    let backtrace: Option<Vec<String>> = e.funcall("backtrace", ()).unwrap();
+    assert!(backtrace.is_some());
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use magnus::{value::Qnil, StaticSymbol};
+
+#[test]
+fn it_uses_immediates_as_hash_keys() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let mut map = HashMap::new();
+    map.insert(ruby.fixnum_from_i64(1).unwrap(), "one");
+    map.insert(ruby.fixnum_from_i64(2).unwrap(), "two");
+    assert_eq!(map.get(&ruby.fixnum_from_i64(1).unwrap()), Some(&"one"));
+    assert_eq!(map.get(&ruby.fixnum_from_i64(3).unwrap()), None);
+
+    let mut map = HashMap::<StaticSymbol, i64>::new();
+    map.insert(ruby.sym_new("foo"), 1);
+    assert_eq!(map.get(&ruby.sym_new("foo")), Some(&1));
+
+    let mut map = HashMap::<Qnil, i64>::new();
+    map.insert(ruby.qnil(), 1);
+    assert_eq!(map.get(&ruby.qnil()), Some(&1));
+
+    assert!(ruby.qtrue() == ruby.qtrue());
+    assert!(ruby.qfalse() == ruby.qfalse());
+}
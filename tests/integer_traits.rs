@@ -13,6 +13,8 @@ fn test_all() {
         test_mul(ruby)?;
         test_div(ruby)?;
         test_ord(ruby)?;
+        test_bit_ops(ruby)?;
+        test_shift(ruby)?;
         Ok(())
     })
     .unwrap();
@@ -221,3 +223,62 @@ fn test_ord(ruby: &Ruby) -> Result<(), Error> {
 
     Ok(())
 }
+
+fn test_bit_ops(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) & ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b1000)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) | ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b1110)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(0b1100) ^ ruby.integer_from_i64(0b1010),
+        ruby.integer_from_i64(0b0110)
+    );
+    assert_eq!(
+        ruby.integer_from_i64(-1) & ruby.integer_from_i64(5),
+        ruby.integer_from_i64(5)
+    );
+
+    let a: Integer = ruby.eval("2**100 + 5")?;
+    let b = ruby.integer_from_i64(3);
+    assert_eq!(a & b, ruby.integer_from_i64(1));
+    assert_eq!(b & a, ruby.integer_from_i64(1));
+    let a_b: Integer = ruby.eval("2**100 + 7")?;
+    assert_eq!(a | b, a_b);
+    let a_b: Integer = ruby.eval("2**100 + 6")?;
+    assert_eq!(a ^ b, a_b);
+
+    let mut a = ruby.integer_from_i64(7);
+    a &= ruby.integer_from_i64(2);
+    assert_eq!(a, ruby.integer_from_i64(2));
+
+    Ok(())
+}
+
+fn test_shift(ruby: &Ruby) -> Result<(), Error> {
+    assert_eq!(ruby.integer_from_i64(1) << 4, ruby.integer_from_i64(16));
+    assert_eq!(ruby.integer_from_i64(-3) << 1, ruby.integer_from_i64(-6));
+    assert_eq!(ruby.integer_from_i64(16) >> 4, ruby.integer_from_i64(1));
+    assert_eq!(ruby.integer_from_i64(-5) >> 1, ruby.integer_from_i64(-3));
+    assert_eq!(ruby.integer_from_i64(-5) >> 100, ruby.integer_from_i64(-1));
+
+    let a: Integer = ruby.eval("2**100")?;
+    assert_eq!(ruby.integer_from_i64(1) << 100, a);
+    assert_eq!(
+        ruby.integer_from_u64(RUBY_FIXNUM_MAX) << 2,
+        ruby.eval::<Integer>(&format!("{} << 2", RUBY_FIXNUM_MAX))?
+    );
+    assert_eq!(a >> 99, ruby.integer_from_i64(2));
+    assert_eq!(a << 1, ruby.eval::<Integer>("2**101")?);
+
+    let mut a = ruby.integer_from_i64(1);
+    a <<= 3;
+    assert_eq!(a, ruby.integer_from_i64(8));
+    a >>= 2;
+    assert_eq!(a, ruby.integer_from_i64(2));
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "msgpack")]
+
+use magnus::{msgpack, rb_assert, Value};
+
+#[test]
+fn it_round_trips_msgpack() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let val: Value = ruby
+        .eval(
+            r#"
+            {
+              "ints" => [0, 127, 128, 255, 256, 65536, 2**32, 2**64 - 1, -1, -32, -33, -129, -2**63],
+              "floats" => [1.5, -0.25],
+              "str" => "héllo" * 10,
+              "bin" => "\xFF\x00".b,
+              :sym => nil,
+              "nested" => {"a" => [true, false, []]},
+            }
+            "#,
+        )
+        .unwrap();
+
+    let bytes = msgpack::encode(val).unwrap();
+    let res = msgpack::decode(&ruby, &bytes).unwrap();
+
+    rb_assert!(ruby, r#"res == val.transform_keys(&:to_s)"#, res, val);
+    rb_assert!(ruby, r#"res["bin"].encoding == Encoding::BINARY"#, res);
+    rb_assert!(ruby, r#"res["str"].encoding == Encoding::UTF_8"#, res);
+
+    let val: Value = ruby.eval("2**64").unwrap();
+    assert!(msgpack::encode(val).is_err());
+
+    let val: Value = ruby.eval("a = []; a << a; a").unwrap();
+    assert!(msgpack::encode(val).is_err());
+
+    assert!(msgpack::decode(&ruby, &[0xc1]).is_err());
+    assert!(msgpack::decode(&ruby, &[0xd4, 0x01, 0x00]).is_err());
+    assert!(msgpack::decode(&ruby, &[0xc0, 0xc0]).is_err());
+}
@@ -0,0 +1,65 @@
+use magnus::{function, prelude::*, rb_assert, Error, RArray, RHash, Ruby, Value};
+
+fn scale(ruby: &Ruby, n: i64, by: Option<i64>, kw: RHash) -> Result<i64, Error> {
+    let offset: Option<i64> = kw.lookup(ruby.to_symbol("offset"))?;
+    Ok(n * by.unwrap_or(2) + offset.unwrap_or(0))
+}
+
+fn map_twice(ruby: &Ruby, values: RArray) -> Result<RArray, Error> {
+    let block = ruby.block_proc()?;
+    let result = ruby.ary_new();
+    for value in values.each() {
+        let once: Value = block.call((value?,))?;
+        result.push(block.call::<_, Value>((once,))?)?;
+    }
+    Ok(result)
+}
+
+#[test]
+fn it_defines_module_functions_on_nested_modules() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let outer = ruby.define_module("Outer").unwrap();
+    let inner = outer.define_module("Inner").unwrap();
+    inner
+        .define_module_function("scale", function!(scale, 1..=2, kw))
+        .unwrap();
+    inner
+        .define_module_function("map_twice", function!(map_twice, 1))
+        .unwrap();
+
+    rb_assert!(ruby, "Outer::Inner.scale(3) == 6");
+    rb_assert!(ruby, "Outer::Inner.scale(3, 3, offset: 1) == 10");
+    rb_assert!(
+        ruby,
+        "Outer::Inner.map_twice([1, 2]) { |i| i * 10 } == [100, 200]"
+    );
+    rb_assert!(
+        ruby,
+        "(Outer::Inner.map_twice([1]) rescue $!).is_a?(ArgumentError)"
+    );
+
+    rb_assert!(
+        ruby,
+        r#"
+            klass = Class.new do
+              include Outer::Inner
+              def call
+                scale(2, offset: 1)
+              end
+            end
+            klass.new.call == 5
+        "#
+    );
+    rb_assert!(
+        ruby,
+        "(Object.new.extend(Outer::Inner).scale(1) rescue $!).is_a?(NoMethodError)"
+    );
+
+    let singleton = inner.singleton_class().unwrap();
+    rb_assert!(
+        ruby,
+        "singleton.public_method_defined?(:scale) && singleton.public_method_defined?(:map_twice)",
+        singleton
+    );
+}
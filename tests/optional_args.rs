@@ -0,0 +1,82 @@
+use magnus::{
+    function, method,
+    r_hash::FromRHash,
+    rb_assert,
+    scan_args::{get_kwargs, KeywordArgs},
+    Error, RHash, Value,
+};
+
+struct Options {
+    times: usize,
+    sep: Option<String>,
+}
+
+impl FromRHash for Options {
+    fn from_r_hash(hash: RHash) -> Result<Self, Error> {
+        let args = get_kwargs::<_, (usize,), (Option<String>,), ()>(hash, &["times"], &["sep"])?;
+        Ok(Self {
+            times: args.required.0,
+            sep: args.optional.0,
+        })
+    }
+}
+
+fn repeat(s: String, times: Option<usize>) -> String {
+    s.repeat(times.unwrap_or(2))
+}
+
+fn join(_rb_self: Value, a: String, b: Option<String>, kw: KeywordArgs<Options>) -> String {
+    let KeywordArgs(Options { times, sep }) = kw;
+    let joined = match b {
+        Some(b) => format!("{}{}{}", a, sep.unwrap_or_default(), b),
+        None => a,
+    };
+    joined.repeat(times)
+}
+
+fn count_keywords(kw: RHash) -> usize {
+    kw.len()
+}
+
+#[test]
+fn it_converts_optional_and_keyword_args() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("repeat", function!(repeat, 1..=2));
+    ruby.define_global_function("join", method!(join, 1..=2, kw));
+    ruby.define_global_function("count_keywords", function!(count_keywords, 0..=0, kw));
+
+    rb_assert!(ruby, r#"repeat("ab") == "abab""#);
+    rb_assert!(ruby, r#"repeat("ab", 3) == "ababab""#);
+    rb_assert!(
+        ruby,
+        r#"begin; repeat; false; rescue ArgumentError => e; e.message == "wrong number of arguments (given 0, expected 1..2)"; end"#
+    );
+    rb_assert!(
+        ruby,
+        r#"begin; repeat("ab", 1, 2); false; rescue ArgumentError => e; e.message == "wrong number of arguments (given 3, expected 1..2)"; end"#
+    );
+
+    rb_assert!(ruby, r#"join("a", times: 2) == "aa""#);
+    rb_assert!(ruby, r#"join("a", "b", times: 1) == "ab""#);
+    rb_assert!(ruby, r#"join("a", "b", sep: "-", times: 2) == "a-ba-b""#);
+    rb_assert!(
+        ruby,
+        r#"begin; join("a", "b", "c", times: 1); false; rescue ArgumentError => e; e.message == "wrong number of arguments (given 3, expected 1..2)"; end"#
+    );
+    rb_assert!(
+        ruby,
+        r#"begin; join("a"); false; rescue ArgumentError => e; e.message.include?("missing keyword"); end"#
+    );
+    rb_assert!(
+        ruby,
+        r#"begin; join("a", times: 1, size: 3); false; rescue ArgumentError => e; e.message.include?("unknown keyword"); end"#
+    );
+
+    rb_assert!(ruby, "count_keywords == 0");
+    rb_assert!(ruby, "count_keywords(a: 1, b: 2) == 2");
+    rb_assert!(
+        ruby,
+        "begin; count_keywords(1); false; rescue ArgumentError => e; e.message == \"wrong number of arguments (given 1, expected 0)\"; end"
+    );
+}
@@ -0,0 +1,36 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use magnus::{prelude::*, rb_assert, RClass, Value};
+
+#[test]
+fn it_wraps_recursive_methods() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    let class: RClass = ruby
+        .eval(
+            r#"
+              class Factorial
+                def calc(n)
+                  n <= 1 ? 1 : n * calc(n - 1)
+                end
+                self
+              end
+            "#,
+        )
+        .unwrap();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    class
+        .prepend_wrapper("calc", move |original, _rb_self, args| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            original.call::<_, Value>(args)
+        })
+        .unwrap();
+
+    rb_assert!(ruby, "Factorial.new.calc(5) == 120");
+    assert_eq!(calls.load(Ordering::Relaxed), 5);
+}
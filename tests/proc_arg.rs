@@ -0,0 +1,28 @@
+use magnus::{block::Proc, function, rb_assert, Error, RArray, Ruby};
+
+fn describe(ruby: &Ruby, proc: Proc) -> Result<RArray, Error> {
+    let result = ruby.ary_new();
+    result.push(proc.arity())?;
+    result.push(proc.is_lambda())?;
+    Ok(result)
+}
+
+fn apply(proc: Proc, value: i64) -> Result<i64, Error> {
+    proc.call((value,))
+}
+
+#[test]
+fn it_converts_proc_arguments() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("describe", function!(describe, 1));
+    ruby.define_global_function("apply", function!(apply, 2));
+
+    rb_assert!(ruby, "describe(proc { |a, b| }) == [2, false]");
+    rb_assert!(ruby, "describe(lambda { |a| }) == [1, true]");
+
+    rb_assert!(ruby, "apply(proc { |i| i * 2 }, 21) == 42");
+    rb_assert!(ruby, "apply(:succ, 41) == 42");
+    rb_assert!(ruby, "apply(1.method(:+), 41) == 42");
+    rb_assert!(ruby, "(apply(1, 41) rescue $!).is_a?(TypeError)");
+}
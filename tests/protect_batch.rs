@@ -0,0 +1,49 @@
+use std::{cell::Cell, rc::Rc};
+
+use magnus::{
+    prelude::*,
+    rb_sys::{protect_batch, AsRawValue},
+    Value,
+};
+
+struct Item {
+    val: Value,
+    drops: Rc<Cell<usize>>,
+}
+
+impl Drop for Item {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[test]
+fn it_stops_and_drops_remaining_items_when_ruby_raises() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    // kept on the stack so it's seen by the GC
+    let five = ruby.str_new("five");
+    let drops = Rc::new(Cell::new(0));
+    let items = (0..10)
+        .map(|i| Item {
+            val: if i == 5 {
+                five.as_value()
+            } else {
+                ruby.integer_from_i64(i).as_value()
+            },
+            drops: drops.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut calls = 0;
+    let res = protect_batch(items, |item| {
+        calls += 1;
+        unsafe { rb_sys::rb_num2long(item.val.as_raw()) }
+    });
+
+    let err = res.unwrap_err();
+    assert!(err.is_kind_of(ruby.exception_type_error()));
+    assert_eq!(calls, 6);
+    // all but the item passed to the call that raised
+    assert_eq!(drops.get(), 9);
+}
@@ -0,0 +1,47 @@
+use magnus::{function, prelude::*, rb_assert, Error, Ruby};
+
+fn find_even(n: i64) -> Option<i64> {
+    if n % 2 == 0 {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+fn checked_div(ruby: &Ruby, a: i64, b: i64) -> Result<Option<i64>, Error> {
+    if a < 0 {
+        return Err(Error::new(ruby.exception_arg_error(), "negative"));
+    }
+    Ok(a.checked_div(b))
+}
+
+fn check(ruby: &Ruby, ok: bool) -> Result<(), Error> {
+    if ok {
+        Ok(())
+    } else {
+        Err(Error::new(ruby.exception_runtime_error(), "not ok"))
+    }
+}
+
+#[test]
+fn it_returns_nil_for_none_and_unit() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("find_even", function!(find_even, 1));
+    ruby.define_global_function("checked_div", function!(checked_div, 2));
+    ruby.define_global_function("check", function!(check, 1));
+
+    rb_assert!(ruby, "find_even(2) == 2");
+    rb_assert!(ruby, "find_even(3).nil?");
+    rb_assert!(ruby, "checked_div(6, 3) == 2");
+    rb_assert!(ruby, "checked_div(6, 0).nil?");
+    rb_assert!(
+        ruby,
+        r#"begin; checked_div(-1, 1); false; rescue ArgumentError => e; e.message == "negative"; end"#
+    );
+    rb_assert!(ruby, "check(true).nil?");
+    rb_assert!(
+        ruby,
+        r#"begin; check(false); false; rescue RuntimeError => e; e.message == "not ok"; end"#
+    );
+}
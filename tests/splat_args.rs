@@ -0,0 +1,35 @@
+use magnus::{function, method, prelude::*, rb_assert, Error, RArray, Ruby, Value};
+
+fn collect(ruby: &Ruby, rb_self: Value, a: i64, b: String, rest: &[Value]) -> RArray {
+    let ary = ruby.ary_new();
+    ary.push(rb_self).unwrap();
+    ary.push(a).unwrap();
+    ary.push(b).unwrap();
+    ary.push(ruby.ary_new_from_values(rest)).unwrap();
+    ary
+}
+
+fn first_or_rest(first: Option<i64>, rest: &[Value]) -> Result<usize, Error> {
+    Ok(first.unwrap_or(0) as usize + rest.len())
+}
+
+#[test]
+fn it_converts_leading_args_and_passes_rest() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("collect", method!(collect, 2, ..));
+    ruby.define_global_function("first_or_rest", function!(first_or_rest, 1, ..));
+
+    rb_assert!(ruby, r#"collect(1, "a") == [self, 1, "a", []]"#);
+    rb_assert!(
+        ruby,
+        r#"collect(1, "a", :b, :c) == [self, 1, "a", [:b, :c]]"#
+    );
+    rb_assert!(
+        ruby,
+        r#"begin; collect(1); false; rescue ArgumentError => e; e.message == "wrong number of arguments (given 1, expected 2+)"; end"#
+    );
+
+    rb_assert!(ruby, "first_or_rest(nil) == 0");
+    rb_assert!(ruby, "first_or_rest(2, 3, 4) == 4");
+}
@@ -0,0 +1,37 @@
+#![cfg(all(feature = "url", feature = "uuid"))]
+
+use magnus::{rb_assert, Error, Ruby};
+
+#[test]
+fn test_all() {
+    Ruby::init(|ruby| {
+        test_supports_uuid(ruby)?;
+        test_supports_url(ruby)?;
+        Ok(())
+    })
+    .unwrap();
+}
+
+fn test_supports_uuid(ruby: &Ruby) -> Result<(), Error> {
+    let id = ruby.eval::<uuid::Uuid>(r#""67E55044-10B1-426F-9247-BB680E5FE0C8""#)?;
+    assert_eq!(id.as_u128(), 0x67e5504410b1426f9247bb680e5fe0c8);
+    rb_assert!(ruby, r#"id == "67e55044-10b1-426f-9247-bb680e5fe0c8""#, id);
+
+    assert!(ruby.eval::<uuid::Uuid>(r#""not a uuid""#).is_err());
+
+    Ok(())
+}
+
+fn test_supports_url(ruby: &Ruby) -> Result<(), Error> {
+    let url = ruby.eval::<url::Url>(r#""https://example.com/foo?bar=baz""#)?;
+    assert_eq!(url.host_str(), Some("example.com"));
+    rb_assert!(ruby, r#"url == "https://example.com/foo?bar=baz""#, url);
+
+    ruby.require("uri")?;
+    let url = ruby.eval::<url::Url>(r#"URI("https://example.com/foo")"#)?;
+    assert_eq!(url.path(), "/foo");
+
+    assert!(ruby.eval::<url::Url>(r#""not a url""#).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+
+use magnus::{
+    gc, prelude::*, testing::verify_compaction, typed_data::Obj, value::Opaque, DataTypeFunctions,
+    RString, Ruby, TypedData, Value,
+};
+
+// Marks its strings as movable, but doesn't update them when they are moved.
+#[derive(TypedData)]
+#[magnus(class = "Broken", mark)]
+struct Broken {
+    texts: RefCell<Vec<Opaque<RString>>>,
+}
+
+impl DataTypeFunctions for Broken {
+    fn mark(&self, marker: &gc::Marker) {
+        for text in self.texts.borrow().iter() {
+            marker.mark_movable(*text);
+        }
+    }
+}
+
+// Not inlined so the strings aren't left on the test's stack, which would pin
+// them during compaction.
+#[inline(never)]
+fn make_broken(ruby: &Ruby) -> Obj<Broken> {
+    let texts = (0..1000)
+        .map(|i| Opaque::from(ruby.str_new(&i.to_string())))
+        .collect();
+    ruby.obj_wrap(Broken {
+        texts: RefCell::new(texts),
+    })
+}
+
+#[test]
+fn it_detects_stale_references() {
+    let ruby = unsafe { magnus::embed::init() };
+    if !ruby.eval::<bool>("GC.respond_to?(:compact)").unwrap() {
+        return;
+    }
+
+    ruby.define_class("Broken", ruby.class_object()).unwrap();
+    // garbage allocated before the strings leaves free slots for compaction
+    // to move them in to
+    let _: Value = ruby.eval("100_000.times.map { Object.new }; nil").unwrap();
+    let broken = make_broken(&ruby);
+
+    let res = verify_compaction(&ruby, broken, |broken| {
+        broken
+            .texts
+            .borrow()
+            .iter()
+            .map(|text| ("texts", ruby.get_inner(*text).as_value()))
+            .collect()
+    });
+    // remove the stale references before Ruby next marks them
+    broken.texts.borrow_mut().clear();
+
+    let err = res.unwrap_err();
+    assert!(err.to_string().contains("texts"), "{}", err);
+}
@@ -0,0 +1,61 @@
+use magnus::{
+    block::{YieldControl, YieldWith},
+    method,
+    prelude::*,
+    rb_assert, Error, Ruby, Value,
+};
+
+fn take_while_truthy(
+    ruby: &Ruby,
+    rb_self: Value,
+) -> YieldWith<impl Iterator<Item = u8>, impl FnMut(Value) -> Result<YieldControl<u8>, Error>> {
+    if ruby.block_given() {
+        let mut count = 0;
+        YieldWith::Iter(1..=5, move |res: Value| {
+            if res.to_bool() {
+                count += 1;
+                Ok(YieldControl::Continue)
+            } else {
+                Ok(YieldControl::Stop(count))
+            }
+        })
+    } else {
+        YieldWith::Enumerator(rb_self.enumeratorize("take_while_truthy", ()))
+    }
+}
+
+fn fail_on_nil(
+    _ruby: &Ruby,
+    _rb_self: Value,
+) -> YieldWith<impl Iterator<Item = u8>, impl FnMut(Value) -> Result<YieldControl<()>, Error>> {
+    YieldWith::Iter(1..=5, |res: Value| {
+        if res.is_nil() {
+            let ruby = Ruby::get_with(res);
+            Err(Error::new(ruby.exception_runtime_error(), "got nil"))
+        } else {
+            Ok(YieldControl::Continue)
+        }
+    })
+}
+
+#[test]
+fn it_stops_yielding_on_block_result() {
+    let ruby = unsafe { magnus::embed::init() };
+
+    ruby.define_global_function("take_while_truthy", method!(take_while_truthy, 0));
+    ruby.define_global_function("fail_on_nil", method!(fail_on_nil, 0));
+
+    let a = ruby.ary_new();
+    rb_assert!(ruby, "take_while_truthy {|i| a << i; i < 3} == 2", a);
+    rb_assert!(ruby, "a == [1, 2, 3]", a);
+    rb_assert!(ruby, "take_while_truthy {|i| true}.nil?");
+    rb_assert!(
+        ruby,
+        "take_while_truthy {|i| break i * 10 if i == 4; true} == 40"
+    );
+    rb_assert!(ruby, "take_while_truthy.next == 1");
+    rb_assert!(
+        ruby,
+        r#"begin; fail_on_nil {|i| i unless i == 2}; false; rescue RuntimeError => e; e.message == "got nil"; end"#
+    );
+}